@@ -0,0 +1,10 @@
+let adder = fn(a) {
+  fn(b) { a + b }
+};
+
+let add_two = adder(2);
+let compose = fn(f, g) {
+  fn(x) { g(f(x)) }
+};
+
+compose(add_two, adder(10))(1);
@@ -0,0 +1,16 @@
+let map = fn(array, f) {
+  let iterate = fn(remaining, accumulated) {
+    if (len(remaining) == 0) {
+      return accumulated;
+    }
+
+    iterate(rest(remaining), push(accumulated, f(first(remaining))))
+  };
+
+  iterate(array, [])
+};
+
+let people = [{"name": "Alice", "age": 30}, {"name": "Bob", "age": 25}];
+let names = map(people, fn(person) { person["name"] });
+
+[names, last(people)["age"], type(people[0]), people[5]];
@@ -0,0 +1,29 @@
+// `return`, `break` and `continue` can also stop the expression they appear in
+let first_negative = fn(numbers) {
+  let found = [];
+
+  for (n in numbers) {
+    found = push(found, if (n < 0) { return n } else { n });
+  }
+
+  found
+};
+
+let evens = [];
+for (i in 0..10) {
+  evens = push(evens, if (i - (i / 2) * 2 == 1) { continue } else { i });
+}
+
+let total = 0;
+while (true) {
+  total = total + if (total > 20) { break } else { 7 };
+}
+
+let pairs = [];
+let i = 0;
+while (i < 5) {
+  i = i + 1;
+  pairs = push(pairs, [i, if (i == 4) { break } else { i * i }]);
+}
+
+[first_negative([3, 1, -4, 1, -5]), first_negative([1, 2]), evens, total, pairs];
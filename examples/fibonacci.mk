@@ -0,0 +1,9 @@
+let fibonacci = fn(n) {
+  if (n < 2) {
+    return n;
+  }
+
+  fibonacci(n - 1) + fibonacci(n - 2)
+};
+
+fibonacci(15);
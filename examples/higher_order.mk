@@ -0,0 +1,19 @@
+let people = [
+  {"name": "Alice", "age": 30},
+  {"name": "Bob", "age": 25},
+  {"name": "Carol", "age": 35}
+];
+
+let by_age = sort(people, fn(a, b) { a["age"] - b["age"] });
+let names = map(by_age, fn(person) { person["name"] });
+let total_age = reduce(people, 0, fn(total, person) { total + person["age"] });
+let older = filter(people, fn(person) { person["age"] > 28 });
+
+[
+  names,
+  total_age,
+  len(older),
+  all(people, fn(person) { person["age"] > 18 }),
+  max(map(people, fn(person) { person["age"] })),
+  enumerate(zip(names, [1, 2]))
+];
@@ -0,0 +1,24 @@
+let inventory = {"apples": 3, "pears": 0, "plums": 7};
+let in_stock = [];
+
+for (fruit in inventory) {
+  if (inventory[fruit] == 0) {
+    continue;
+  }
+
+  in_stock = push(in_stock, fruit);
+}
+
+let letters = 0;
+for (name in in_stock) {
+  for (letter in name) {
+    letters = letters + 1;
+  }
+}
+
+let squares = [];
+for (i in 1..6) {
+  squares = push(squares, i * i);
+}
+
+[in_stock, letters, squares];
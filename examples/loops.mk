@@ -0,0 +1,29 @@
+// Collects the first ten primes by trial division
+let primes = [];
+let candidate = 2;
+
+while (len(primes) < 10) {
+  let divisor = 2;
+  let is_prime = true;
+
+  /* Only divisors up to the square root need to be tried,
+     a larger one would pair with a smaller one /* already tried */ */
+  while (divisor * divisor < candidate + 1) {
+    if (candidate - (candidate / divisor) * divisor == 0) {
+      is_prime = false; // found a divisor
+      break;
+    }
+
+    divisor = divisor + 1;
+  }
+
+  candidate = candidate + 1;
+
+  if (!is_prime) {
+    continue;
+  }
+
+  primes = push(primes, candidate - 1);
+}
+
+primes;
@@ -0,0 +1,16 @@
+let unless = macro(condition, consequence, alternative) {
+  quote(if (!(unquote(condition))) {
+    unquote(consequence);
+  } else {
+    unquote(alternative);
+  });
+};
+
+let swap = macro(a, b) {
+  quote(unquote(b) - unquote(a));
+};
+
+let x = 10;
+let checked = unless(x > 5, "small", "big");
+
+[checked, swap(1, x), quote(x + unquote(x * 2))];
@@ -0,0 +1,27 @@
+// Recursion thousands of calls deep, and recursion deeper than the interpreter allows
+let count_down = fn(n) {
+  if (n == 0) {
+    0
+  } else {
+    1 + count_down(n - 1)
+  }
+};
+
+let depth = count_down(9000);
+
+let sum_to = fn(n, total) {
+  if (n == 0) {
+    total
+  } else {
+    sum_to(n - 1, total + n)
+  }
+};
+
+let sum = sum_to(5000, 0);
+
+let forever = fn(n) {
+  forever(n + 1)
+};
+
+puts(depth, sum);
+forever(0);
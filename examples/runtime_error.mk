@@ -0,0 +1,5 @@
+let divide_all = fn(items) {
+  first(items) / missing
+};
+
+divide_all([10, 5]);
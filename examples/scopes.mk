@@ -0,0 +1,13 @@
+let value = "global";
+let read = fn() { value };
+
+let shadow = fn(value) {
+  if (true) {
+    let inner = value + "!";
+  }
+
+  let results = [read(), value, inner];
+  results
+};
+
+shadow("local");
@@ -0,0 +1,32 @@
+use crate::ast::Expression;
+use crate::token::*;
+
+#[derive(Debug, Clone)]
+pub struct ArrayLiteral {
+  pub token: Token,
+  pub span: Span,
+  pub elements: Vec<Expression>,
+}
+
+impl ArrayLiteral {
+  pub fn token_literal(&self) -> Literal {
+    self.token.literal.clone()
+  }
+
+  pub fn to_string(&self) -> String {
+    let mut string = String::new();
+
+    string.push('[');
+    string.push_str(
+      &self
+        .elements
+        .iter()
+        .map(|element| element.to_string())
+        .collect::<Vec<String>>()
+        .join(", "),
+    );
+    string.push(']');
+
+    string
+  }
+}
@@ -0,0 +1,29 @@
+use crate::ast::identifier::Identifier;
+use crate::ast::Expression;
+use crate::token::*;
+
+#[derive(Debug, Clone)]
+pub struct AssignExpression {
+  pub token: Token,
+  pub span: Span,
+  pub name: Identifier,
+  pub value: Box<Expression>,
+}
+
+impl AssignExpression {
+  pub fn token_literal(&self) -> Literal {
+    self.token.literal.clone()
+  }
+
+  pub fn to_string(&self) -> String {
+    let mut string = String::new();
+
+    string.push('(');
+    string.push_str(&self.name.value);
+    string.push_str(" = ");
+    string.push_str(&self.value.to_string());
+    string.push(')');
+
+    string
+  }
+}
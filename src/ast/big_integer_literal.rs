@@ -0,0 +1,20 @@
+use crate::object::big_integer::BigInteger;
+use crate::token::*;
+
+// An integer literal too large for an i64
+#[derive(Debug, Clone)]
+pub struct BigIntegerLiteral {
+  pub token: Token,
+  pub span: Span,
+  pub value: BigInteger,
+}
+
+impl BigIntegerLiteral {
+  pub fn token_literal(&self) -> Literal {
+    self.token.literal.clone()
+  }
+
+  pub fn to_string(&self) -> String {
+    self.value.to_string()
+  }
+}
@@ -4,6 +4,7 @@ use crate::token::*;
 #[derive(Debug, Clone)]
 pub struct BlockStatement {
   pub token: Token,
+  pub span: Span,
   pub statements: Vec<Statement>,
 }
 
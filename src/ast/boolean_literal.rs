@@ -3,6 +3,7 @@ use crate::token::*;
 #[derive(Debug, Clone)]
 pub struct BooleanLiteral {
   pub token: Token,
+  pub span: Span,
   pub value: bool,
 }
 
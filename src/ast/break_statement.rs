@@ -0,0 +1,22 @@
+use crate::token::*;
+
+#[derive(Debug, Clone)]
+pub struct BreakStatement {
+  pub token: Token,
+  pub span: Span,
+}
+
+impl BreakStatement {
+  pub fn token_literal(&self) -> Literal {
+    self.token.literal.clone()
+  }
+
+  pub fn to_string(&self) -> String {
+    let mut string = String::new();
+
+    string.push_str(&self.token_literal());
+    string.push(';');
+
+    string
+  }
+}
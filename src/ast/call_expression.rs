@@ -4,6 +4,7 @@ use crate::token::*;
 #[derive(Debug, Clone)]
 pub struct CallExpression {
   pub token: Token,
+  pub span: Span,
   pub function: Box<Expression>,
   pub arguments: Box<Vec<Expression>>,
 }
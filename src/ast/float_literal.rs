@@ -0,0 +1,19 @@
+use crate::token::*;
+
+#[derive(Debug, Clone)]
+pub struct FloatLiteral {
+  pub token: Token,
+  pub span: Span,
+  pub value: f64,
+}
+
+impl FloatLiteral {
+  pub fn token_literal(&self) -> Literal {
+    self.token.literal.clone()
+  }
+
+  // Debug formatting always includes a fraction or an exponent, so the output lexes as a float
+  pub fn to_string(&self) -> String {
+    format!("{:?}", self.value)
+  }
+}
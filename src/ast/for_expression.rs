@@ -0,0 +1,33 @@
+use crate::ast::identifier::Identifier;
+use crate::ast::BlockStatement;
+use crate::ast::Expression;
+use crate::token::*;
+
+#[derive(Debug, Clone)]
+pub struct ForExpression {
+  pub token: Token,
+  pub span: Span,
+  pub variable: Identifier,
+  pub iterable: Box<Expression>,
+  pub body: Box<BlockStatement>,
+}
+
+impl ForExpression {
+  pub fn token_literal(&self) -> Literal {
+    self.token.literal.clone()
+  }
+
+  pub fn to_string(&self) -> String {
+    let mut string = String::new();
+
+    string.push_str("for ");
+    string.push('(');
+    string.push_str(&self.variable.value);
+    string.push_str(" in ");
+    string.push_str(&self.iterable.to_string());
+    string.push_str(") ");
+    string.push_str(&self.body.to_string());
+
+    string
+  }
+}
@@ -5,6 +5,7 @@ use crate::token::*;
 #[derive(Debug, Clone)]
 pub struct FunctionLiteral {
   pub token: Token,
+  pub span: Span,
   pub arguments: Vec<Identifier>,
   pub body: Box<BlockStatement>,
 }
@@ -0,0 +1,32 @@
+use crate::ast::Expression;
+use crate::token::*;
+
+#[derive(Debug, Clone)]
+pub struct HashLiteral {
+  pub token: Token,
+  pub span: Span,
+  pub pairs: Vec<(Expression, Expression)>,
+}
+
+impl HashLiteral {
+  pub fn token_literal(&self) -> Literal {
+    self.token.literal.clone()
+  }
+
+  pub fn to_string(&self) -> String {
+    let mut string = String::new();
+
+    string.push('{');
+    string.push_str(
+      &self
+        .pairs
+        .iter()
+        .map(|(key, value)| format!("{}: {}", key.to_string(), value.to_string()))
+        .collect::<Vec<String>>()
+        .join(", "),
+    );
+    string.push('}');
+
+    string
+  }
+}
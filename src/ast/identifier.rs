@@ -3,6 +3,7 @@ use crate::token::*;
 #[derive(Debug, Clone)]
 pub struct Identifier {
   pub token: Token,
+  pub span: Span,
   pub value: String,
 }
 
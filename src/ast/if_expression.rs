@@ -5,6 +5,7 @@ use crate::token::*;
 #[derive(Debug, Clone)]
 pub struct IfExpression {
   pub token: Token,
+  pub span: Span,
   pub condition: Box<Expression>,
   pub true_block: Box<BlockStatement>,
   pub false_block_or_none: Box<Option<BlockStatement>>,
@@ -0,0 +1,28 @@
+use crate::ast::Expression;
+use crate::token::*;
+
+#[derive(Debug, Clone)]
+pub struct IndexExpression {
+  pub token: Token,
+  pub span: Span,
+  pub left: Box<Expression>,
+  pub index: Box<Expression>,
+}
+
+impl IndexExpression {
+  pub fn token_literal(&self) -> Literal {
+    self.token.literal.clone()
+  }
+
+  pub fn to_string(&self) -> String {
+    let mut string = String::new();
+
+    string.push('(');
+    string.push_str(&self.left.to_string());
+    string.push('[');
+    string.push_str(&self.index.to_string());
+    string.push_str("])");
+
+    string
+  }
+}
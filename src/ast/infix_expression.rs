@@ -4,6 +4,7 @@ use crate::token::*;
 #[derive(Debug, Clone)]
 pub struct InfixExpression {
   pub token: Token,
+  pub span: Span,
   pub left: Box<Expression>,
   pub operator: String,
   pub right: Box<Expression>,
@@ -0,0 +1,33 @@
+use crate::ast::Expression;
+use crate::token::*;
+
+#[derive(Debug, Clone)]
+pub enum StringPart {
+  Literal(String),
+  Expression(Box<Expression>),
+}
+
+// A string like `"hello ${name}"`, made of literal parts and embedded expressions in order
+#[derive(Debug, Clone)]
+pub struct InterpolatedString {
+  pub token: Token,
+  pub span: Span,
+  pub parts: Vec<StringPart>,
+}
+
+impl InterpolatedString {
+  pub fn token_literal(&self) -> Literal {
+    self.token.literal.clone()
+  }
+
+  pub fn to_string(&self) -> String {
+    self
+      .parts
+      .iter()
+      .map(|part| match part {
+        StringPart::Literal(string) => string.clone(),
+        StringPart::Expression(expression) => format!("${{{}}}", expression.to_string()),
+      })
+      .collect()
+  }
+}
@@ -6,11 +6,17 @@ use crate::token::*;
 #[derive(Debug, Clone)]
 pub struct LetStatement {
   pub token: Token,
+  pub span: Span,
   pub name: Identifier,
-  pub value: Expression,
+  pub value: Box<Expression>,
 }
 
 impl LetStatement {
+  // `const` declarations share the node with `let`, only the keyword differs
+  pub fn is_constant(&self) -> bool {
+    self.token.token_type == token_types::CONST
+  }
+
   pub fn token_literal(&self) -> Literal {
     self.token.literal.clone()
   }
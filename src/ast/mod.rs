@@ -1,44 +1,85 @@
 #[cfg(test)]
 mod tests;
 
+pub mod array_literal;
+pub mod assign_expression;
+pub mod big_integer_literal;
 pub mod block_statement;
 pub mod boolean_literal;
+pub mod break_statement;
 pub mod call_expression;
+pub mod continue_statement;
+pub mod float_literal;
+pub mod for_expression;
 pub mod function_literal;
+pub mod hash_literal;
 pub mod identifier;
 pub mod if_expression;
+pub mod index_expression;
 pub mod infix_expression;
+pub mod interpolated_string;
 pub mod integer_literal;
 pub mod let_statement;
+pub mod macro_literal;
+pub mod modify;
 pub mod prefix_expression;
 pub mod return_statement;
+pub mod slice_expression;
 pub mod string_literal;
+pub mod visitor;
+pub mod visitor_mut;
+pub mod while_expression;
 
 use crate::token::Literal;
+use crate::token::Span;
+use array_literal::ArrayLiteral;
+use assign_expression::AssignExpression;
+use big_integer_literal::BigIntegerLiteral;
 use block_statement::BlockStatement;
 use boolean_literal::BooleanLiteral;
+use break_statement::BreakStatement;
 use call_expression::CallExpression;
+use continue_statement::ContinueStatement;
+use for_expression::ForExpression;
+use float_literal::FloatLiteral;
 use function_literal::FunctionLiteral;
+use hash_literal::HashLiteral;
 use identifier::Identifier;
 use if_expression::IfExpression;
+use index_expression::IndexExpression;
+use slice_expression::SliceExpression;
 use infix_expression::InfixExpression;
+use interpolated_string::InterpolatedString;
 use integer_literal::IntegerLiteral;
 use let_statement::LetStatement;
+use macro_literal::MacroLiteral;
 use prefix_expression::PrefixExpression;
 use return_statement::ReturnStatement;
 use string_literal::StringLiteral;
+use while_expression::WhileExpression;
 
 #[derive(Debug, Clone)]
 pub enum Expression {
   BooleanLiteral(BooleanLiteral),
   Identifier(Identifier),
   IntegerLiteral(IntegerLiteral),
+  BigIntegerLiteral(BigIntegerLiteral),
+  FloatLiteral(FloatLiteral),
   PrefixExpression(PrefixExpression),
   InfixExpression(InfixExpression),
   IfExpression(IfExpression),
   FunctionLiteral(FunctionLiteral),
+  MacroLiteral(MacroLiteral),
   CallExpression(CallExpression),
   StringLiteral(StringLiteral),
+  InterpolatedString(InterpolatedString),
+  ArrayLiteral(ArrayLiteral),
+  IndexExpression(IndexExpression),
+  SliceExpression(SliceExpression),
+  HashLiteral(HashLiteral),
+  AssignExpression(AssignExpression),
+  WhileExpression(WhileExpression),
+  ForExpression(ForExpression),
 }
 
 impl Expression {
@@ -47,12 +88,48 @@ impl Expression {
       Expression::Identifier(expression) => expression.token_literal(),
       Expression::BooleanLiteral(boolean_literal) => boolean_literal.token_literal(),
       Expression::IntegerLiteral(integer_literal) => integer_literal.token_literal(),
+      Expression::BigIntegerLiteral(big_integer_literal) => big_integer_literal.token_literal(),
+      Expression::FloatLiteral(float_literal) => float_literal.token_literal(),
       Expression::PrefixExpression(prefix_expression) => prefix_expression.token_literal(),
       Expression::InfixExpression(infix_expression) => infix_expression.token_literal(),
       Expression::IfExpression(if_expression) => if_expression.token_literal(),
       Expression::FunctionLiteral(function_literal) => function_literal.token_literal(),
+      Expression::MacroLiteral(macro_literal) => macro_literal.token_literal(),
       Expression::CallExpression(call_expression) => call_expression.token_literal(),
       Expression::StringLiteral(string_literal) => string_literal.token_literal(),
+      Expression::InterpolatedString(interpolated_string) => interpolated_string.token_literal(),
+      Expression::ArrayLiteral(array_literal) => array_literal.token_literal(),
+      Expression::IndexExpression(index_expression) => index_expression.token_literal(),
+      Expression::SliceExpression(slice_expression) => slice_expression.token_literal(),
+      Expression::HashLiteral(hash_literal) => hash_literal.token_literal(),
+      Expression::AssignExpression(assign_expression) => assign_expression.token_literal(),
+      Expression::WhileExpression(while_expression) => while_expression.token_literal(),
+      Expression::ForExpression(for_expression) => for_expression.token_literal(),
+    }
+  }
+
+  pub fn span(&self) -> Span {
+    match self {
+      Expression::Identifier(expression) => expression.span,
+      Expression::BooleanLiteral(boolean_literal) => boolean_literal.span,
+      Expression::IntegerLiteral(integer_literal) => integer_literal.span,
+      Expression::BigIntegerLiteral(big_integer_literal) => big_integer_literal.span,
+      Expression::FloatLiteral(float_literal) => float_literal.span,
+      Expression::PrefixExpression(prefix_expression) => prefix_expression.span,
+      Expression::InfixExpression(infix_expression) => infix_expression.span,
+      Expression::IfExpression(if_expression) => if_expression.span,
+      Expression::FunctionLiteral(function_literal) => function_literal.span,
+      Expression::MacroLiteral(macro_literal) => macro_literal.span,
+      Expression::CallExpression(call_expression) => call_expression.span,
+      Expression::StringLiteral(string_literal) => string_literal.span,
+      Expression::InterpolatedString(interpolated_string) => interpolated_string.span,
+      Expression::ArrayLiteral(array_literal) => array_literal.span,
+      Expression::IndexExpression(index_expression) => index_expression.span,
+      Expression::SliceExpression(slice_expression) => slice_expression.span,
+      Expression::HashLiteral(hash_literal) => hash_literal.span,
+      Expression::AssignExpression(assign_expression) => assign_expression.span,
+      Expression::WhileExpression(while_expression) => while_expression.span,
+      Expression::ForExpression(for_expression) => for_expression.span,
     }
   }
 
@@ -60,13 +137,24 @@ impl Expression {
     match self {
       Expression::Identifier(expression) => expression.to_string(),
       Expression::IntegerLiteral(integer_literal) => integer_literal.to_string(),
+      Expression::BigIntegerLiteral(big_integer_literal) => big_integer_literal.to_string(),
+      Expression::FloatLiteral(float_literal) => float_literal.to_string(),
       Expression::BooleanLiteral(boolean_literal) => boolean_literal.to_string(),
       Expression::PrefixExpression(prefix_expression) => prefix_expression.to_string(),
       Expression::InfixExpression(infix_expression) => infix_expression.to_string(),
       Expression::IfExpression(if_expression) => if_expression.to_string(),
       Expression::FunctionLiteral(function_literal) => function_literal.to_string(),
+      Expression::MacroLiteral(macro_literal) => macro_literal.to_string(),
       Expression::CallExpression(call_expression) => call_expression.to_string(),
       Expression::StringLiteral(string_literal) => string_literal.to_string(),
+      Expression::InterpolatedString(interpolated_string) => interpolated_string.to_string(),
+      Expression::ArrayLiteral(array_literal) => array_literal.to_string(),
+      Expression::IndexExpression(index_expression) => index_expression.to_string(),
+      Expression::SliceExpression(slice_expression) => slice_expression.to_string(),
+      Expression::HashLiteral(hash_literal) => hash_literal.to_string(),
+      Expression::AssignExpression(assign_expression) => assign_expression.to_string(),
+      Expression::WhileExpression(while_expression) => while_expression.to_string(),
+      Expression::ForExpression(for_expression) => for_expression.to_string(),
     }
   }
 }
@@ -77,6 +165,8 @@ pub enum Statement {
   ReturnStatement(ReturnStatement),
   Expression(Expression),
   BlockStatement(BlockStatement),
+  BreakStatement(BreakStatement),
+  ContinueStatement(ContinueStatement),
 }
 
 impl Statement {
@@ -86,6 +176,19 @@ impl Statement {
       Statement::ReturnStatement(return_statement) => return_statement.token_literal(),
       Statement::Expression(expression) => expression.token_literal(),
       Statement::BlockStatement(block_statement) => block_statement.token_literal(),
+      Statement::BreakStatement(break_statement) => break_statement.token_literal(),
+      Statement::ContinueStatement(continue_statement) => continue_statement.token_literal(),
+    }
+  }
+
+  pub fn span(&self) -> Span {
+    match self {
+      Statement::LetStatement(let_statement) => let_statement.span,
+      Statement::ReturnStatement(return_statement) => return_statement.span,
+      Statement::Expression(expression) => expression.span(),
+      Statement::BlockStatement(block_statement) => block_statement.span,
+      Statement::BreakStatement(break_statement) => break_statement.span,
+      Statement::ContinueStatement(continue_statement) => continue_statement.span,
     }
   }
 
@@ -95,11 +198,13 @@ impl Statement {
       Statement::ReturnStatement(return_statement) => return_statement.to_string(),
       Statement::Expression(expression) => expression.to_string(),
       Statement::BlockStatement(block_statement) => block_statement.to_string(),
+      Statement::BreakStatement(break_statement) => break_statement.to_string(),
+      Statement::ContinueStatement(continue_statement) => continue_statement.to_string(),
     }
   }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Program {
   pub statements: Vec<Statement>,
 }
@@ -0,0 +1,60 @@
+use std::mem;
+
+use crate::ast::*;
+use crate::ast::visitor_mut::*;
+use crate::token::*;
+
+// Rebuilds a tree from the bottom up: the children of an expression are modified before the
+// expression itself is handed to the modifier, which returns what to put in its place. The first
+// error returned by the modifier stops the walk.
+pub fn modify_program<E, M>(mut program: Program, modifier: &mut M) -> Result<Program, E>
+where
+  M: FnMut(Expression) -> Result<Expression, E>,
+{
+  let mut modifier = Modifier { modifier, error: None };
+  modifier.visit_program_mut(&mut program);
+
+  match modifier.error {
+    Some(error) => Err(error),
+    None => Ok(program),
+  }
+}
+
+pub fn modify_expression<E, M>(mut expression: Expression, modifier: &mut M) -> Result<Expression, E>
+where
+  M: FnMut(Expression) -> Result<Expression, E>,
+{
+  let mut modifier = Modifier { modifier, error: None };
+  modifier.visit_expression_mut(&mut expression);
+
+  match modifier.error {
+    Some(error) => Err(error),
+    None => Ok(expression),
+  }
+}
+
+struct Modifier<'a, E, M> {
+  modifier: &'a mut M,
+  error: Option<E>,
+}
+
+impl<'a, E, M> VisitorMut for Modifier<'a, E, M>
+where
+  M: FnMut(Expression) -> Result<Expression, E>,
+{
+  fn visit_expression_mut(&mut self, expression: &mut Expression) {
+    walk_expression_mut(self, expression);
+
+    if self.error.is_some() {
+      return
+    }
+
+    // The modifier takes the expression by value, so a placeholder holds its place meanwhile
+    let placeholder = Expression::BooleanLiteral(BooleanLiteral { token: Token::default(), span: Span::default(), value: false });
+
+    match (self.modifier)(mem::replace(expression, placeholder)) {
+      Ok(modified) => *expression = modified,
+      Err(error) => self.error = Some(error),
+    }
+  }
+}
@@ -4,6 +4,7 @@ use crate::token::*;
 #[derive(Debug, Clone)]
 pub struct PrefixExpression {
   pub token: Token,
+  pub span: Span,
   pub operator: String,
   pub right: Box<Expression>,
 }
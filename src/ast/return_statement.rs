@@ -4,6 +4,7 @@ use crate::token::*;
 #[derive(Debug, Clone)]
 pub struct ReturnStatement {
   pub token: Token,
+  pub span: Span,
   pub return_value: Box<Expression>,
 }
 
@@ -0,0 +1,40 @@
+use crate::ast::Expression;
+use crate::token::*;
+
+// `left[start:end]`, where a missing bound means the start or the end of `left`
+#[derive(Debug, Clone)]
+pub struct SliceExpression {
+  pub token: Token,
+  pub span: Span,
+  pub left: Box<Expression>,
+  pub start: Option<Box<Expression>>,
+  pub end: Option<Box<Expression>>,
+}
+
+impl SliceExpression {
+  pub fn token_literal(&self) -> Literal {
+    self.token.literal.clone()
+  }
+
+  pub fn to_string(&self) -> String {
+    let mut string = String::new();
+
+    string.push('(');
+    string.push_str(&self.left.to_string());
+    string.push('[');
+
+    if let Some(start) = &self.start {
+      string.push_str(&start.to_string());
+    }
+
+    string.push(':');
+
+    if let Some(end) = &self.end {
+      string.push_str(&end.to_string());
+    }
+
+    string.push_str("])");
+
+    string
+  }
+}
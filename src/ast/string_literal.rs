@@ -3,6 +3,7 @@ use crate::token::*;
 #[derive(Debug, Clone)]
 pub struct StringLiteral {
   pub token: Token,
+  pub span: Span,
   pub value: String,
 }
 
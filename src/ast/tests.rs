@@ -1,4 +1,9 @@
 use crate::ast::*;
+use crate::ast::modify::*;
+use crate::ast::visitor::*;
+use crate::ast::visitor_mut::*;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
 use crate::token::*;
 use block_statement::BlockStatement;
 use function_literal::FunctionLiteral;
@@ -13,46 +18,60 @@ fn test_to_string() {
       token: Token {
         token_type: token_types::LET,
         literal: "let".to_string(),
+        ..Default::default()
       },
+      span: Span::default(),
       name: Identifier {
         token: Token {
           token_type: token_types::IDENT,
           literal: "myVar".to_string(),
+          ..Default::default()
         },
+        span: Span::default(),
         value: "myVar".to_string(),
       },
-      value: Expression::FunctionLiteral(FunctionLiteral {
+      value: Box::new(Expression::FunctionLiteral(FunctionLiteral {
         token: Token {
           token_type: token_types::FUNCTION,
           literal: "fn".to_string(),
+          ..Default::default()
         },
+        span: Span::default(),
         arguments: vec![Identifier {
           token: Token {
             token_type: token_types::IDENT,
             literal: "param".to_string(),
+            ..Default::default()
           },
+          span: Span::default(),
           value: "param".to_string(),
         }],
         body: Box::new(BlockStatement {
           token: Token {
             token_type: token_types::LBRACE,
             literal: "{".to_string(),
+            ..Default::default()
           },
+          span: Span::default(),
           statements: vec![Statement::ReturnStatement(ReturnStatement {
             token: Token {
               token_type: token_types::RETURN,
               literal: "return".to_string(),
+              ..Default::default()
             },
+            span: Span::default(),
             return_value: Box::new(Expression::IntegerLiteral(IntegerLiteral {
               token: Token {
                 token_type: token_types::INT,
                 literal: "10".to_string(),
+                ..Default::default()
               },
+              span: Span::default(),
               value: 10,
             })),
           })],
         }),
-      }),
+      })),
     })],
   };
 
@@ -61,3 +80,122 @@ fn test_to_string() {
     "let myVar = fn (param) {\nreturn 10;\n}\n;"
   );
 }
+
+#[test]
+fn test_modify() {
+  // Every integer literal 1 is replaced with 2
+  let tests = vec![
+    ("1", "2"),
+    ("1 + 2", "2 + 2"),
+    ("-1", "-2"),
+    ("let x = 1; return 1;", "let x = 2; return 2;"),
+    ("x = 1", "x = 2"),
+    ("if (1) { 1 } else { 1 }", "if (2) { 2 } else { 2 }"),
+    ("fn(x) { 1 }", "fn(x) { 2 }"),
+    ("macro(x) { 1 }", "macro(x) { 2 }"),
+    ("f(1, a[1], a[1:1])", "f(2, a[2], a[2:2])"),
+    ("[1, {1: 1}]", "[2, {2: 2}]"),
+    ("\"a${1}\"", "\"a${2}\""),
+    ("while (1) { 1 }", "while (2) { 2 }"),
+    ("for (x in 1) { 1 }", "for (x in 2) { 2 }"),
+  ];
+
+  for (input, expected) in tests {
+    let program = match_or_fail!(Parser::new(Lexer::new(input)).parse_program(), Ok(m) => m);
+    let modified = modify_program::<(), _>(program, &mut |expression| match expression {
+      Expression::IntegerLiteral(mut integer_literal) if integer_literal.value == 1 => {
+        integer_literal.value = 2;
+        Ok(Expression::IntegerLiteral(integer_literal))
+      },
+      expression => Ok(expression),
+    });
+
+    let expected = match_or_fail!(Parser::new(Lexer::new(expected)).parse_program(), Ok(m) => m);
+
+    assert_eq!(match_or_fail!(modified, Ok(m) => m).to_string(), expected.to_string(), "input: {}", input);
+  }
+}
+
+#[test]
+fn test_modify_stops_at_first_error() {
+  let program = match_or_fail!(Parser::new(Lexer::new("1; 2; 3")).parse_program(), Ok(m) => m);
+  let mut visited = vec![];
+
+  let result = modify_program(program, &mut |expression| {
+    visited.push(expression.to_string());
+
+    match expression {
+      Expression::IntegerLiteral(integer_literal) if integer_literal.value == 2 => Err("two"),
+      expression => Ok(expression),
+    }
+  });
+
+  assert!(matches!(result, Err("two")));
+  assert_eq!(visited, vec!["1", "2"]);
+}
+
+// Collects the names of the identifiers it visits, without looking into function bodies
+struct IdentifierCollector {
+  names: Vec<String>,
+}
+
+impl Visitor for IdentifierCollector {
+  fn visit_identifier(&mut self, identifier: &Identifier) {
+    self.names.push(identifier.value.clone());
+  }
+
+  fn visit_function_literal(&mut self, _function_literal: &FunctionLiteral) {}
+}
+
+#[test]
+fn test_visitor() {
+  let input = "
+    let a = b + c[d:e];
+    for (f in g) { h(\"${i}\", {j: k}) }
+    let l = fn(m) { n };
+    if (o) { p = q } else { -r }
+  ";
+
+  let program = match_or_fail!(Parser::new(Lexer::new(input)).parse_program(), Ok(m) => m);
+  let mut collector = IdentifierCollector { names: vec![] };
+  collector.visit_program(&program);
+
+  let expected: Vec<&str> = "a b c d e f g h i j k l o p q r".split(' ').collect();
+  assert_eq!(collector.names, expected);
+}
+
+// Renames identifiers and doubles integer literals in place
+struct Rewriter;
+
+impl VisitorMut for Rewriter {
+  fn visit_identifier_mut(&mut self, identifier: &mut Identifier) {
+    identifier.value = identifier.value.to_uppercase();
+  }
+
+  fn visit_expression_mut(&mut self, expression: &mut Expression) {
+    walk_expression_mut(self, expression);
+
+    // Whole nodes can be replaced too
+    if let Expression::IntegerLiteral(integer_literal) = expression {
+      *expression = Expression::InfixExpression(infix_expression::InfixExpression {
+        token: integer_literal.token.clone(),
+        span: integer_literal.span,
+        left: Box::new(Expression::IntegerLiteral(integer_literal.clone())),
+        operator: "*".to_string(),
+        right: Box::new(Expression::IntegerLiteral(integer_literal.clone())),
+      });
+    }
+  }
+}
+
+#[test]
+fn test_visitor_mut() {
+  let input = "let a = fn(b) { b + 1 }; a(2)";
+  let expected = "let A = fn(B) { B + 1 * 1 }; A(2 * 2)";
+
+  let mut program = match_or_fail!(Parser::new(Lexer::new(input)).parse_program(), Ok(m) => m);
+  let expected = match_or_fail!(Parser::new(Lexer::new(expected)).parse_program(), Ok(m) => m);
+  Rewriter.visit_program_mut(&mut program);
+
+  assert_eq!(program.to_string(), expected.to_string());
+}
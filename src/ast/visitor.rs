@@ -0,0 +1,252 @@
+use crate::ast::*;
+use interpolated_string::StringPart;
+
+// Walks a tree without changing it. Every method visits one kind of node and by default walks into
+// its children with the matching `walk_` function, so an implementation only overrides the nodes
+// it is interested in. An override that still wants to reach the children calls `walk_` itself.
+pub trait Visitor {
+  fn visit_program(&mut self, program: &Program) {
+    walk_program(self, program)
+  }
+
+  fn visit_statement(&mut self, statement: &Statement) {
+    walk_statement(self, statement)
+  }
+
+  fn visit_let_statement(&mut self, let_statement: &LetStatement) {
+    walk_let_statement(self, let_statement)
+  }
+
+  fn visit_return_statement(&mut self, return_statement: &ReturnStatement) {
+    walk_return_statement(self, return_statement)
+  }
+
+  fn visit_block_statement(&mut self, block_statement: &BlockStatement) {
+    walk_block_statement(self, block_statement)
+  }
+
+  fn visit_break_statement(&mut self, _break_statement: &BreakStatement) {}
+
+  fn visit_continue_statement(&mut self, _continue_statement: &ContinueStatement) {}
+
+  fn visit_expression(&mut self, expression: &Expression) {
+    walk_expression(self, expression)
+  }
+
+  fn visit_identifier(&mut self, _identifier: &Identifier) {}
+
+  fn visit_boolean_literal(&mut self, _boolean_literal: &BooleanLiteral) {}
+
+  fn visit_integer_literal(&mut self, _integer_literal: &IntegerLiteral) {}
+
+  fn visit_big_integer_literal(&mut self, _big_integer_literal: &BigIntegerLiteral) {}
+
+  fn visit_float_literal(&mut self, _float_literal: &FloatLiteral) {}
+
+  fn visit_string_literal(&mut self, _string_literal: &StringLiteral) {}
+
+  fn visit_interpolated_string(&mut self, interpolated_string: &InterpolatedString) {
+    walk_interpolated_string(self, interpolated_string)
+  }
+
+  fn visit_prefix_expression(&mut self, prefix_expression: &PrefixExpression) {
+    walk_prefix_expression(self, prefix_expression)
+  }
+
+  fn visit_infix_expression(&mut self, infix_expression: &InfixExpression) {
+    walk_infix_expression(self, infix_expression)
+  }
+
+  fn visit_if_expression(&mut self, if_expression: &IfExpression) {
+    walk_if_expression(self, if_expression)
+  }
+
+  fn visit_function_literal(&mut self, function_literal: &FunctionLiteral) {
+    walk_function_literal(self, function_literal)
+  }
+
+  fn visit_macro_literal(&mut self, macro_literal: &MacroLiteral) {
+    walk_macro_literal(self, macro_literal)
+  }
+
+  fn visit_call_expression(&mut self, call_expression: &CallExpression) {
+    walk_call_expression(self, call_expression)
+  }
+
+  fn visit_array_literal(&mut self, array_literal: &ArrayLiteral) {
+    walk_array_literal(self, array_literal)
+  }
+
+  fn visit_index_expression(&mut self, index_expression: &IndexExpression) {
+    walk_index_expression(self, index_expression)
+  }
+
+  fn visit_slice_expression(&mut self, slice_expression: &SliceExpression) {
+    walk_slice_expression(self, slice_expression)
+  }
+
+  fn visit_hash_literal(&mut self, hash_literal: &HashLiteral) {
+    walk_hash_literal(self, hash_literal)
+  }
+
+  fn visit_assign_expression(&mut self, assign_expression: &AssignExpression) {
+    walk_assign_expression(self, assign_expression)
+  }
+
+  fn visit_while_expression(&mut self, while_expression: &WhileExpression) {
+    walk_while_expression(self, while_expression)
+  }
+
+  fn visit_for_expression(&mut self, for_expression: &ForExpression) {
+    walk_for_expression(self, for_expression)
+  }
+}
+
+pub fn walk_program<V: Visitor + ?Sized>(visitor: &mut V, program: &Program) {
+  for statement in &program.statements {
+    visitor.visit_statement(statement);
+  }
+}
+
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Statement) {
+  match statement {
+    Statement::LetStatement(let_statement) => visitor.visit_let_statement(let_statement),
+    Statement::ReturnStatement(return_statement) => visitor.visit_return_statement(return_statement),
+    Statement::Expression(expression) => visitor.visit_expression(expression),
+    Statement::BlockStatement(block_statement) => visitor.visit_block_statement(block_statement),
+    Statement::BreakStatement(break_statement) => visitor.visit_break_statement(break_statement),
+    Statement::ContinueStatement(continue_statement) => visitor.visit_continue_statement(continue_statement),
+  }
+}
+
+pub fn walk_let_statement<V: Visitor + ?Sized>(visitor: &mut V, let_statement: &LetStatement) {
+  visitor.visit_identifier(&let_statement.name);
+  visitor.visit_expression(&let_statement.value);
+}
+
+pub fn walk_return_statement<V: Visitor + ?Sized>(visitor: &mut V, return_statement: &ReturnStatement) {
+  visitor.visit_expression(&return_statement.return_value);
+}
+
+pub fn walk_block_statement<V: Visitor + ?Sized>(visitor: &mut V, block_statement: &BlockStatement) {
+  for statement in &block_statement.statements {
+    visitor.visit_statement(statement);
+  }
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expression) {
+  match expression {
+    Expression::Identifier(identifier) => visitor.visit_identifier(identifier),
+    Expression::BooleanLiteral(boolean_literal) => visitor.visit_boolean_literal(boolean_literal),
+    Expression::IntegerLiteral(integer_literal) => visitor.visit_integer_literal(integer_literal),
+    Expression::BigIntegerLiteral(big_integer_literal) => visitor.visit_big_integer_literal(big_integer_literal),
+    Expression::FloatLiteral(float_literal) => visitor.visit_float_literal(float_literal),
+    Expression::StringLiteral(string_literal) => visitor.visit_string_literal(string_literal),
+    Expression::InterpolatedString(interpolated_string) => visitor.visit_interpolated_string(interpolated_string),
+    Expression::PrefixExpression(prefix_expression) => visitor.visit_prefix_expression(prefix_expression),
+    Expression::InfixExpression(infix_expression) => visitor.visit_infix_expression(infix_expression),
+    Expression::IfExpression(if_expression) => visitor.visit_if_expression(if_expression),
+    Expression::FunctionLiteral(function_literal) => visitor.visit_function_literal(function_literal),
+    Expression::MacroLiteral(macro_literal) => visitor.visit_macro_literal(macro_literal),
+    Expression::CallExpression(call_expression) => visitor.visit_call_expression(call_expression),
+    Expression::ArrayLiteral(array_literal) => visitor.visit_array_literal(array_literal),
+    Expression::IndexExpression(index_expression) => visitor.visit_index_expression(index_expression),
+    Expression::SliceExpression(slice_expression) => visitor.visit_slice_expression(slice_expression),
+    Expression::HashLiteral(hash_literal) => visitor.visit_hash_literal(hash_literal),
+    Expression::AssignExpression(assign_expression) => visitor.visit_assign_expression(assign_expression),
+    Expression::WhileExpression(while_expression) => visitor.visit_while_expression(while_expression),
+    Expression::ForExpression(for_expression) => visitor.visit_for_expression(for_expression),
+  }
+}
+
+pub fn walk_interpolated_string<V: Visitor + ?Sized>(visitor: &mut V, interpolated_string: &InterpolatedString) {
+  for part in &interpolated_string.parts {
+    if let StringPart::Expression(expression) = part {
+      visitor.visit_expression(expression);
+    }
+  }
+}
+
+pub fn walk_prefix_expression<V: Visitor + ?Sized>(visitor: &mut V, prefix_expression: &PrefixExpression) {
+  visitor.visit_expression(&prefix_expression.right);
+}
+
+pub fn walk_infix_expression<V: Visitor + ?Sized>(visitor: &mut V, infix_expression: &InfixExpression) {
+  visitor.visit_expression(&infix_expression.left);
+  visitor.visit_expression(&infix_expression.right);
+}
+
+pub fn walk_if_expression<V: Visitor + ?Sized>(visitor: &mut V, if_expression: &IfExpression) {
+  visitor.visit_expression(&if_expression.condition);
+  visitor.visit_block_statement(&if_expression.true_block);
+
+  if let Some(false_block) = &*if_expression.false_block_or_none {
+    visitor.visit_block_statement(false_block);
+  }
+}
+
+pub fn walk_function_literal<V: Visitor + ?Sized>(visitor: &mut V, function_literal: &FunctionLiteral) {
+  for argument in &function_literal.arguments {
+    visitor.visit_identifier(argument);
+  }
+
+  visitor.visit_block_statement(&function_literal.body);
+}
+
+pub fn walk_macro_literal<V: Visitor + ?Sized>(visitor: &mut V, macro_literal: &MacroLiteral) {
+  for argument in &macro_literal.arguments {
+    visitor.visit_identifier(argument);
+  }
+
+  visitor.visit_block_statement(&macro_literal.body);
+}
+
+pub fn walk_call_expression<V: Visitor + ?Sized>(visitor: &mut V, call_expression: &CallExpression) {
+  visitor.visit_expression(&call_expression.function);
+
+  for argument in call_expression.arguments.iter() {
+    visitor.visit_expression(argument);
+  }
+}
+
+pub fn walk_array_literal<V: Visitor + ?Sized>(visitor: &mut V, array_literal: &ArrayLiteral) {
+  for element in &array_literal.elements {
+    visitor.visit_expression(element);
+  }
+}
+
+pub fn walk_index_expression<V: Visitor + ?Sized>(visitor: &mut V, index_expression: &IndexExpression) {
+  visitor.visit_expression(&index_expression.left);
+  visitor.visit_expression(&index_expression.index);
+}
+
+pub fn walk_slice_expression<V: Visitor + ?Sized>(visitor: &mut V, slice_expression: &SliceExpression) {
+  visitor.visit_expression(&slice_expression.left);
+
+  for bound in slice_expression.start.iter().chain(slice_expression.end.iter()) {
+    visitor.visit_expression(bound);
+  }
+}
+
+pub fn walk_hash_literal<V: Visitor + ?Sized>(visitor: &mut V, hash_literal: &HashLiteral) {
+  for (key, value) in &hash_literal.pairs {
+    visitor.visit_expression(key);
+    visitor.visit_expression(value);
+  }
+}
+
+pub fn walk_assign_expression<V: Visitor + ?Sized>(visitor: &mut V, assign_expression: &AssignExpression) {
+  visitor.visit_identifier(&assign_expression.name);
+  visitor.visit_expression(&assign_expression.value);
+}
+
+pub fn walk_while_expression<V: Visitor + ?Sized>(visitor: &mut V, while_expression: &WhileExpression) {
+  visitor.visit_expression(&while_expression.condition);
+  visitor.visit_block_statement(&while_expression.body);
+}
+
+pub fn walk_for_expression<V: Visitor + ?Sized>(visitor: &mut V, for_expression: &ForExpression) {
+  visitor.visit_identifier(&for_expression.variable);
+  visitor.visit_expression(&for_expression.iterable);
+  visitor.visit_block_statement(&for_expression.body);
+}
@@ -0,0 +1,251 @@
+use crate::ast::*;
+use interpolated_string::StringPart;
+
+// Walks a tree and changes it in place, like `Visitor` does for reading. A method can also replace
+// the whole node it is given, e.g. an expression with a simpler one.
+pub trait VisitorMut {
+  fn visit_program_mut(&mut self, program: &mut Program) {
+    walk_program_mut(self, program)
+  }
+
+  fn visit_statement_mut(&mut self, statement: &mut Statement) {
+    walk_statement_mut(self, statement)
+  }
+
+  fn visit_let_statement_mut(&mut self, let_statement: &mut LetStatement) {
+    walk_let_statement_mut(self, let_statement)
+  }
+
+  fn visit_return_statement_mut(&mut self, return_statement: &mut ReturnStatement) {
+    walk_return_statement_mut(self, return_statement)
+  }
+
+  fn visit_block_statement_mut(&mut self, block_statement: &mut BlockStatement) {
+    walk_block_statement_mut(self, block_statement)
+  }
+
+  fn visit_break_statement_mut(&mut self, _break_statement: &mut BreakStatement) {}
+
+  fn visit_continue_statement_mut(&mut self, _continue_statement: &mut ContinueStatement) {}
+
+  fn visit_expression_mut(&mut self, expression: &mut Expression) {
+    walk_expression_mut(self, expression)
+  }
+
+  fn visit_identifier_mut(&mut self, _identifier: &mut Identifier) {}
+
+  fn visit_boolean_literal_mut(&mut self, _boolean_literal: &mut BooleanLiteral) {}
+
+  fn visit_integer_literal_mut(&mut self, _integer_literal: &mut IntegerLiteral) {}
+
+  fn visit_big_integer_literal_mut(&mut self, _big_integer_literal: &mut BigIntegerLiteral) {}
+
+  fn visit_float_literal_mut(&mut self, _float_literal: &mut FloatLiteral) {}
+
+  fn visit_string_literal_mut(&mut self, _string_literal: &mut StringLiteral) {}
+
+  fn visit_interpolated_string_mut(&mut self, interpolated_string: &mut InterpolatedString) {
+    walk_interpolated_string_mut(self, interpolated_string)
+  }
+
+  fn visit_prefix_expression_mut(&mut self, prefix_expression: &mut PrefixExpression) {
+    walk_prefix_expression_mut(self, prefix_expression)
+  }
+
+  fn visit_infix_expression_mut(&mut self, infix_expression: &mut InfixExpression) {
+    walk_infix_expression_mut(self, infix_expression)
+  }
+
+  fn visit_if_expression_mut(&mut self, if_expression: &mut IfExpression) {
+    walk_if_expression_mut(self, if_expression)
+  }
+
+  fn visit_function_literal_mut(&mut self, function_literal: &mut FunctionLiteral) {
+    walk_function_literal_mut(self, function_literal)
+  }
+
+  fn visit_macro_literal_mut(&mut self, macro_literal: &mut MacroLiteral) {
+    walk_macro_literal_mut(self, macro_literal)
+  }
+
+  fn visit_call_expression_mut(&mut self, call_expression: &mut CallExpression) {
+    walk_call_expression_mut(self, call_expression)
+  }
+
+  fn visit_array_literal_mut(&mut self, array_literal: &mut ArrayLiteral) {
+    walk_array_literal_mut(self, array_literal)
+  }
+
+  fn visit_index_expression_mut(&mut self, index_expression: &mut IndexExpression) {
+    walk_index_expression_mut(self, index_expression)
+  }
+
+  fn visit_slice_expression_mut(&mut self, slice_expression: &mut SliceExpression) {
+    walk_slice_expression_mut(self, slice_expression)
+  }
+
+  fn visit_hash_literal_mut(&mut self, hash_literal: &mut HashLiteral) {
+    walk_hash_literal_mut(self, hash_literal)
+  }
+
+  fn visit_assign_expression_mut(&mut self, assign_expression: &mut AssignExpression) {
+    walk_assign_expression_mut(self, assign_expression)
+  }
+
+  fn visit_while_expression_mut(&mut self, while_expression: &mut WhileExpression) {
+    walk_while_expression_mut(self, while_expression)
+  }
+
+  fn visit_for_expression_mut(&mut self, for_expression: &mut ForExpression) {
+    walk_for_expression_mut(self, for_expression)
+  }
+}
+
+pub fn walk_program_mut<V: VisitorMut + ?Sized>(visitor: &mut V, program: &mut Program) {
+  for statement in &mut program.statements {
+    visitor.visit_statement_mut(statement);
+  }
+}
+
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement) {
+  match statement {
+    Statement::LetStatement(let_statement) => visitor.visit_let_statement_mut(let_statement),
+    Statement::ReturnStatement(return_statement) => visitor.visit_return_statement_mut(return_statement),
+    Statement::Expression(expression) => visitor.visit_expression_mut(expression),
+    Statement::BlockStatement(block_statement) => visitor.visit_block_statement_mut(block_statement),
+    Statement::BreakStatement(break_statement) => visitor.visit_break_statement_mut(break_statement),
+    Statement::ContinueStatement(continue_statement) => visitor.visit_continue_statement_mut(continue_statement),
+  }
+}
+
+pub fn walk_let_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, let_statement: &mut LetStatement) {
+  visitor.visit_identifier_mut(&mut let_statement.name);
+  visitor.visit_expression_mut(&mut let_statement.value);
+}
+
+pub fn walk_return_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, return_statement: &mut ReturnStatement) {
+  visitor.visit_expression_mut(&mut return_statement.return_value);
+}
+
+pub fn walk_block_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, block_statement: &mut BlockStatement) {
+  for statement in &mut block_statement.statements {
+    visitor.visit_statement_mut(statement);
+  }
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expression: &mut Expression) {
+  match expression {
+    Expression::Identifier(identifier) => visitor.visit_identifier_mut(identifier),
+    Expression::BooleanLiteral(boolean_literal) => visitor.visit_boolean_literal_mut(boolean_literal),
+    Expression::IntegerLiteral(integer_literal) => visitor.visit_integer_literal_mut(integer_literal),
+    Expression::BigIntegerLiteral(big_integer_literal) => visitor.visit_big_integer_literal_mut(big_integer_literal),
+    Expression::FloatLiteral(float_literal) => visitor.visit_float_literal_mut(float_literal),
+    Expression::StringLiteral(string_literal) => visitor.visit_string_literal_mut(string_literal),
+    Expression::InterpolatedString(interpolated_string) => visitor.visit_interpolated_string_mut(interpolated_string),
+    Expression::PrefixExpression(prefix_expression) => visitor.visit_prefix_expression_mut(prefix_expression),
+    Expression::InfixExpression(infix_expression) => visitor.visit_infix_expression_mut(infix_expression),
+    Expression::IfExpression(if_expression) => visitor.visit_if_expression_mut(if_expression),
+    Expression::FunctionLiteral(function_literal) => visitor.visit_function_literal_mut(function_literal),
+    Expression::MacroLiteral(macro_literal) => visitor.visit_macro_literal_mut(macro_literal),
+    Expression::CallExpression(call_expression) => visitor.visit_call_expression_mut(call_expression),
+    Expression::ArrayLiteral(array_literal) => visitor.visit_array_literal_mut(array_literal),
+    Expression::IndexExpression(index_expression) => visitor.visit_index_expression_mut(index_expression),
+    Expression::SliceExpression(slice_expression) => visitor.visit_slice_expression_mut(slice_expression),
+    Expression::HashLiteral(hash_literal) => visitor.visit_hash_literal_mut(hash_literal),
+    Expression::AssignExpression(assign_expression) => visitor.visit_assign_expression_mut(assign_expression),
+    Expression::WhileExpression(while_expression) => visitor.visit_while_expression_mut(while_expression),
+    Expression::ForExpression(for_expression) => visitor.visit_for_expression_mut(for_expression),
+  }
+}
+
+pub fn walk_interpolated_string_mut<V: VisitorMut + ?Sized>(visitor: &mut V, interpolated_string: &mut InterpolatedString) {
+  for part in &mut interpolated_string.parts {
+    if let StringPart::Expression(expression) = part {
+      visitor.visit_expression_mut(expression);
+    }
+  }
+}
+
+pub fn walk_prefix_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, prefix_expression: &mut PrefixExpression) {
+  visitor.visit_expression_mut(&mut prefix_expression.right);
+}
+
+pub fn walk_infix_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, infix_expression: &mut InfixExpression) {
+  visitor.visit_expression_mut(&mut infix_expression.left);
+  visitor.visit_expression_mut(&mut infix_expression.right);
+}
+
+pub fn walk_if_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, if_expression: &mut IfExpression) {
+  visitor.visit_expression_mut(&mut if_expression.condition);
+  visitor.visit_block_statement_mut(&mut if_expression.true_block);
+
+  if let Some(false_block) = &mut *if_expression.false_block_or_none {
+    visitor.visit_block_statement_mut(false_block);
+  }
+}
+
+pub fn walk_function_literal_mut<V: VisitorMut + ?Sized>(visitor: &mut V, function_literal: &mut FunctionLiteral) {
+  for argument in &mut function_literal.arguments {
+    visitor.visit_identifier_mut(argument);
+  }
+
+  visitor.visit_block_statement_mut(&mut function_literal.body);
+}
+
+pub fn walk_macro_literal_mut<V: VisitorMut + ?Sized>(visitor: &mut V, macro_literal: &mut MacroLiteral) {
+  for argument in &mut macro_literal.arguments {
+    visitor.visit_identifier_mut(argument);
+  }
+
+  visitor.visit_block_statement_mut(&mut macro_literal.body);
+}
+
+pub fn walk_call_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, call_expression: &mut CallExpression) {
+  visitor.visit_expression_mut(&mut call_expression.function);
+
+  for argument in call_expression.arguments.iter_mut() {
+    visitor.visit_expression_mut(argument);
+  }
+}
+
+pub fn walk_array_literal_mut<V: VisitorMut + ?Sized>(visitor: &mut V, array_literal: &mut ArrayLiteral) {
+  for element in &mut array_literal.elements {
+    visitor.visit_expression_mut(element);
+  }
+}
+
+pub fn walk_index_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, index_expression: &mut IndexExpression) {
+  visitor.visit_expression_mut(&mut index_expression.left);
+  visitor.visit_expression_mut(&mut index_expression.index);
+}
+
+pub fn walk_slice_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, slice_expression: &mut SliceExpression) {
+  visitor.visit_expression_mut(&mut slice_expression.left);
+
+  for bound in slice_expression.start.iter_mut().chain(slice_expression.end.iter_mut()) {
+    visitor.visit_expression_mut(bound);
+  }
+}
+
+pub fn walk_hash_literal_mut<V: VisitorMut + ?Sized>(visitor: &mut V, hash_literal: &mut HashLiteral) {
+  for (key, value) in &mut hash_literal.pairs {
+    visitor.visit_expression_mut(key);
+    visitor.visit_expression_mut(value);
+  }
+}
+
+pub fn walk_assign_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, assign_expression: &mut AssignExpression) {
+  visitor.visit_identifier_mut(&mut assign_expression.name);
+  visitor.visit_expression_mut(&mut assign_expression.value);
+}
+
+pub fn walk_while_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, while_expression: &mut WhileExpression) {
+  visitor.visit_expression_mut(&mut while_expression.condition);
+  visitor.visit_block_statement_mut(&mut while_expression.body);
+}
+
+pub fn walk_for_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, for_expression: &mut ForExpression) {
+  visitor.visit_identifier_mut(&mut for_expression.variable);
+  visitor.visit_expression_mut(&mut for_expression.iterable);
+  visitor.visit_block_statement_mut(&mut for_expression.body);
+}
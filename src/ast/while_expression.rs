@@ -0,0 +1,29 @@
+use crate::ast::BlockStatement;
+use crate::ast::Expression;
+use crate::token::*;
+
+#[derive(Debug, Clone)]
+pub struct WhileExpression {
+  pub token: Token,
+  pub span: Span,
+  pub condition: Box<Expression>,
+  pub body: Box<BlockStatement>,
+}
+
+impl WhileExpression {
+  pub fn token_literal(&self) -> Literal {
+    self.token.literal.clone()
+  }
+
+  pub fn to_string(&self) -> String {
+    let mut string = String::new();
+
+    string.push_str("while ");
+    string.push('(');
+    string.push_str(&self.condition.to_string());
+    string.push_str(") ");
+    string.push_str(&self.body.to_string());
+
+    string
+  }
+}
@@ -0,0 +1,496 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+use crate::eval::eval_error::EvalError;
+use crate::eval::{eval_infix_expression, eval_iterable, get_iteration_item};
+use crate::object::*;
+use crate::object::big_integer::BigInteger;
+use crate::token::*;
+
+// Calls a user function from inside a builtin. Each engine passes its own way of calling
+// functions, so callbacks behave exactly like calls written in the script.
+pub type CallFunction<'a> = dyn FnMut(Object, Vec<Object>) -> Result<Object, EvalError> + 'a;
+
+pub type BuiltinFunction = fn(Vec<Object>, &mut CallFunction) -> Result<Object, EvalError>;
+
+// The longest string, in bytes, that builtins will build
+const MAX_STRING_LENGTH: usize = 1 << 30;
+
+pub const BUILTINS: [(&str, BuiltinFunction); 31] = [
+  ("len", len),
+  ("puts", puts),
+  ("first", first),
+  ("last", last),
+  ("rest", rest),
+  ("push", push),
+  ("type", type_of),
+  ("split", split),
+  ("join", join),
+  ("trim", trim),
+  ("upper", upper),
+  ("lower", lower),
+  ("replace", replace),
+  ("contains", contains),
+  ("starts_with", starts_with),
+  ("ends_with", ends_with),
+  ("index_of", index_of),
+  ("chars", chars),
+  ("repeat", repeat),
+  ("map", map),
+  ("filter", filter),
+  ("reduce", reduce),
+  ("each", each),
+  ("sort", sort),
+  ("zip", zip),
+  ("enumerate", enumerate),
+  ("any", any),
+  ("all", all),
+  ("sum", sum),
+  ("min", min),
+  ("max", max),
+];
+
+pub fn get_builtin(name: &str) -> Option<Object> {
+  BUILTINS
+    .iter()
+    .find(|(builtin_name, _)| *builtin_name == name)
+    .map(|(builtin_name, function)| Object::Builtin(builtin_name, *function))
+}
+
+fn check_argument_count(name: &str, arguments: &[Object], expected: usize) -> Result<(), EvalError> {
+  if arguments.len() == expected {
+    return Ok(())
+  }
+
+  let noun = if expected == 1 { "argument" } else { "arguments" };
+  Err(EvalError::new(format!("Expected {} {} to {}, got {}", expected, noun, name, arguments.len())))
+}
+
+fn unsupported_argument(name: &str, expected: &str, argument: &Object) -> EvalError {
+  EvalError::new(format!("Argument to {} must be {}, got {}", name, expected, argument.type_name()))
+}
+
+fn get_string<'a>(name: &str, argument: &'a Object) -> Result<&'a str, EvalError> {
+  match argument {
+    Object::String(string) => Ok(string),
+    x => Err(unsupported_argument(name, "String", x)),
+  }
+}
+
+// Collections are anything a `for` loop can iterate over. Like the loop, this gets one element at
+// a time, so builtins that stop early, like `any`, never go through all of a huge range.
+fn iterate(name: &str, argument: &Object) -> Result<impl Iterator<Item = Object>, EvalError> {
+  let iterable = eval_iterable(argument.clone()).map_err(|_| unsupported_argument(name, "iterable", argument))?;
+
+  Ok((0..).map_while(move |index| get_iteration_item(&iterable, index)))
+}
+
+fn len(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("len", &arguments, 1)?;
+
+  match &arguments[0] {
+    Object::String(string) => Ok(Object::Integer(string.chars().count() as i64)),
+    Object::Array(elements) => Ok(Object::Integer(elements.len() as i64)),
+    Object::Hash(hash) => Ok(Object::Integer(hash.pairs().len() as i64)),
+    x => Err(unsupported_argument("len", "String, Array or Hash", x)),
+  }
+}
+
+fn puts(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  for argument in &arguments {
+    println!("{}", argument.inspect());
+  }
+
+  Ok(Object::Null)
+}
+
+fn first(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("first", &arguments, 1)?;
+
+  match &arguments[0] {
+    Object::Array(elements) => Ok(elements.first().cloned().unwrap_or(Object::Null)),
+    x => Err(unsupported_argument("first", "Array", x)),
+  }
+}
+
+fn last(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("last", &arguments, 1)?;
+
+  match &arguments[0] {
+    Object::Array(elements) => Ok(elements.last().cloned().unwrap_or(Object::Null)),
+    x => Err(unsupported_argument("last", "Array", x)),
+  }
+}
+
+fn rest(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("rest", &arguments, 1)?;
+
+  match &arguments[0] {
+    Object::Array(elements) if elements.is_empty() => Ok(Object::Null),
+    Object::Array(elements) => Ok(Object::Array(elements[1..].to_vec())),
+    x => Err(unsupported_argument("rest", "Array", x)),
+  }
+}
+
+fn push(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("push", &arguments, 2)?;
+
+  match &arguments[0] {
+    Object::Array(elements) => {
+      let mut elements = elements.clone();
+      elements.push(arguments[1].clone());
+
+      Ok(Object::Array(elements))
+    },
+    x => Err(unsupported_argument("push", "Array", x)),
+  }
+}
+
+fn type_of(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("type", &arguments, 1)?;
+
+  Ok(Object::String(arguments[0].type_name().to_string()))
+}
+
+fn split(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("split", &arguments, 2)?;
+
+  let string = get_string("split", &arguments[0])?;
+  let separator = get_string("split", &arguments[1])?;
+
+  // An empty separator splits the string into its characters
+  let parts: Vec<Object> = if separator.is_empty() {
+    string.chars().map(|ch| Object::String(ch.to_string())).collect()
+  } else {
+    string.split(separator).map(|part| Object::String(part.to_string())).collect()
+  };
+
+  Ok(Object::Array(parts))
+}
+
+fn join(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("join", &arguments, 2)?;
+
+  let elements = match &arguments[0] {
+    Object::Array(elements) => elements,
+    x => return Err(unsupported_argument("join", "Array", x)),
+  };
+  let separator = get_string("join", &arguments[1])?;
+  let parts = elements
+    .iter()
+    .map(|element| get_string("join", element))
+    .collect::<Result<Vec<&str>, EvalError>>()?;
+
+  Ok(Object::String(parts.join(separator)))
+}
+
+fn trim(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("trim", &arguments, 1)?;
+
+  Ok(Object::String(get_string("trim", &arguments[0])?.trim().to_string()))
+}
+
+fn upper(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("upper", &arguments, 1)?;
+
+  Ok(Object::String(get_string("upper", &arguments[0])?.to_uppercase()))
+}
+
+fn lower(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("lower", &arguments, 1)?;
+
+  Ok(Object::String(get_string("lower", &arguments[0])?.to_lowercase()))
+}
+
+fn replace(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("replace", &arguments, 3)?;
+
+  let string = get_string("replace", &arguments[0])?;
+  let from = get_string("replace", &arguments[1])?;
+  let to = get_string("replace", &arguments[2])?;
+
+  if from.is_empty() {
+    return Err(EvalError::new("Cannot replace an empty string".to_string()))
+  }
+
+  Ok(Object::String(string.replace(from, to)))
+}
+
+fn contains(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("contains", &arguments, 2)?;
+
+  let string = get_string("contains", &arguments[0])?;
+  let substring = get_string("contains", &arguments[1])?;
+
+  Ok(Object::Boolean(string.contains(substring)))
+}
+
+fn starts_with(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("starts_with", &arguments, 2)?;
+
+  let string = get_string("starts_with", &arguments[0])?;
+  let prefix = get_string("starts_with", &arguments[1])?;
+
+  Ok(Object::Boolean(string.starts_with(prefix)))
+}
+
+fn ends_with(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("ends_with", &arguments, 2)?;
+
+  let string = get_string("ends_with", &arguments[0])?;
+  let suffix = get_string("ends_with", &arguments[1])?;
+
+  Ok(Object::Boolean(string.ends_with(suffix)))
+}
+
+// Returns the index in characters, like string indexing expects, rather than in bytes
+fn index_of(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("index_of", &arguments, 2)?;
+
+  let string = get_string("index_of", &arguments[0])?;
+  let substring = get_string("index_of", &arguments[1])?;
+
+  match string.find(substring) {
+    Some(byte_index) => Ok(Object::Integer(string[..byte_index].chars().count() as i64)),
+    None => Ok(Object::Null),
+  }
+}
+
+fn chars(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("chars", &arguments, 1)?;
+
+  let string = get_string("chars", &arguments[0])?;
+
+  Ok(Object::Array(string.chars().map(|ch| Object::String(ch.to_string())).collect()))
+}
+
+fn repeat(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("repeat", &arguments, 2)?;
+
+  let string = get_string("repeat", &arguments[0])?;
+
+  let negative_count_error = || {
+    EvalError::new(format!("Cannot repeat a string a negative number of times: {}", arguments[1].inspect()))
+  };
+
+  // Big integers are always too large a count, unless there is nothing to repeat
+  let count = match &arguments[1] {
+    Object::Integer(count) if *count < 0 => return Err(negative_count_error()),
+    Object::BigInteger(count) if *count < BigInteger::from(0) => return Err(negative_count_error()),
+    Object::Integer(count) => usize::try_from(*count).ok(),
+    Object::BigInteger(_) => None,
+    x => return Err(unsupported_argument("repeat", "Integer", x)),
+  };
+
+  match count.and_then(|count| Some((count, string.len().checked_mul(count)?))) {
+    Some((count, length)) if length <= MAX_STRING_LENGTH => Ok(Object::String(string.repeat(count))),
+    _ if string.is_empty() => Ok(Object::String(String::new())),
+    _ => Err(EvalError::new(format!(
+      "Cannot repeat a string {} times, the result would be longer than {} bytes",
+      arguments[1].inspect(),
+      MAX_STRING_LENGTH
+    ))),
+  }
+}
+
+fn map(arguments: Vec<Object>, call: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("map", &arguments, 2)?;
+
+  let mut results = vec![];
+
+  for element in iterate("map", &arguments[0])? {
+    results.push(call(arguments[1].clone(), vec![element])?);
+  }
+
+  Ok(Object::Array(results))
+}
+
+fn filter(arguments: Vec<Object>, call: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("filter", &arguments, 2)?;
+
+  let mut results = vec![];
+
+  for element in iterate("filter", &arguments[0])? {
+    if *call(arguments[1].clone(), vec![element.clone()])?.get_is_truthy() {
+      results.push(element);
+    }
+  }
+
+  Ok(Object::Array(results))
+}
+
+fn reduce(arguments: Vec<Object>, call: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("reduce", &arguments, 3)?;
+
+  let mut accumulator = arguments[1].clone();
+
+  for element in iterate("reduce", &arguments[0])? {
+    accumulator = call(arguments[2].clone(), vec![accumulator, element])?;
+  }
+
+  Ok(accumulator)
+}
+
+fn each(arguments: Vec<Object>, call: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("each", &arguments, 2)?;
+
+  for element in iterate("each", &arguments[0])? {
+    call(arguments[1].clone(), vec![element])?;
+  }
+
+  Ok(Object::Null)
+}
+
+// Sorts with `<` and `>` by default. A comparator gets two elements and returns a negative number,
+// zero or a positive number, like the ordering of the first element relative to the second.
+fn sort(arguments: Vec<Object>, call: &mut CallFunction) -> Result<Object, EvalError> {
+  if arguments.len() != 2 {
+    check_argument_count("sort", &arguments, 1)?;
+  }
+
+  let elements: Vec<Object> = iterate("sort", &arguments[0])?.collect();
+  let comparator = arguments.get(1);
+
+  let sorted = merge_sort(elements, &mut |a, b| match comparator {
+    Some(comparator) => match call(comparator.clone(), vec![a.clone(), b.clone()])? {
+      Object::Integer(integer) => Ok(integer.cmp(&0)),
+      Object::BigInteger(integer) => Ok(integer.cmp(&BigInteger::from(0))),
+      Object::Float(float) => Ok(float.partial_cmp(&0.0).unwrap_or(Ordering::Equal)),
+      x => Err(EvalError::new(format!("Comparator must return a number, got {}", x.type_name()))),
+    },
+    None => compare(a, b),
+  })?;
+
+  Ok(Object::Array(sorted))
+}
+
+// A stable merge sort that stops at the first error of the comparison. Scripts can pass comparators
+// that are not a consistent order, which still give some ordering of the elements here.
+fn merge_sort(
+  mut elements: Vec<Object>,
+  compare: &mut dyn FnMut(&Object, &Object) -> Result<Ordering, EvalError>,
+) -> Result<Vec<Object>, EvalError> {
+  if elements.len() < 2 {
+    return Ok(elements)
+  }
+
+  let right = elements.split_off(elements.len() / 2);
+  let mut left = merge_sort(elements, compare)?.into_iter().peekable();
+  let mut right = merge_sort(right, compare)?.into_iter().peekable();
+  let mut merged = Vec::with_capacity(left.len() + right.len());
+
+  while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+    // Equal elements keep their order, so the right one is only taken when it comes first
+    let next = if compare(b, a)? == Ordering::Less { right.next() } else { left.next() };
+    merged.extend(next);
+  }
+
+  merged.extend(left);
+  merged.extend(right);
+
+  Ok(merged)
+}
+
+fn zip(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("zip", &arguments, 2)?;
+
+  let left = iterate("zip", &arguments[0])?;
+  let right = iterate("zip", &arguments[1])?;
+  let pairs = left.zip(right).map(|(a, b)| Object::Array(vec![a, b])).collect();
+
+  Ok(Object::Array(pairs))
+}
+
+fn enumerate(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("enumerate", &arguments, 1)?;
+
+  let pairs = iterate("enumerate", &arguments[0])?
+    .enumerate()
+    .map(|(index, element)| Object::Array(vec![Object::Integer(index as i64), element]))
+    .collect();
+
+  Ok(Object::Array(pairs))
+}
+
+fn any(arguments: Vec<Object>, call: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("any", &arguments, 2)?;
+
+  for element in iterate("any", &arguments[0])? {
+    if *call(arguments[1].clone(), vec![element])?.get_is_truthy() {
+      return Ok(Object::Boolean(true))
+    }
+  }
+
+  Ok(Object::Boolean(false))
+}
+
+fn all(arguments: Vec<Object>, call: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("all", &arguments, 2)?;
+
+  for element in iterate("all", &arguments[0])? {
+    if !*call(arguments[1].clone(), vec![element])?.get_is_truthy() {
+      return Ok(Object::Boolean(false))
+    }
+  }
+
+  Ok(Object::Boolean(true))
+}
+
+fn sum(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("sum", &arguments, 1)?;
+
+  iterate("sum", &arguments[0])?
+    .try_fold(Object::Integer(0), |total, element| eval_infix_expression(token_types::PLUS, total, element))
+}
+
+fn min(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("min", &arguments, 1)?;
+
+  find_extreme("min", &arguments[0], Ordering::Less)
+}
+
+fn max(arguments: Vec<Object>, _: &mut CallFunction) -> Result<Object, EvalError> {
+  check_argument_count("max", &arguments, 1)?;
+
+  find_extreme("max", &arguments[0], Ordering::Greater)
+}
+
+// Returns the first element that no other element is ordered before, or null for an empty collection
+fn find_extreme(name: &str, argument: &Object, wanted: Ordering) -> Result<Object, EvalError> {
+  let mut result: Option<Object> = None;
+
+  for element in iterate(name, argument)? {
+    result = match result {
+      Some(current) if compare(&element, &current)? != wanted => Some(current),
+      _ => Some(element),
+    };
+  }
+
+  Ok(result.unwrap_or(Object::Null))
+}
+
+// Orders two values the same way the comparison operators do. NaN is neither less nor greater than
+// any number, so it is ordered after all of them instead, to keep the order total.
+fn compare(a: &Object, b: &Object) -> Result<Ordering, EvalError> {
+  let is_nan = |object: &Object| matches!(object, Object::Float(float) if float.is_nan());
+  let is_number = |object: &Object| matches!(object, Object::Integer(_) | Object::BigInteger(_) | Object::Float(_));
+
+  if is_number(a) && is_number(b) {
+    match (is_nan(a), is_nan(b)) {
+      (true, true) => return Ok(Ordering::Equal),
+      (true, false) => return Ok(Ordering::Greater),
+      (false, true) => return Ok(Ordering::Less),
+      (false, false) => {},
+    }
+  }
+
+  if *eval_infix_expression(token_types::LT, a.clone(), b.clone())?.get_is_truthy() {
+    return Ok(Ordering::Less)
+  }
+
+  if *eval_infix_expression(token_types::GT, a.clone(), b.clone())?.get_is_truthy() {
+    return Ok(Ordering::Greater)
+  }
+
+  Ok(Ordering::Equal)
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests;
+
+use crate::engine::Engine;
+use crate::optimizer::Optimization;
+
+pub const USAGE: &str = "Usage:
+  monkey-interpreter [options]                  Start the REPL (or run the program piped to stdin)
+  monkey-interpreter [options] <file>           Run a script file
+  monkey-interpreter [options] -e <source>      Run the given source
+  monkey-interpreter [options] -                Run the program read from stdin
+  monkey-interpreter fmt [--check] [<file>...]  Format script files in place (or stdin to stdout)
+
+Options:
+  --check            With fmt, list the files that are not formatted instead of formatting them
+  --engine=<eval|vm> Run programs with the tree-walking evaluator (the default) or the virtual machine
+  --optimize         Fold constants and remove dead code before running programs
+  --optimize=debug   Optimize and print every change the optimizer made
+  --plain            Print errors without colors (the default when stderr is not a terminal)
+  -h, --help         Show this message";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+  Repl,
+  RunFile(String),
+  RunSource(String),
+  RunStdin,
+  Format { paths: Vec<String>, check: bool },
+  Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Options {
+  pub command: Command,
+  pub plain: bool,
+  pub engine: Engine,
+  pub optimization: Optimization,
+}
+
+pub fn parse_arguments(arguments: &[String]) -> Result<Options, String> {
+  let mut command = None;
+  let mut plain = false;
+  let mut engine = Engine::Eval;
+  let mut optimization = Optimization::Off;
+  let mut remaining = arguments.iter();
+
+  while let Some(argument) = remaining.next() {
+    let next_command = match argument.as_str() {
+      "-h" | "--help" => return Ok(Options { command: Command::Help, plain, engine, optimization }),
+      "--plain" => {
+        plain = true;
+        continue
+      },
+      flag if flag.starts_with("--engine=") => {
+        let name = &flag["--engine=".len()..];
+        engine = Engine::from_name(name).ok_or(format!("Unknown engine: {} (expected eval or vm)", name))?;
+        continue
+      },
+      "--optimize" => {
+        optimization = Optimization::On;
+        continue
+      },
+      "--optimize=debug" => {
+        optimization = Optimization::Debug;
+        continue
+      },
+      "-e" => match remaining.next() {
+        Some(source) => Command::RunSource(source.clone()),
+        None => return Err("Missing source after -e".to_string()),
+      },
+      "-" => Command::RunStdin,
+      "fmt" if command.is_none() => return parse_format_arguments(remaining, plain),
+      flag if flag.starts_with('-') => return Err(format!("Unknown option: {}", flag)),
+      path => Command::RunFile(path.to_string()),
+    };
+
+    if command.is_some() {
+      return Err(format!("Unexpected argument: {}", argument))
+    }
+    command = Some(next_command);
+  }
+
+  Ok(Options { command: command.unwrap_or(Command::Repl), plain, engine, optimization })
+}
+
+// `fmt` takes files to format instead of one to run, so it has options of its own
+fn parse_format_arguments<'a>(arguments: impl Iterator<Item = &'a String>, mut plain: bool) -> Result<Options, String> {
+  let mut paths = vec![];
+  let mut check = false;
+
+  for argument in arguments {
+    match argument.as_str() {
+      "-h" | "--help" => {
+        return Ok(Options { command: Command::Help, plain, engine: Engine::Eval, optimization: Optimization::Off })
+      },
+      "--check" => check = true,
+      "--plain" => plain = true,
+      flag if flag.starts_with('-') => return Err(format!("Unknown option: {}", flag)),
+      path => paths.push(path.to_string()),
+    }
+  }
+
+  Ok(Options { command: Command::Format { paths, check }, plain, engine: Engine::Eval, optimization: Optimization::Off })
+}
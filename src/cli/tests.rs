@@ -0,0 +1,95 @@
+use crate::cli::*;
+use crate::engine::Engine;
+use crate::optimizer::Optimization;
+
+fn arguments(arguments: &[&str]) -> Vec<String> {
+  arguments.iter().map(|argument| argument.to_string()).collect()
+}
+
+#[test]
+fn test_parse_arguments() {
+  let tests = vec![
+    (vec![], Command::Repl),
+    (vec!["script.mk"], Command::RunFile("script.mk".to_string())),
+    (vec!["-e", "puts(1)"], Command::RunSource("puts(1)".to_string())),
+    (vec!["-"], Command::RunStdin),
+    (vec!["--help"], Command::Help),
+    (vec!["script.mk", "-h"], Command::Help),
+  ];
+
+  for (input, expected) in tests {
+    assert_eq!(
+      parse_arguments(&arguments(&input)),
+      Ok(Options { command: expected, plain: false, engine: Engine::Eval, optimization: Optimization::Off })
+    );
+  }
+}
+
+#[test]
+fn test_parse_plain_flag() {
+  let options = parse_arguments(&arguments(&["--plain", "script.mk"]));
+
+  assert_eq!(
+    options,
+    Ok(Options { command: Command::RunFile("script.mk".to_string()), plain: true, engine: Engine::Eval, optimization: Optimization::Off })
+  );
+}
+
+#[test]
+fn test_parse_engine_flag() {
+  let options = parse_arguments(&arguments(&["--engine=vm", "-e", "1"]));
+
+  assert_eq!(
+    options,
+    Ok(Options { command: Command::RunSource("1".to_string()), plain: false, engine: Engine::Vm, optimization: Optimization::Off })
+  );
+}
+
+#[test]
+fn test_parse_optimize_flag() {
+  let tests = vec![
+    (vec!["--optimize", "script.mk"], Optimization::On),
+    (vec!["--optimize=debug", "script.mk"], Optimization::Debug),
+  ];
+
+  for (input, expected) in tests {
+    assert_eq!(
+      parse_arguments(&arguments(&input)),
+      Ok(Options { command: Command::RunFile("script.mk".to_string()), plain: false, engine: Engine::Eval, optimization: expected })
+    );
+  }
+}
+
+#[test]
+fn test_parse_format_command() {
+  let tests = vec![
+    (vec!["fmt"], Command::Format { paths: vec![], check: false }),
+    (vec!["fmt", "a.mk", "b.mk"], Command::Format { paths: vec!["a.mk".to_string(), "b.mk".to_string()], check: false }),
+    (vec!["fmt", "--check", "a.mk"], Command::Format { paths: vec!["a.mk".to_string()], check: true }),
+    (vec!["fmt", "--help"], Command::Help),
+  ];
+
+  for (input, expected) in tests {
+    assert_eq!(
+      parse_arguments(&arguments(&input)),
+      Ok(Options { command: expected, plain: false, engine: Engine::Eval, optimization: Optimization::Off })
+    );
+  }
+}
+
+#[test]
+fn test_parse_arguments_errors() {
+  let tests = vec![
+    (vec!["-e"], "Missing source after -e"),
+    (vec!["--fast"], "Unknown option: --fast"),
+    (vec!["a.mk", "b.mk"], "Unexpected argument: b.mk"),
+    (vec!["--engine=jit"], "Unknown engine: jit (expected eval or vm)"),
+    (vec!["--optimize=fast"], "Unknown option: --optimize=fast"),
+    (vec!["fmt", "--engine=vm"], "Unknown option: --engine=vm"),
+    (vec!["a.mk", "fmt"], "Unexpected argument: fmt"),
+  ];
+
+  for (input, expected) in tests {
+    assert_eq!(parse_arguments(&arguments(&input)), Err(expected.to_string()));
+  }
+}
@@ -0,0 +1,114 @@
+use std::fmt;
+
+use crate::token::Span;
+
+// Instructions are kept as a flat list per function. Operands that refer to other data (constants,
+// scope slots, jump targets) are indices, which keeps every instruction small and `Copy`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Instruction {
+  Constant(usize),
+  Null,
+  True,
+  False,
+  Pop,
+
+  Add,
+  Subtract,
+  Multiply,
+  Divide,
+  Modulo,
+  Power,
+  Equal,
+  NotEqual,
+  LessThan,
+  GreaterThan,
+  LessThanOrEqual,
+  GreaterThanOrEqual,
+  Range,
+  Minus,
+  Bang,
+
+  Jump(usize),
+  JumpIfFalsy(usize),
+  // Used by `&&` and `||`: jumps when the value on top of the stack decides the result, leaving
+  // it on the stack, and pops it otherwise.
+  JumpIfFalsyOrPop(usize),
+  JumpIfTruthyOrPop(usize),
+
+  // Runs the following instructions in a new scope with the given number of slots, as loop
+  // bodies do. Leaving the scope also drops anything the body left on the stack, which makes it
+  // safe to jump out of a body in the middle of an expression.
+  EnterScope(usize),
+  LeaveScope,
+
+  // Replaces the value on top of the stack with an iterable (see `eval_iterable`) followed by
+  // the index of the next item.
+  PrepareIteration,
+  // Pushes the next item of the iteration below the current scope, or jumps to the target when
+  // there are no more items.
+  Iterate(usize),
+
+  // Reads a name through the lookup with the given index, see `Lookup`.
+  GetName(usize),
+  // Assigns the value on top of the stack to the first bound slot of a lookup, leaving the value
+  // on the stack.
+  SetName(usize),
+  // Binds the value on top of the stack to a slot of the current scope. The second operand is
+  // the index of the name, used for error messages.
+  Define(usize, usize),
+  DefineConstant(usize, usize),
+
+  Array(usize),
+  Hash(usize),
+  // Joins the given number of values on top of the stack into one string, see `interpolate`.
+  Interpolate(usize),
+  Index,
+  // Pops the end and start bounds, which are null when missing, and the value to slice.
+  Slice,
+  // Builds the quoted expression in the constant, splicing in the given number of values from the
+  // stack in place of the `unquote` calls, see `quote`.
+  Quote(usize, usize),
+
+  Closure(usize),
+  Call(usize),
+  ReturnValue,
+}
+
+// Names are resolved at compile time to the slots of every enclosing scope that declares them,
+// innermost first. At runtime the first slot that has been bound wins, and builtins are consulted
+// when none has, which mirrors how the tree-walker walks its environment chain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lookup {
+  pub name: String,
+  // (number of scopes to walk up, slot index)
+  pub candidates: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CompiledFunction {
+  pub instructions: Vec<Instruction>,
+  // The source span each instruction was compiled from, used to locate runtime errors.
+  pub spans: Vec<Span>,
+  pub parameters: Vec<String>,
+  pub slot_count: usize,
+  pub names: Vec<String>,
+  pub lookups: Vec<Lookup>,
+}
+
+impl fmt::Display for CompiledFunction {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (index, instruction) in self.instructions.iter().enumerate() {
+      match instruction {
+        Instruction::GetName(lookup) | Instruction::SetName(lookup) => {
+          writeln!(f, "{:04} {:?} ; {}", index, instruction, self.lookups[*lookup].name)?
+        },
+        Instruction::Define(_, name) | Instruction::DefineConstant(_, name) => {
+          writeln!(f, "{:04} {:?} ; {}", index, instruction, self.names[*name])?
+        },
+        _ => writeln!(f, "{:04} {:?}", index, instruction)?,
+      }
+    }
+
+    Ok(())
+  }
+}
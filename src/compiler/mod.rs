@@ -0,0 +1,527 @@
+pub mod symbol_table;
+
+#[cfg(test)]
+mod tests;
+
+use std::rc::Rc;
+
+use crate::ast::*;
+use crate::ast::visitor::*;
+use crate::code::*;
+use crate::eval::eval_error::EvalError;
+use crate::eval::macro_expansion::*;
+use crate::object::*;
+use crate::token::*;
+
+use block_statement::BlockStatement;
+use function_literal::FunctionLiteral;
+use if_expression::IfExpression;
+use infix_expression::InfixExpression;
+use interpolated_string::StringPart;
+use let_statement::LetStatement;
+use macro_literal::MacroLiteral;
+use for_expression::ForExpression;
+use symbol_table::*;
+use while_expression::WhileExpression;
+
+pub struct Bytecode {
+  pub main: Rc<CompiledFunction>,
+  pub constants: Vec<Object>,
+}
+
+// Lowers a program into bytecode. The global symbol table and the constant pool survive between
+// calls to `compile`, so the REPL can keep compiling new lines against the same globals.
+pub struct Compiler {
+  constants: Vec<Object>,
+  symbol_table: SymbolTable,
+  functions: Vec<CompiledFunction>,
+  loops: Vec<Loop>,
+}
+
+// The jumps of `break` statements can only be patched once the end of the loop is known
+struct Loop {
+  start: usize,
+  breaks: Vec<usize>,
+}
+
+impl Compiler {
+  pub fn new() -> Self {
+    Compiler {
+      constants: vec![],
+      symbol_table: SymbolTable::new(),
+      functions: vec![],
+      loops: vec![],
+    }
+  }
+
+  pub fn compile(&mut self, program: &Program) -> Result<Bytecode, EvalError> {
+    self.functions = vec![CompiledFunction::default()];
+
+    self.symbol_table.declare_all(&collect_declarations(&program.statements));
+    self.compile_statements(&program.statements, Span::default())?;
+
+    let mut main = self.functions.pop().unwrap();
+    main.slot_count = self.symbol_table.slot_count();
+
+    Ok(Bytecode {
+      main: Rc::new(main),
+      constants: self.constants.clone(),
+    })
+  }
+
+  // Every statement leaves exactly one value on the stack. All but the last are popped, so a
+  // list of statements evaluates to the value of its last statement, like a `BlockStatement`.
+  fn compile_statements(&mut self, statements: &[Statement], span: Span) -> Result<(), EvalError> {
+    if statements.is_empty() {
+      self.emit(Instruction::Null, span);
+    }
+
+    for (index, statement) in statements.iter().enumerate() {
+      if index > 0 {
+        self.emit(Instruction::Pop, statement.span());
+      }
+
+      self.compile_statement(statement)?;
+    }
+
+    Ok(())
+  }
+
+  fn compile_block(&mut self, block: &BlockStatement) -> Result<(), EvalError> {
+    self.compile_statements(&block.statements, block.span)
+  }
+
+  fn compile_statement(&mut self, statement: &Statement) -> Result<(), EvalError> {
+    match statement {
+      Statement::LetStatement(let_statement) => {
+        self.compile_expression(&let_statement.value)?;
+
+        let name = &let_statement.name.value;
+        let slot = self.symbol_table.resolve_local(name).unwrap();
+        let name_index = self.add_name(name);
+
+        let instruction = if let_statement.is_constant() {
+          Instruction::DefineConstant(slot, name_index)
+        } else {
+          Instruction::Define(slot, name_index)
+        };
+        self.emit(instruction, let_statement.span);
+        self.emit(Instruction::Null, let_statement.span);
+      },
+      Statement::ReturnStatement(return_statement) => {
+        self.compile_expression(&return_statement.return_value)?;
+        self.emit(Instruction::ReturnValue, return_statement.span);
+      },
+      Statement::Expression(expression) => self.compile_expression(expression)?,
+      Statement::BlockStatement(block_statement) => self.compile_block(block_statement)?,
+      Statement::BreakStatement(break_statement) => {
+        let span = break_statement.span;
+
+        self.emit(Instruction::LeaveScope, span);
+        let jump = self.emit(Instruction::Jump(0), span);
+        self.current_loop(span)?.breaks.push(jump);
+      },
+      Statement::ContinueStatement(continue_statement) => {
+        let span = continue_statement.span;
+        let start = self.current_loop(span)?.start;
+
+        self.emit(Instruction::LeaveScope, span);
+        self.emit(Instruction::Jump(start), span);
+      },
+    }
+
+    Ok(())
+  }
+
+  fn compile_expression(&mut self, expression: &Expression) -> Result<(), EvalError> {
+    let span = expression.span();
+
+    match expression {
+      Expression::IntegerLiteral(integer_literal) => {
+        let index = self.add_constant(Object::Integer(integer_literal.value));
+        self.emit(Instruction::Constant(index), span);
+      },
+      Expression::BigIntegerLiteral(big_integer_literal) => {
+        let index = self.add_constant(Object::BigInteger(big_integer_literal.value.clone()));
+        self.emit(Instruction::Constant(index), span);
+      },
+      Expression::FloatLiteral(float_literal) => {
+        let index = self.add_constant(Object::Float(float_literal.value));
+        self.emit(Instruction::Constant(index), span);
+      },
+      Expression::StringLiteral(string_literal) => {
+        let index = self.add_constant(Object::String(string_literal.value.clone()));
+        self.emit(Instruction::Constant(index), span);
+      },
+      Expression::BooleanLiteral(boolean_literal) => {
+        let instruction = if boolean_literal.value { Instruction::True } else { Instruction::False };
+        self.emit(instruction, span);
+      },
+      Expression::Identifier(identifier) => {
+        let lookup = self.symbol_table.resolve(&identifier.value);
+        let index = self.add_lookup(lookup);
+        self.emit(Instruction::GetName(index), span);
+      },
+      Expression::AssignExpression(assign_expression) => {
+        self.compile_expression(&assign_expression.value)?;
+
+        let lookup = self.symbol_table.resolve(&assign_expression.name.value);
+        let index = self.add_lookup(lookup);
+        self.emit(Instruction::SetName(index), span);
+      },
+      Expression::PrefixExpression(prefix_expression) => {
+        self.compile_expression(&prefix_expression.right)?;
+
+        let instruction = match prefix_expression.operator.as_str() {
+          token_types::MINUS => Instruction::Minus,
+          token_types::BANG => Instruction::Bang,
+          x => return Err(EvalError::not_implemented(&format!("PrefixExpression for operator: {}", x))),
+        };
+        self.emit(instruction, span);
+      },
+      Expression::InfixExpression(infix_expression) if is_logical_operator(&infix_expression.operator) => {
+        self.compile_logical_expression(infix_expression)?
+      },
+      Expression::InfixExpression(infix_expression) => {
+        self.compile_expression(&infix_expression.left)?;
+        self.compile_expression(&infix_expression.right)?;
+
+        let instruction = match infix_expression.operator.as_str() {
+          token_types::PLUS => Instruction::Add,
+          token_types::MINUS => Instruction::Subtract,
+          token_types::ASTERISK => Instruction::Multiply,
+          token_types::SLASH => Instruction::Divide,
+          token_types::PERCENT => Instruction::Modulo,
+          token_types::POWER => Instruction::Power,
+          token_types::EQ => Instruction::Equal,
+          token_types::NOT_EQ => Instruction::NotEqual,
+          token_types::LT => Instruction::LessThan,
+          token_types::GT => Instruction::GreaterThan,
+          token_types::LT_EQ => Instruction::LessThanOrEqual,
+          token_types::GT_EQ => Instruction::GreaterThanOrEqual,
+          token_types::RANGE => Instruction::Range,
+          x => return Err(EvalError::new(format!("Unknown operator: {}", x))),
+        };
+        self.emit(instruction, span);
+      },
+      Expression::IfExpression(if_expression) => self.compile_if_expression(if_expression)?,
+      Expression::WhileExpression(while_expression) => self.compile_while_expression(while_expression)?,
+      Expression::ForExpression(for_expression) => self.compile_for_expression(for_expression)?,
+      Expression::FunctionLiteral(function_literal) => self.compile_function_literal(function_literal)?,
+      Expression::MacroLiteral(_) => return Err(misplaced_macro_error().with_span_or_keep(span)),
+      Expression::CallExpression(call_expression) => {
+        if let Some(arguments) = get_special_form_arguments(call_expression, QUOTE) {
+          let expression = get_quoted_expression(arguments).map_err(|error| error.with_span_or_keep(span))?;
+          let unquoted_expressions = get_unquoted_expressions(expression);
+
+          for unquoted_expression in &unquoted_expressions {
+            self.compile_expression(unquoted_expression)?;
+          }
+
+          let index = self.add_constant(Object::Quote(Box::new(expression.clone())));
+          self.emit(Instruction::Quote(index, unquoted_expressions.len()), span);
+          return Ok(())
+        }
+
+        self.compile_expression(&call_expression.function)?;
+
+        for argument in call_expression.arguments.iter() {
+          self.compile_expression(argument)?;
+        }
+
+        self.emit(Instruction::Call(call_expression.arguments.len()), span);
+      },
+      Expression::InterpolatedString(interpolated_string) => {
+        for part in &interpolated_string.parts {
+          match part {
+            StringPart::Literal(string) => {
+              let index = self.add_constant(Object::String(string.clone()));
+              self.emit(Instruction::Constant(index), span);
+            },
+            StringPart::Expression(expression) => self.compile_expression(expression)?,
+          }
+        }
+
+        self.emit(Instruction::Interpolate(interpolated_string.parts.len()), span);
+      },
+      Expression::ArrayLiteral(array_literal) => {
+        for element in &array_literal.elements {
+          self.compile_expression(element)?;
+        }
+
+        self.emit(Instruction::Array(array_literal.elements.len()), span);
+      },
+      Expression::HashLiteral(hash_literal) => {
+        for (key, value) in &hash_literal.pairs {
+          self.compile_expression(key)?;
+          self.compile_expression(value)?;
+        }
+
+        self.emit(Instruction::Hash(hash_literal.pairs.len()), span);
+      },
+      Expression::IndexExpression(index_expression) => {
+        self.compile_expression(&index_expression.left)?;
+        self.compile_expression(&index_expression.index)?;
+        self.emit(Instruction::Index, span);
+      },
+      Expression::SliceExpression(slice_expression) => {
+        self.compile_expression(&slice_expression.left)?;
+
+        // Missing bounds are passed as null
+        for bound in [&slice_expression.start, &slice_expression.end].iter() {
+          match bound {
+            Some(bound) => self.compile_expression(bound)?,
+            None => {
+              self.emit(Instruction::Null, span);
+            },
+          }
+        }
+
+        self.emit(Instruction::Slice, span);
+      },
+    }
+
+    Ok(())
+  }
+
+  fn compile_logical_expression(&mut self, infix_expression: &InfixExpression) -> Result<(), EvalError> {
+    self.compile_expression(&infix_expression.left)?;
+    let jump = self.emit(Instruction::Jump(0), infix_expression.span);
+
+    self.compile_expression(&infix_expression.right)?;
+
+    let end = self.current_position();
+    let instruction = if infix_expression.operator == token_types::AND {
+      Instruction::JumpIfFalsyOrPop(end)
+    } else {
+      Instruction::JumpIfTruthyOrPop(end)
+    };
+    self.replace_instruction(jump, instruction);
+
+    Ok(())
+  }
+
+  fn compile_if_expression(&mut self, if_expression: &IfExpression) -> Result<(), EvalError> {
+    self.compile_expression(&if_expression.condition)?;
+
+    let jump_if_falsy = self.emit(Instruction::JumpIfFalsy(0), if_expression.span);
+    self.compile_block(&if_expression.true_block)?;
+    let jump = self.emit(Instruction::Jump(0), if_expression.span);
+
+    let false_block_start = self.current_position();
+    self.replace_instruction(jump_if_falsy, Instruction::JumpIfFalsy(false_block_start));
+
+    match &*if_expression.false_block_or_none {
+      Some(false_block) => self.compile_block(false_block)?,
+      None => {
+        self.emit(Instruction::Null, if_expression.span);
+      },
+    }
+
+    let end = self.current_position();
+    self.replace_instruction(jump, Instruction::Jump(end));
+
+    Ok(())
+  }
+
+  fn compile_while_expression(&mut self, while_expression: &WhileExpression) -> Result<(), EvalError> {
+    let span = while_expression.span;
+    let start = self.current_position();
+
+    self.compile_expression(&while_expression.condition)?;
+    let jump_if_falsy = self.emit(Instruction::JumpIfFalsy(0), span);
+
+    self.symbol_table.enter_scope();
+    self.symbol_table.declare_all(&collect_declarations(&while_expression.body.statements));
+    let enter_scope = self.emit(Instruction::EnterScope(0), span);
+
+    self.loops.push(Loop { start, breaks: vec![] });
+    self.compile_block(&while_expression.body)?;
+    let body_loop = self.loops.pop().unwrap();
+
+    let slot_count = self.symbol_table.slot_count();
+    self.symbol_table.leave_scope();
+    self.replace_instruction(enter_scope, Instruction::EnterScope(slot_count));
+
+    self.emit(Instruction::LeaveScope, span);
+    self.emit(Instruction::Jump(start), span);
+
+    let end = self.current_position();
+    self.replace_instruction(jump_if_falsy, Instruction::JumpIfFalsy(end));
+    for jump in body_loop.breaks {
+      self.replace_instruction(jump, Instruction::Jump(end));
+    }
+
+    self.emit(Instruction::Null, span);
+
+    Ok(())
+  }
+
+  // The iterable and the index of the next item stay on the stack while the loop runs. Unlike in a
+  // `while` loop, the scope is entered before checking whether there is another item, as the
+  // item is bound in the scope of the iteration.
+  fn compile_for_expression(&mut self, for_expression: &ForExpression) -> Result<(), EvalError> {
+    let span = for_expression.span;
+
+    self.compile_expression(&for_expression.iterable)?;
+    self.emit(Instruction::PrepareIteration, span);
+
+    let variable = &for_expression.variable.value;
+    self.symbol_table.enter_scope();
+    self.symbol_table.declare(variable);
+    self.symbol_table.declare_all(&collect_declarations(&for_expression.body.statements));
+
+    let start = self.emit(Instruction::EnterScope(0), span);
+    let iterate = self.emit(Instruction::Iterate(0), span);
+    let name_index = self.add_name(variable);
+    self.emit(Instruction::Define(0, name_index), for_expression.variable.span);
+
+    self.loops.push(Loop { start, breaks: vec![] });
+    self.compile_block(&for_expression.body)?;
+    let body_loop = self.loops.pop().unwrap();
+
+    let slot_count = self.symbol_table.slot_count();
+    self.symbol_table.leave_scope();
+    self.replace_instruction(start, Instruction::EnterScope(slot_count));
+
+    self.emit(Instruction::LeaveScope, span);
+    self.emit(Instruction::Jump(start), span);
+
+    let exhausted = self.emit(Instruction::LeaveScope, span);
+    self.replace_instruction(iterate, Instruction::Iterate(exhausted));
+
+    let end = self.current_position();
+    for jump in body_loop.breaks {
+      self.replace_instruction(jump, Instruction::Jump(end));
+    }
+
+    self.emit(Instruction::Pop, span);
+    self.emit(Instruction::Pop, span);
+    self.emit(Instruction::Null, span);
+
+    Ok(())
+  }
+
+  fn compile_function_literal(&mut self, function_literal: &FunctionLiteral) -> Result<(), EvalError> {
+    let parameters: Vec<String> = function_literal
+      .arguments
+      .iter()
+      .map(|argument| argument.value.clone())
+      .collect();
+
+    self.symbol_table.enter_scope();
+    self.symbol_table.declare_all(&parameters);
+    self.symbol_table.declare_all(&collect_declarations(&function_literal.body.statements));
+    self.functions.push(CompiledFunction::default());
+
+    self.compile_block(&function_literal.body)?;
+    self.emit(Instruction::ReturnValue, function_literal.body.span);
+
+    let mut function = self.functions.pop().unwrap();
+    function.parameters = parameters;
+    function.slot_count = self.symbol_table.slot_count();
+    self.symbol_table.leave_scope();
+
+    let index = self.add_constant(Object::CompiledFunction(Rc::new(function)));
+    self.emit(Instruction::Closure(index), function_literal.span);
+
+    Ok(())
+  }
+
+  fn current_loop(&mut self, span: Span) -> Result<&mut Loop, EvalError> {
+    let error = || EvalError::new("Loop control statement outside of a loop".to_string()).with_span_or_keep(span);
+
+    self.loops.last_mut().ok_or_else(error)
+  }
+
+  fn current_function(&mut self) -> &mut CompiledFunction {
+    self.functions.last_mut().unwrap()
+  }
+
+  fn current_position(&mut self) -> usize {
+    self.current_function().instructions.len()
+  }
+
+  fn emit(&mut self, instruction: Instruction, span: Span) -> usize {
+    let function = self.current_function();
+
+    function.instructions.push(instruction);
+    function.spans.push(span);
+
+    function.instructions.len() - 1
+  }
+
+  fn replace_instruction(&mut self, position: usize, instruction: Instruction) {
+    self.current_function().instructions[position] = instruction;
+  }
+
+  fn add_constant(&mut self, object: Object) -> usize {
+    self.constants.push(object);
+    self.constants.len() - 1
+  }
+
+  fn add_name(&mut self, name: &str) -> usize {
+    let names = &mut self.current_function().names;
+
+    match names.iter().position(|existing| existing == name) {
+      Some(index) => index,
+      None => {
+        names.push(name.to_string());
+        names.len() - 1
+      },
+    }
+  }
+
+  fn add_lookup(&mut self, lookup: Lookup) -> usize {
+    let lookups = &mut self.current_function().lookups;
+
+    match lookups.iter().position(|existing| *existing == lookup) {
+      Some(index) => index,
+      None => {
+        lookups.push(lookup);
+        lookups.len() - 1
+      },
+    }
+  }
+}
+
+fn is_logical_operator(operator: &str) -> bool {
+  operator == token_types::AND || operator == token_types::OR
+}
+
+// Collects the names bound by `let` anywhere in a scope, including inside `if` blocks, which share
+// the scope of the surrounding function. Function literals and loop bodies are skipped, they get
+// scopes of their own.
+pub fn collect_declarations(statements: &[Statement]) -> Vec<String> {
+  let mut collector = DeclarationCollector { names: vec![] };
+
+  for statement in statements {
+    collector.visit_statement(statement);
+  }
+
+  collector.names
+}
+
+struct DeclarationCollector {
+  names: Vec<String>,
+}
+
+impl Visitor for DeclarationCollector {
+  fn visit_let_statement(&mut self, let_statement: &LetStatement) {
+    self.names.push(let_statement.name.value.clone());
+    self.visit_expression(&let_statement.value);
+  }
+
+  // The body of a loop gets a scope of its own
+  fn visit_while_expression(&mut self, while_expression: &WhileExpression) {
+    self.visit_expression(&while_expression.condition);
+  }
+
+  fn visit_for_expression(&mut self, for_expression: &ForExpression) {
+    self.visit_expression(&for_expression.iterable);
+  }
+
+  fn visit_function_literal(&mut self, _function_literal: &FunctionLiteral) {}
+
+  fn visit_macro_literal(&mut self, _macro_literal: &MacroLiteral) {}
+}
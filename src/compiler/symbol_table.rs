@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use crate::code::Lookup;
+
+// Tracks the slots of every scope enclosing the code being compiled. The first scope holds the
+// globals, each function literal pushes a scope of its own.
+pub struct SymbolTable {
+  scopes: Vec<HashMap<String, usize>>,
+}
+
+impl SymbolTable {
+  pub fn new() -> Self {
+    SymbolTable { scopes: vec![HashMap::new()] }
+  }
+
+  pub fn enter_scope(&mut self) {
+    self.scopes.push(HashMap::new());
+  }
+
+  pub fn leave_scope(&mut self) {
+    self.scopes.pop();
+  }
+
+  pub fn slot_count(&self) -> usize {
+    self.scopes.last().unwrap().len()
+  }
+
+  // Declaring a name twice returns the same slot. Whether a binding is defined twice can only be
+  // known at runtime, as it may depend on which branch of an `if` was taken.
+  pub fn declare(&mut self, name: &str) -> usize {
+    let symbols = self.scopes.last_mut().unwrap();
+    let next_slot = symbols.len();
+
+    *symbols.entry(name.to_string()).or_insert(next_slot)
+  }
+
+  pub fn declare_all(&mut self, names: &[String]) {
+    for name in names {
+      self.declare(name);
+    }
+  }
+
+  pub fn resolve_local(&self, name: &str) -> Option<usize> {
+    self.scopes.last().unwrap().get(name).copied()
+  }
+
+  // Names that no scope declares still get a global slot, as a later line of the REPL (or a
+  // later `let` in the program, for functions that are called after it) may define them.
+  pub fn resolve(&mut self, name: &str) -> Lookup {
+    let mut candidates: Vec<(usize, usize)> = self
+      .scopes
+      .iter()
+      .rev()
+      .enumerate()
+      .filter_map(|(depth, symbols)| symbols.get(name).map(|slot| (depth, *slot)))
+      .collect();
+
+    if !self.scopes[0].contains_key(name) {
+      let next_slot = self.scopes[0].len();
+      self.scopes[0].insert(name.to_string(), next_slot);
+      candidates.push((self.scopes.len() - 1, next_slot));
+    }
+
+    Lookup { name: name.to_string(), candidates }
+  }
+}
@@ -0,0 +1,133 @@
+use crate::code::*;
+use crate::compiler::*;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+
+use Instruction::*;
+
+#[test]
+fn test_expression_instructions() -> Result<(), EvalError> {
+  let tests = vec![
+    ("1 + 2", vec![Constant(0), Constant(1), Add]),
+    ("1; 2", vec![Constant(0), Pop, Constant(1)]),
+    ("-1 * 2", vec![Constant(0), Minus, Constant(1), Multiply]),
+    ("!true == false", vec![True, Bang, False, Equal]),
+    ("[1, 2][0]", vec![Constant(0), Constant(1), Array(2), Constant(2), Index]),
+    ("{1: 2}", vec![Constant(0), Constant(1), Hash(1)]),
+    ("", vec![Null]),
+  ];
+
+  for test in &tests {
+    let (input, expected_instructions) = test;
+    let bytecode = test_compile(input)?;
+
+    assert_eq!(&bytecode.main.instructions, expected_instructions, "input: {}", input);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_conditional_instructions() -> Result<(), EvalError> {
+  let tests = vec![
+    ("if (true) { 10 }", vec![True, JumpIfFalsy(4), Constant(0), Jump(5), Null]),
+    (
+      "if (true) { 10 } else { 20 }",
+      vec![True, JumpIfFalsy(4), Constant(0), Jump(5), Constant(1)],
+    ),
+  ];
+
+  for test in &tests {
+    let (input, expected_instructions) = test;
+    let bytecode = test_compile(input)?;
+
+    assert_eq!(&bytecode.main.instructions, expected_instructions, "input: {}", input);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_loop_instructions() -> Result<(), EvalError> {
+  let bytecode = test_compile("while (true) { let a = 1; break; continue }")?;
+
+  assert_eq!(
+    bytecode.main.instructions,
+    vec![
+      True,
+      JumpIfFalsy(14),
+      EnterScope(1),
+      Constant(0),
+      Define(0, 0),
+      Null,
+      Pop,
+      LeaveScope,
+      Jump(14),
+      Pop,
+      LeaveScope,
+      Jump(0),
+      LeaveScope,
+      Jump(0),
+      Null,
+    ]
+  );
+
+  Ok(())
+}
+
+#[test]
+fn test_let_statements_are_hoisted() -> Result<(), EvalError> {
+  let bytecode = test_compile("a; let a = 1; if (a) { let b = 2 }")?;
+
+  assert_eq!(bytecode.main.slot_count, 2);
+  assert_eq!(bytecode.main.lookups[0], Lookup { name: "a".to_string(), candidates: vec![(0, 0)] });
+  assert_eq!(bytecode.main.instructions[3], Define(0, 0));
+
+  Ok(())
+}
+
+#[test]
+fn test_function_scopes() -> Result<(), EvalError> {
+  let bytecode = test_compile("let a = 1; fn(b) { let a = b; a }")?;
+
+  assert_eq!(bytecode.main.instructions.last(), Some(&Closure(1)));
+
+  let function = match_or_fail!(&bytecode.constants[1], Object::CompiledFunction(m) => m);
+
+  assert_eq!(function.parameters, vec!["b".to_string()]);
+  assert_eq!(function.slot_count, 2);
+  assert_eq!(function.instructions, vec![GetName(0), Define(1, 0), Null, Pop, GetName(1), ReturnValue]);
+  // `b` is not a global, but still gets a global slot, in case a later line of the REPL declares it
+  assert_eq!(function.lookups[0].candidates, vec![(0, 0), (1, 1)]);
+  // The local `a` shadows the global one, but the global is still a candidate until it is bound
+  assert_eq!(function.lookups[1].candidates, vec![(0, 1), (1, 0)]);
+
+  Ok(())
+}
+
+#[test]
+fn test_undeclared_names_get_global_slots() -> Result<(), EvalError> {
+  let mut compiler = Compiler::new();
+
+  let first = compiler.compile(&test_parse("fn() { later }"))?;
+  let function = match_or_fail!(&first.constants[0], Object::CompiledFunction(m) => m);
+  assert_eq!(function.lookups[0].candidates, vec![(1, 0)]);
+
+  // A later compilation, like the next line in the REPL, can declare the name
+  let second = compiler.compile(&test_parse("let later = 1;"))?;
+  assert_eq!(second.main.instructions[1], Define(0, 0));
+  assert_eq!(second.main.slot_count, 1);
+
+  Ok(())
+}
+
+fn test_parse(input: &str) -> Program {
+  let lexer = Lexer::new(input);
+  let mut parser = Parser::new(lexer);
+
+  match_or_fail!(parser.parse_program(), Ok(m) => m)
+}
+
+fn test_compile(input: &str) -> Result<Bytecode, EvalError> {
+  Compiler::new().compile(&test_parse(input))
+}
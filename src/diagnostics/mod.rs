@@ -0,0 +1,191 @@
+#[cfg(test)]
+mod tests;
+
+use crate::eval::eval_error::*;
+use crate::parser::parser_error::*;
+use crate::token::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Style {
+  Color,
+  Plain,
+}
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+// Tabs in source excerpts are printed as this many spaces, so carets line up under them
+const TAB_WIDTH: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+  pub code: &'static str,
+  pub message: String,
+  pub span: Option<Span>,
+  pub help: Vec<String>,
+}
+
+impl Diagnostic {
+  pub fn new(code: &'static str, message: String, span: Option<Span>) -> Self {
+    Diagnostic { code, message, span, help: vec![] }
+  }
+
+  pub fn with_help(mut self, help: String) -> Self {
+    self.help.push(help);
+    self
+  }
+
+  // Renders the diagnostic the way rustc does: a header, the location, the offending source line
+  // with the span underlined, and any help notes.
+  //
+  //   error[E0001]: expected next token to be ), got EOF instead
+  //    --> script.mk:3:14
+  //     |
+  //   3 | let y = add(1,
+  //     |              ^
+  //     |
+  //     = help: the program ended before the expression was complete
+  pub fn render(&self, source: &str, origin: &str, style: Style) -> String {
+    let mut output = format!(
+      "{}{}\n",
+      paint(style, RED, &format!("error[{}]", self.code)),
+      paint(style, BOLD, &format!(": {}", self.message)),
+    );
+
+    let span = match self.span {
+      Some(span) => span,
+      None => {
+        output.push_str(&format!("{} {}\n", paint(style, BLUE, "-->"), origin));
+        self.render_help(&mut output, "", style);
+        return output
+      },
+    };
+
+    let line_number = span.start.line.to_string();
+    let gutter = " ".repeat(line_number.len());
+    let line = source.lines().nth(span.start.line - 1).unwrap_or("");
+
+    output.push_str(&format!("{}{} {}:{}\n", gutter, paint(style, BLUE, "-->"), origin, span.start));
+    output.push_str(&format!("{} {}\n", gutter, paint(style, BLUE, "|")));
+    output.push_str(&format!("{} {}\n", paint(style, BLUE, &format!("{} |", line_number)), line.replace('\t', &" ".repeat(TAB_WIDTH))));
+    output.push_str(&format!(
+      "{} {} {}{}\n",
+      gutter,
+      paint(style, BLUE, "|"),
+      " ".repeat(display_width(line, span.start.column - 1)),
+      paint(style, RED, &"^".repeat(underline_width(span, line))),
+    ));
+
+    self.render_help(&mut output, &gutter, style);
+
+    output
+  }
+
+  fn render_help(&self, output: &mut String, gutter: &str, style: Style) {
+    if self.help.is_empty() {
+      return
+    }
+
+    output.push_str(&format!("{} {}\n", gutter, paint(style, BLUE, "|")));
+
+    for help in &self.help {
+      output.push_str(&format!("{} {} {}\n", gutter, paint(style, BLUE, "="), paint(style, BOLD, &format!("help: {}", help))));
+    }
+  }
+}
+
+fn paint(style: Style, color: &str, text: &str) -> String {
+  match style {
+    Style::Color => format!("{}{}{}", color, text, RESET),
+    Style::Plain => text.to_string(),
+  }
+}
+
+// Spans covering several lines are underlined to the end of their first line, and empty spans
+// (like the one of EOF) still get a single caret.
+fn underline_width(span: Span, line: &str) -> usize {
+  let line_length = line.chars().count();
+
+  let end_column = if span.end.line == span.start.line {
+    span.end.column
+  } else {
+    line_length + 1
+  };
+
+  let width = display_width(line, end_column.saturating_sub(1)).saturating_sub(display_width(line, span.start.column - 1));
+
+  std::cmp::max(1, width)
+}
+
+// How many columns the first `count` characters of a line take up once tabs are expanded.
+// Columns past the end of the line (like the one of EOF) are one wide.
+fn display_width(line: &str, count: usize) -> usize {
+  let width: usize = line.chars().take(count).map(|character| if character == '\t' { TAB_WIDTH } else { 1 }).sum();
+
+  width + count.saturating_sub(line.chars().count())
+}
+
+impl From<&ParserError> for Diagnostic {
+  fn from(error: &ParserError) -> Self {
+    match &error.kind {
+      ParserErrorKind::UnexpectedToken { expected, found } => {
+        let diagnostic = Diagnostic::new("E0001", error.message.clone(), error.span);
+
+        if *found == token_types::EOF {
+          diagnostic.with_help(format!("the program ended early, is a `{}` missing?", expected))
+        } else {
+          diagnostic
+        }
+      },
+      ParserErrorKind::NoPrefixParseFunction(token_type) => {
+        let help = if *token_type == token_types::EOF {
+          "the program ended before the expression was complete".to_string()
+        } else {
+          format!("`{}` cannot be used at the start of an expression", token_type)
+        };
+
+        Diagnostic::new("E0002", error.message.clone(), error.span).with_help(help)
+      },
+      ParserErrorKind::InvalidInteger => Diagnostic::new("E0003", error.message.clone(), error.span),
+      ParserErrorKind::InvalidAssignmentTarget => {
+        Diagnostic::new("E0004", error.message.clone(), error.span)
+          .with_help("only names can be assigned to, like `x = 1`".to_string())
+      },
+      ParserErrorKind::OutsideLoop(_) => {
+        Diagnostic::new("E0005", error.message.clone(), error.span)
+          .with_help("`break` and `continue` can only be used in the body of a loop, not in functions called from it".to_string())
+      },
+      ParserErrorKind::IllegalToken => Diagnostic::new("E0006", error.message.clone(), error.span),
+      ParserErrorKind::Other => Diagnostic::new("E0000", error.message.clone(), error.span),
+    }
+  }
+}
+
+impl From<&EvalError> for Diagnostic {
+  fn from(error: &EvalError) -> Self {
+    match &error.kind {
+      EvalErrorKind::DivisionByZero { .. } => Diagnostic::new("E0101", error.message.clone(), error.span),
+      EvalErrorKind::Other => Diagnostic::new("E0100", error.message.clone(), error.span),
+    }
+  }
+}
+
+pub fn render_parser_errors(errors: &[ParserError], source: &str, origin: &str, style: Style) -> String {
+  let mut output = String::new();
+
+  for error in errors {
+    output.push_str(&Diagnostic::from(error).render(source, origin, style));
+    output.push('\n');
+  }
+
+  let noun = if errors.len() == 1 { "error" } else { "errors" };
+  output.push_str(&format!(
+    "{}{}\n",
+    paint(style, RED, "error"),
+    paint(style, BOLD, &format!(": could not parse {} due to {} previous {}", origin, errors.len(), noun)),
+  ));
+
+  output
+}
@@ -0,0 +1,179 @@
+use crate::diagnostics::*;
+use crate::eval::eval;
+use crate::lexer::Lexer;
+use crate::object::environment::*;
+use crate::parser::Parser;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn render_parse(input: &str) -> String {
+  let lexer = Lexer::new(input);
+  let mut parser = Parser::new(lexer);
+
+  match_or_fail!(parser.parse_program(), Err(_) => ());
+  render_parser_errors(&parser.errors, input, "test.mk", Style::Plain)
+}
+
+fn render_eval(input: &str) -> String {
+  let lexer = Lexer::new(input);
+  let mut parser = Parser::new(lexer);
+
+  let program = match_or_fail!(parser.parse_program(), Ok(m) => m);
+  let env = Rc::new(RefCell::new(Environment::new()));
+  let error = match_or_fail!(eval(&program, &env), Err(m) => m);
+
+  Diagnostic::from(&error).render(input, "test.mk", Style::Plain)
+}
+
+#[test]
+fn test_render_parser_error_at_eof() {
+  let input = "let x = 1;\nlet y = 2;\nlet z = add(1, 2";
+
+  assert_eq!(render_parse(input), "\
+error[E0001]: expected next token to be ,, got EOF instead
+ --> test.mk:3:17
+  |
+3 | let z = add(1, 2
+  |                 ^
+  |
+  = help: the program ended early, is a `,` missing?
+
+error: could not parse test.mk due to 1 previous error
+");
+}
+
+#[test]
+fn test_render_multiple_parser_errors() {
+  let input = "let x 1;\n1 + ;";
+
+  assert_eq!(render_parse(input), "\
+error[E0001]: expected next token to be =, got INT instead
+ --> test.mk:1:7
+  |
+1 | let x 1;
+  |       ^
+
+error[E0002]: no prefix parse function found for ;
+ --> test.mk:2:5
+  |
+2 | 1 + ;
+  |     ^
+  |
+  = help: `;` cannot be used at the start of an expression
+
+error: could not parse test.mk due to 2 previous errors
+");
+}
+
+#[test]
+fn test_render_incomplete_expression() {
+  let input = "let z = add(1,";
+
+  assert_eq!(render_parse(input), "\
+error[E0002]: no prefix parse function found for EOF
+ --> test.mk:1:15
+  |
+1 | let z = add(1,
+  |               ^
+  |
+  = help: the program ended before the expression was complete
+
+error: could not parse test.mk due to 1 previous error
+");
+}
+
+#[test]
+fn test_render_unterminated_string() {
+  let input = "puts(\"hello);";
+
+  assert_eq!(render_parse(input), "\
+error[E0006]: unterminated string
+ --> test.mk:1:6
+  |
+1 | puts(\"hello);
+  |      ^^^^^^^^
+
+error: could not parse test.mk due to 1 previous error
+");
+}
+
+#[test]
+fn test_render_eval_error_underlines_span() {
+  let input = "let a = 1;\nlet b = a + true;";
+
+  assert_eq!(render_eval(input), "\
+error[E0100]: Expected integer, found: Boolean(true)
+ --> test.mk:2:9
+  |
+2 | let b = a + true;
+  |         ^^^^^^^^
+");
+}
+
+#[test]
+fn test_render_multiline_span_underlines_first_line() {
+  let input = "if (true) {\n  1 + true\n} + 1";
+
+  assert_eq!(render_eval(input), "\
+error[E0100]: Expected integer, found: Boolean(true)
+ --> test.mk:2:3
+  |
+2 |   1 + true
+  |   ^^^^^^^^
+");
+}
+
+#[test]
+fn test_render_with_colors() {
+  let diagnostic = Diagnostic::new("E0100", "boom".to_string(), None);
+
+  assert_eq!(
+    diagnostic.render("", "test.mk", Style::Color),
+    "\x1b[1;31merror[E0100]\x1b[0m\x1b[1m: boom\x1b[0m\n\x1b[1;34m-->\x1b[0m test.mk\n"
+  );
+}
+
+#[test]
+fn test_render_invalid_assignment_target() {
+  let output = render_parse("let a = [1];\na[0] = 2;");
+
+  assert_eq!(
+    output,
+    "error[E0004]: cannot assign to (a[0])
+ --> test.mk:2:1
+  |
+2 | a[0] = 2;
+  | ^^^^
+  |
+  = help: only names can be assigned to, like `x = 1`
+
+error: could not parse test.mk due to 1 previous error
+"
+  );
+}
+
+#[test]
+fn test_render_division_by_zero() {
+  let input = "let zero = 0;\n10 / zero;";
+
+  assert_eq!(render_eval(input), "\
+error[E0101]: Division by zero: 10 / 0
+ --> test.mk:2:1
+  |
+2 | 10 / zero;
+  | ^^^^^^^^^
+");
+}
+
+#[test]
+fn test_render_expands_tabs() {
+  let input = "if (true) {\n\tputs(1 / 0)\n}";
+
+  assert_eq!(render_eval(input), "\
+error[E0101]: Division by zero: 1 / 0
+ --> test.mk:2:7
+  |
+2 |     puts(1 / 0)
+  |          ^^^^^
+");
+}
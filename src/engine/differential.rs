@@ -0,0 +1,54 @@
+// Runs programs on both engines and compares the results, so the virtual machine can be checked
+// against the tree-walker.
+
+use std::thread;
+
+use crate::engine::*;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+
+// What running a source resulted in, reduced to what both engines are expected to agree on.
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+  Value(String),
+  ParserError,
+  EvalError(String),
+}
+
+impl Outcome {
+  pub fn from_source(source: &str, engine: Engine) -> Outcome {
+    let lexer = Lexer::new(source);
+    let mut parser = Parser::new(lexer);
+
+    match parser.parse_program() {
+      Ok(program) => Outcome::from_result(&Session::new(engine).run(&program)),
+      Err(_error) => Outcome::ParserError,
+    }
+  }
+
+  pub fn from_result(result: &Result<Object, EvalError>) -> Outcome {
+    match result {
+      Ok(object) => Outcome::Value(object.inspect()),
+      Err(error) => Outcome::EvalError(error.to_string()),
+    }
+  }
+}
+
+// Runs a source on both engines, returning the outcome they agree on or a description of how they
+// differ. The engines run on a stack as large as the interpreter's, so deep recursion is compared too.
+pub fn compare_engines(source: &str) -> Result<Outcome, String> {
+  let (eval_outcome, vm_outcome) = thread::scope(|scope| {
+    thread::Builder::new()
+      .stack_size(STACK_SIZE)
+      .spawn_scoped(scope, || (Outcome::from_source(source, Engine::Eval), Outcome::from_source(source, Engine::Vm)))
+      .unwrap()
+      .join()
+      .unwrap()
+  });
+
+  if eval_outcome != vm_outcome {
+    return Err(format!("Engines disagree on:\n{}\n\teval: {:?}\n\tvm: {:?}", source, eval_outcome, vm_outcome))
+  }
+
+  Ok(eval_outcome)
+}
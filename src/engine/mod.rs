@@ -0,0 +1,88 @@
+#[cfg(test)]
+pub mod differential;
+#[cfg(test)]
+mod tests;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::ast::Program;
+use crate::compiler::Compiler;
+use crate::eval::eval;
+use crate::eval::eval_error::EvalError;
+use crate::eval::macro_expansion::*;
+use crate::object::environment::*;
+use crate::object::*;
+use crate::optimizer::*;
+use crate::vm::VM;
+
+// The tree-walker uses the native stack for every call it evaluates, so programs are run on a
+// thread with room for `MAX_CALL_DEPTH` nested calls
+pub const STACK_SIZE: usize = 1 << 30;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Engine {
+  Eval,
+  Vm,
+}
+
+impl Engine {
+  pub fn from_name(name: &str) -> Option<Engine> {
+    match name {
+      "eval" => Some(Engine::Eval),
+      "vm" => Some(Engine::Vm),
+      _ => None,
+    }
+  }
+}
+
+// Runs programs on one of the engines. A session keeps its state between runs, so bindings made
+// by one line of the REPL are visible to the next.
+pub struct Session {
+  // Macros are expanded by the tree-walker before a program runs, whichever engine runs it
+  macros: WrappedEnv,
+  state: EngineState,
+  optimization: Optimization,
+}
+
+enum EngineState {
+  Eval(WrappedEnv),
+  Vm(Compiler, VM),
+}
+
+impl Session {
+  pub fn new(engine: Engine) -> Self {
+    let state = match engine {
+      Engine::Eval => EngineState::Eval(Rc::new(RefCell::new(Environment::new()))),
+      Engine::Vm => EngineState::Vm(Compiler::new(), VM::new()),
+    };
+
+    Session { macros: Rc::new(RefCell::new(Environment::new())), state, optimization: Optimization::Off }
+  }
+
+  pub fn with_optimization(mut self, optimization: Optimization) -> Self {
+    self.optimization = optimization;
+    self
+  }
+
+  pub fn run(&mut self, program: &Program) -> Result<Object, EvalError> {
+    let mut program = program.clone();
+    define_macros(&mut program, &self.macros)?;
+    let mut program = expand_macros(program, &self.macros)?;
+
+    if self.optimization != Optimization::Off {
+      let changes = optimize(&mut program);
+
+      if self.optimization == Optimization::Debug {
+        for change in changes {
+          eprintln!("{}: {}", change.span, change.description);
+        }
+      }
+    }
+
+    match &mut self.state {
+      EngineState::Eval(env) => eval(&program, env),
+      EngineState::Vm(compiler, vm) => vm.run(compiler.compile(&program)?),
+    }
+  }
+}
@@ -0,0 +1,88 @@
+use std::fs;
+use std::path::Path;
+
+use crate::engine::*;
+use crate::engine::differential::*;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+
+#[test]
+fn test_engine_from_name() {
+  assert_eq!(Engine::from_name("eval"), Some(Engine::Eval));
+  assert_eq!(Engine::from_name("vm"), Some(Engine::Vm));
+  assert_eq!(Engine::from_name("jit"), None);
+}
+
+#[test]
+fn test_sessions_keep_bindings() -> Result<(), EvalError> {
+  let lines = [("let a = 2;", "Null"), ("let double = fn(x) { x * a };", "Null"), ("double(21)", "42")];
+
+  for engine in [Engine::Eval, Engine::Vm] {
+    let mut session = Session::new(engine);
+
+    for (line, expected) in lines {
+      let program = match_or_fail!(Parser::new(Lexer::new(line)).parse_program(), Ok(m) => m);
+
+      assert_eq!(session.run(&program)?.inspect(), expected, "engine: {:?}", engine);
+    }
+  }
+
+  Ok(())
+}
+
+// A declaration that fails must not change the binding it clashes with
+#[test]
+fn test_sessions_keep_bindings_after_failed_declaration() {
+  let lines = [("const k = 1;", Ok("Null")), ("let k = 5;", Err("Identifier has already been declared: k")), ("k", Ok("1"))];
+
+  for engine in [Engine::Eval, Engine::Vm] {
+    let mut session = Session::new(engine);
+
+    for (line, expected) in lines {
+      let program = match_or_fail!(Parser::new(Lexer::new(line)).parse_program(), Ok(m) => m);
+      let result = session.run(&program).map(|object| object.inspect()).map_err(|error| error.message);
+
+      assert_eq!(result.as_deref().map_err(|message| message.as_str()), expected, "engine: {:?}, line: {}", engine, line);
+    }
+  }
+}
+
+#[test]
+fn test_compare_engines() {
+  let tests = vec![
+    ("1 + 2", Outcome::Value("3".to_string())),
+    ("let", Outcome::ParserError),
+    ("-9223372036854775808 + 100000000000000000000", Outcome::Value("90776627963145224192".to_string())),
+    ("-true", Outcome::EvalError("1:1: Expected integer, found: Boolean(true)".to_string())),
+    ("let f = fn() { let a = [if (true) { return 5 }]; 1 }; f()", Outcome::Value("5".to_string())),
+    ("let r = []; for (x in 0..3) { r = push(r, if (x == 1) { continue } else { x }) }; r", Outcome::Value("[0, 2]".to_string())),
+    ("let r = 0; while (true) { r = 1 + if (true) { break } }; r", Outcome::Value("0".to_string())),
+    ("let f = fn(n) { f(n + 1) }; f(0)", Outcome::EvalError("1:17: Maximum call depth exceeded".to_string())),
+  ];
+
+  for (input, expected) in tests {
+    assert_eq!(compare_engines(input), Ok(expected));
+  }
+}
+
+// Every script in the examples directory must give the same result on both engines
+#[test]
+fn test_example_scripts() {
+  let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+  let mut script_count = 0;
+
+  for entry in fs::read_dir(directory).unwrap() {
+    let path = entry.unwrap().path();
+    let source = fs::read_to_string(&path).unwrap();
+
+    match compare_engines(&source) {
+      Ok(Outcome::ParserError) => panic!("{}: could not be parsed", path.display()),
+      Ok(_) => {},
+      Err(difference) => panic!("{}: {}", path.display(), difference),
+    }
+
+    script_count += 1;
+  }
+
+  assert!(script_count > 0);
+}
@@ -1,22 +1,87 @@
 use std::fmt;
 
-pub struct EvalError(pub String);
+use crate::object::Object;
+use crate::object::big_integer::BigInteger;
+use crate::token::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalErrorKind {
+  // The right operand is always zero
+  DivisionByZero { operator: TokenType, left: BigInteger },
+  Other,
+}
+
+pub struct EvalError {
+  pub kind: EvalErrorKind,
+  pub message: String,
+  pub span: Option<Span>,
+}
 
 impl EvalError {
+  pub fn new(message: String) -> Self {
+    EvalError { kind: EvalErrorKind::Other, message, span: None }
+  }
+
+  pub fn division_by_zero(operator: TokenType, left: BigInteger) -> Self {
+    EvalError {
+      message: format!("Division by zero: {} {} 0", left, operator),
+      kind: EvalErrorKind::DivisionByZero { operator, left },
+      span: None,
+    }
+  }
+
   pub fn not_implemented(feature_name: &str) -> Self {
-    EvalError(format!("Not implemented: {}", feature_name))
+    EvalError::new(format!("Not implemented: {}", feature_name))
+  }
+
+  // Errors are created deep inside the evaluator, where the node being evaluated is not known.
+  // The span is attached on the way out, so the innermost node that failed gets to claim it.
+  pub fn with_span_or_keep(mut self, span: Span) -> Self {
+    if self.span.is_none() {
+      self.span = Some(span);
+    }
+
+    self
+  }
+}
+
+// Why the tree-walker stopped evaluating a node before it had a value. Besides errors, this carries
+// a `return` up through the expressions around it to the function it returns from, and `break` and
+// `continue` up to the enclosing loop.
+pub enum Unwind {
+  Error(EvalError),
+  Return(Object),
+  Break,
+  Continue,
+}
+
+impl Unwind {
+  pub fn with_span_or_keep(self, span: Span) -> Self {
+    match self {
+      Unwind::Error(error) => Unwind::Error(error.with_span_or_keep(span)),
+      unwind => unwind,
+    }
+  }
+}
+
+impl From<EvalError> for Unwind {
+  fn from(error: EvalError) -> Self {
+    Unwind::Error(error)
   }
 }
 
 impl From<std::num::ParseIntError> for EvalError {
   fn from(_error: std::num::ParseIntError) -> Self {
-    EvalError("ParseIntError".to_string())
+    EvalError::new("ParseIntError".to_string())
   }
 }
 
 impl fmt::Display for EvalError {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    write!(f, "{}", self.0)
+    match &self.span {
+      Some(span) => write!(f, "{}: {}", span, self.message),
+      None => write!(f, "{}", self.message),
+    }
   }
 }
 
@@ -0,0 +1,247 @@
+use std::convert::Infallible;
+use std::rc::Rc;
+
+use crate::ast::*;
+use crate::ast::modify::*;
+use crate::eval::eval_error::EvalError;
+use crate::eval::eval;
+use crate::object::*;
+use crate::object::environment::*;
+use crate::token::*;
+
+use array_literal::ArrayLiteral;
+use big_integer_literal::BigIntegerLiteral;
+use boolean_literal::BooleanLiteral;
+use block_statement::BlockStatement;
+use call_expression::CallExpression;
+use float_literal::FloatLiteral;
+use hash_literal::HashLiteral;
+use integer_literal::IntegerLiteral;
+use let_statement::LetStatement;
+use macro_literal::MacroLiteral;
+use string_literal::StringLiteral;
+
+// `quote` and `unquote` look like calls, but their arguments are not evaluated like the arguments
+// of a function are.
+pub const QUOTE: &str = "quote";
+pub const UNQUOTE: &str = "unquote";
+
+// Returns the arguments of a call to the special form with the given name
+pub fn get_special_form_arguments<'a>(call_expression: &'a CallExpression, name: &str) -> Option<&'a [Expression]> {
+  match &*call_expression.function {
+    Expression::Identifier(identifier) if identifier.value == name => Some(&call_expression.arguments),
+    _ => None,
+  }
+}
+
+pub fn get_quoted_expression(arguments: &[Expression]) -> Result<&Expression, EvalError> {
+  match arguments {
+    [expression] => Ok(expression),
+    _ => Err(EvalError::new(format!("Expected 1 argument to quote, got {}", arguments.len()))),
+  }
+}
+
+// Quotes an expression. Each `unquote(argument)` inside of it is replaced by the value `unquote`
+// gives for the argument, converted back into an expression.
+pub fn quote(
+  expression: &Expression,
+  unquote: &mut dyn FnMut(&Expression) -> Result<Object, EvalError>,
+) -> Result<Object, EvalError> {
+  let expression = modify_expression(expression.clone(), &mut |expression| {
+    let argument = match get_unquoted_expression(&expression) {
+      Some(argument) => argument,
+      None => return Ok(expression),
+    };
+
+    object_to_expression(unquote(argument)?, expression.span())
+  })?;
+
+  Ok(Object::Quote(Box::new(expression)))
+}
+
+// The arguments of every `unquote` in a quoted expression, in the order `quote` asks for them
+pub fn get_unquoted_expressions(expression: &Expression) -> Vec<Expression> {
+  let mut arguments = vec![];
+
+  let _ = modify_expression::<Infallible, _>(expression.clone(), &mut |expression| {
+    if let Some(argument) = get_unquoted_expression(&expression) {
+      arguments.push(argument.clone());
+    }
+
+    Ok(expression)
+  });
+
+  arguments
+}
+
+fn get_unquoted_expression(expression: &Expression) -> Option<&Expression> {
+  match expression {
+    Expression::CallExpression(call_expression) => match get_special_form_arguments(call_expression, UNQUOTE) {
+      Some([argument]) => Some(argument),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+pub fn object_to_expression(object: Object, span: Span) -> Result<Expression, EvalError> {
+  let token = |token_type, literal: String| Token { token_type, literal, span };
+
+  let expression = match object {
+    Object::Integer(value) => Expression::IntegerLiteral(IntegerLiteral {
+      token: token(token_types::INT, value.to_string()),
+      span,
+      value,
+    }),
+    Object::BigInteger(value) => Expression::BigIntegerLiteral(BigIntegerLiteral {
+      token: token(token_types::INT, value.to_string()),
+      span,
+      value,
+    }),
+    Object::Float(value) => Expression::FloatLiteral(FloatLiteral {
+      token: token(token_types::FLOAT, format!("{:?}", value)),
+      span,
+      value,
+    }),
+    Object::String(value) => Expression::StringLiteral(StringLiteral {
+      token: token(token_types::STRING, value.clone()),
+      span,
+      value,
+    }),
+    Object::Boolean(value) => Expression::BooleanLiteral(BooleanLiteral {
+      token: token(if value { token_types::TRUE } else { token_types::FALSE }, value.to_string()),
+      span,
+      value,
+    }),
+    Object::Array(elements) => Expression::ArrayLiteral(ArrayLiteral {
+      token: token(token_types::LBRACKET, "[".to_string()),
+      span,
+      elements: elements
+        .into_iter()
+        .map(|element| object_to_expression(element, span))
+        .collect::<Result<Vec<Expression>, EvalError>>()?,
+    }),
+    Object::Hash(hash) => {
+      let mut pairs = vec![];
+
+      for pair in hash.pairs() {
+        pairs.push((object_to_expression(pair.key.clone(), span)?, object_to_expression(pair.value.clone(), span)?));
+      }
+
+      Expression::HashLiteral(HashLiteral {
+        token: token(token_types::LBRACE, "{".to_string()),
+        span,
+        pairs,
+      })
+    },
+    Object::Quote(expression) => *expression,
+    x => return Err(EvalError::new(format!("Cannot unquote a value of type {}", x.type_name()))),
+  };
+
+  Ok(expression)
+}
+
+pub fn misplaced_macro_error() -> EvalError {
+  EvalError::new("Macros can only be defined by top-level let statements".to_string())
+}
+
+// Takes the macro definitions, `let name = macro(...) { ... }` at the top level, out of the
+// program and binds them in the macro environment.
+pub fn define_macros(program: &mut Program, env: &WrappedEnv) -> Result<(), EvalError> {
+  let mut statements = vec![];
+
+  for statement in std::mem::take(&mut program.statements) {
+    match get_macro_definition(&statement) {
+      Some((let_statement, macro_literal)) => {
+        define_macro(let_statement, macro_literal, env).map_err(|error| error.with_span_or_keep(let_statement.span))?
+      },
+      None => statements.push(statement),
+    }
+  }
+
+  program.statements = statements;
+  Ok(())
+}
+
+fn get_macro_definition(statement: &Statement) -> Option<(&LetStatement, &MacroLiteral)> {
+  match statement {
+    Statement::LetStatement(let_statement) => match &*let_statement.value {
+      Expression::MacroLiteral(macro_literal) => Some((let_statement, macro_literal)),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+fn define_macro(let_statement: &LetStatement, macro_literal: &MacroLiteral, env: &WrappedEnv) -> Result<(), EvalError> {
+  let parameters = macro_literal.arguments.iter().map(|argument| argument.value.clone()).collect();
+  let object = Object::Macro(parameters, macro_literal.body.clone(), Rc::clone(env));
+
+  if let_statement.is_constant() {
+    env.borrow_mut().set_constant(&let_statement.name.value, object)?;
+  } else {
+    env.borrow_mut().set(&let_statement.name.value, object)?;
+  }
+
+  Ok(())
+}
+
+// Replaces every call to a macro with the expression the macro returns. The arguments are passed
+// to the macro quoted, without evaluating them.
+pub fn expand_macros(program: Program, env: &WrappedEnv) -> Result<Program, EvalError> {
+  modify_program(program, &mut |expression| {
+    let call_expression = match &expression {
+      Expression::CallExpression(call_expression) => call_expression,
+      _ => return Ok(expression),
+    };
+
+    match get_macro(call_expression, env) {
+      Some(Object::Macro(parameters, body, outer_env)) => {
+        expand_macro(&parameters, &body, &outer_env, call_expression)
+          .map_err(|error| error.with_span_or_keep(call_expression.span))
+      },
+      _ => Ok(expression),
+    }
+  })
+}
+
+fn get_macro(call_expression: &CallExpression, env: &WrappedEnv) -> Option<Object> {
+  let identifier = match &*call_expression.function {
+    Expression::Identifier(identifier) => identifier,
+    _ => return None,
+  };
+
+  match env.borrow().get(&identifier.value) {
+    Ok(object @ Object::Macro(_, _, _)) => Some(object),
+    _ => None,
+  }
+}
+
+fn expand_macro(
+  parameters: &[String],
+  body: &BlockStatement,
+  outer_env: &WrappedEnv,
+  call_expression: &CallExpression,
+) -> Result<Expression, EvalError> {
+  let arguments = &call_expression.arguments;
+
+  if parameters.len() != arguments.len() {
+    return Err(EvalError::new(format!(
+      "Expected {} arguments ({}), got {}",
+      parameters.len(),
+      parameters.join(", "),
+      arguments.len()
+    )))
+  }
+
+  let macro_env = Environment::extend(outer_env);
+
+  for (name, argument) in parameters.iter().zip(arguments.iter()) {
+    macro_env.borrow_mut().set(name, Object::Quote(Box::new(argument.clone())))?;
+  }
+
+  match eval(body, &macro_env)? {
+    Object::Quote(expression) => Ok(*expression),
+    x => Err(EvalError::new(format!("Macros must return a quoted expression, got {}", x.type_name()))),
+  }
+}
@@ -1,45 +1,66 @@
 pub mod eval_error;
+pub mod macro_expansion;
 
 #[cfg(test)]
 mod tests;
 
 use crate::ast::*;
+use crate::builtins::get_builtin;
 use crate::object::*;
+use crate::object::big_integer::BigInteger;
 use crate::object::environment::*;
+use crate::object::hash::*;
 use crate::token::*;
-use eval_error::EvalError;
+use eval_error::*;
+use macro_expansion::*;
 
+use array_literal::ArrayLiteral;
+use assign_expression::AssignExpression;
+use big_integer_literal::BigIntegerLiteral;
 use boolean_literal::BooleanLiteral;
 use call_expression::CallExpression;
+use float_literal::FloatLiteral;
+use for_expression::ForExpression;
 use function_literal::FunctionLiteral;
+use hash_literal::HashLiteral;
 use identifier::Identifier;
 use if_expression::IfExpression;
+use index_expression::IndexExpression;
+use slice_expression::SliceExpression;
 use infix_expression::InfixExpression;
+use interpolated_string::*;
 use integer_literal::IntegerLiteral;
 use string_literal::StringLiteral;
+use while_expression::WhileExpression;
 use prefix_expression::PrefixExpression;
 use block_statement::BlockStatement;
 use let_statement::LetStatement;
 use return_statement::ReturnStatement;
+use std::cell::Cell;
+use std::convert::TryFrom;
 use std::rc::Rc;
 
+// How deeply calls can nest before the program stops with an error, on either engine
+pub const MAX_CALL_DEPTH: usize = 10_000;
+
+thread_local! {
+  // The calls of user functions that have not returned yet
+  static CALL_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
 pub const TRUE_OBJECT: Object = Object::Boolean(true);
 pub const FALSE_OBJECT: Object = Object::Boolean(false);
 
 pub trait EvalObject {
-  fn eval(&self, env: &WrappedEnv) -> Result<Object, EvalError>;
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind>;
 }
 
 impl EvalObject for Program {
-  fn eval(&self, env: &WrappedEnv) -> Result<Object, EvalError> {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
     let mut result = Object::Null;
 
     for statement in &self.statements {
-      result = eval(statement, &Rc::clone(env))?;
-
-      if let Object::Return(boxed_result) = result {
-        return Ok(*boxed_result)
-      }
+      result = statement.eval(&Rc::clone(env))?;
     }
 
     Ok(result)
@@ -47,70 +68,175 @@ impl EvalObject for Program {
 }
 
 impl EvalObject for Statement {
-  fn eval(&self, env: &WrappedEnv) -> Result<Object, EvalError> {
-    match &self {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
+    let result = match &self {
       Statement::LetStatement(let_statement) => let_statement.eval(env),
       Statement::ReturnStatement(return_statement) => return_statement.eval(env),
       Statement::Expression(expression) => expression.eval(env),
       Statement::BlockStatement(block_statement) => block_statement.eval(env),
-    }
+      Statement::BreakStatement(_) => Err(Unwind::Break),
+      Statement::ContinueStatement(_) => Err(Unwind::Continue),
+    };
+
+    result.map_err(|error| error.with_span_or_keep(self.span()))
   }
 }
 
 impl EvalObject for Expression {
-  fn eval(&self, env: &WrappedEnv) -> Result<Object, EvalError> {
-    match &self {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
+    let result = match &self {
       Expression::Identifier(identifier) => identifier.eval(env),
       Expression::BooleanLiteral(boolean_literal) => boolean_literal.eval(env),
       Expression::IntegerLiteral(integer_literal) => integer_literal.eval(env),
+      Expression::BigIntegerLiteral(big_integer_literal) => big_integer_literal.eval(env),
+      Expression::FloatLiteral(float_literal) => float_literal.eval(env),
       Expression::PrefixExpression(prefix_expression) => prefix_expression.eval(env),
       Expression::InfixExpression(infix_expression) => infix_expression.eval(env),
       Expression::IfExpression(if_expression) => if_expression.eval(env),
       Expression::FunctionLiteral(function_literal) => function_literal.eval(env),
+      Expression::MacroLiteral(_) => Err(misplaced_macro_error().into()),
       Expression::CallExpression(call_expression) => call_expression.eval(env),
       Expression::StringLiteral(string_literal) => string_literal.eval(env),
-    }
+      Expression::InterpolatedString(interpolated_string) => interpolated_string.eval(env),
+      Expression::ArrayLiteral(array_literal) => array_literal.eval(env),
+      Expression::IndexExpression(index_expression) => index_expression.eval(env),
+      Expression::SliceExpression(slice_expression) => slice_expression.eval(env),
+      Expression::HashLiteral(hash_literal) => hash_literal.eval(env),
+      Expression::AssignExpression(assign_expression) => assign_expression.eval(env),
+      Expression::WhileExpression(while_expression) => while_expression.eval(env),
+      Expression::ForExpression(for_expression) => for_expression.eval(env),
+    };
+
+    result.map_err(|error| error.with_span_or_keep(self.span()))
   }
 }
 
 impl EvalObject for IntegerLiteral {
-  fn eval(&self, _: &WrappedEnv) -> Result<Object, EvalError> {
+  fn eval(&self, _: &WrappedEnv) -> Result<Object, Unwind> {
     Ok(Object::Integer(self.value.clone()))
   }
 }
 
+impl EvalObject for BigIntegerLiteral {
+  fn eval(&self, _: &WrappedEnv) -> Result<Object, Unwind> {
+    Ok(Object::BigInteger(self.value.clone()))
+  }
+}
+
+impl EvalObject for FloatLiteral {
+  fn eval(&self, _: &WrappedEnv) -> Result<Object, Unwind> {
+    Ok(Object::Float(self.value))
+  }
+}
+
 impl EvalObject for StringLiteral {
-  fn eval(&self, _: &WrappedEnv) -> Result<Object, EvalError> {
+  fn eval(&self, _: &WrappedEnv) -> Result<Object, Unwind> {
     Ok(Object::String(self.value.clone()))
   }
 }
 
+impl EvalObject for InterpolatedString {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
+    let mut parts = vec![];
+
+    for part in &self.parts {
+      match part {
+        StringPart::Literal(string) => parts.push(Object::String(string.clone())),
+        StringPart::Expression(expression) => parts.push(expression.eval(env)?),
+      }
+    }
+
+    Ok(interpolate(&parts))
+  }
+}
+
+// Each part of an interpolated string is shown the way `puts` shows it
+pub fn interpolate(parts: &[Object]) -> Object {
+  Object::String(parts.iter().map(|part| part.inspect()).collect())
+}
+
+impl EvalObject for ArrayLiteral {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
+    let mut elements = vec![];
+    for element in &self.elements {
+      elements.push(element.eval(env)?);
+    }
+
+    Ok(Object::Array(elements))
+  }
+}
+
+impl EvalObject for HashLiteral {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
+    let mut hash = Hash::new();
+
+    for (key_expression, value_expression) in &self.pairs {
+      let key = key_expression.eval(env)?;
+      let hash_key = key.get_hash_key()?;
+      let value = value_expression.eval(env)?;
+
+      hash.insert(hash_key, key, value);
+    }
+
+    Ok(Object::Hash(hash))
+  }
+}
+
+impl EvalObject for IndexExpression {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
+    let left_object = self.left.eval(env)?;
+    let index_object = self.index.eval(env)?;
+
+    Ok(eval_index_expression(left_object, index_object)?)
+  }
+}
+
+impl EvalObject for SliceExpression {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
+    let left_object = self.left.eval(env)?;
+    let start_object = match &self.start {
+      Some(start) => start.eval(env)?,
+      None => Object::Null,
+    };
+    let end_object = match &self.end {
+      Some(end) => end.eval(env)?,
+      None => Object::Null,
+    };
+
+    Ok(eval_slice_expression(left_object, start_object, end_object)?)
+  }
+}
+
 impl EvalObject for BooleanLiteral {
-  fn eval(&self, _: &WrappedEnv) -> Result<Object, EvalError> {
+  fn eval(&self, _: &WrappedEnv) -> Result<Object, Unwind> {
     Ok(native_boolean_to_boolean_object(self.value))
   }
 }
 
 impl EvalObject for PrefixExpression {
-  fn eval(&self, env: &WrappedEnv) -> Result<Object, EvalError> {
-    match self.operator.as_str() {
-      token_types::BANG => eval_bang_operator_expression(&self.right, env),
-      token_types::MINUS => eval_minus_operator_expression(&self.right, env),
-      x => Err(EvalError::not_implemented(&format!("PrefixExpression for operator: {}", x))),
-    }
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
+    let right_object = self.right.eval(env)?;
+
+    Ok(eval_prefix_expression(&self.operator, right_object)?)
+  }
+}
+
+// The operator semantics are shared with the virtual machine, which is why they work on
+// evaluated objects rather than on AST nodes.
+pub fn eval_prefix_expression(operator: &str, right: Object) -> Result<Object, EvalError> {
+  match operator {
+    token_types::BANG => eval_bang_operator_expression(right),
+    token_types::MINUS => eval_minus_operator_expression(right),
+    x => Err(EvalError::not_implemented(&format!("PrefixExpression for operator: {}", x))),
   }
 }
 
 impl EvalObject for BlockStatement {
-  fn eval(&self, env: &WrappedEnv) -> Result<Object, EvalError> {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
     let mut result = Object::Null;
 
     for statement in &self.statements {
       result = statement.eval(&Rc::clone(env))?;
-
-      if let Object::Return(_) = result {
-        return Ok(result)
-      }
     }
 
     Ok(result)
@@ -118,7 +244,7 @@ impl EvalObject for BlockStatement {
 }
 
 impl EvalObject for IfExpression {
-  fn eval(&self, env: &WrappedEnv) -> Result<Object, EvalError> {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
     let condition_is_met = self.condition.eval(&Rc::clone(env))?.get_is_truthy().clone();
 
     if condition_is_met {
@@ -133,15 +259,79 @@ impl EvalObject for IfExpression {
   }
 }
 
+// Every iteration gets a scope of its own, so the body can declare names with `let`
+impl EvalObject for WhileExpression {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
+    while *self.condition.eval(&Rc::clone(env))?.get_is_truthy() {
+      let iteration_env = Environment::extend(env);
+
+      match self.body.eval(&iteration_env) {
+        Ok(_) | Err(Unwind::Continue) => {},
+        Err(Unwind::Break) => break,
+        Err(unwind) => return Err(unwind),
+      }
+    }
+
+    Ok(Object::Null)
+  }
+}
+
+impl EvalObject for ForExpression {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
+    let iterable = eval_iterable(self.iterable.eval(&Rc::clone(env))?)?;
+    let mut index = 0;
+
+    while let Some(item) = get_iteration_item(&iterable, index) {
+      index += 1;
+
+      let iteration_env = Environment::extend(env);
+      iteration_env.borrow_mut().set(&self.variable.value, item)?;
+
+      match self.body.eval(&iteration_env) {
+        Ok(_) | Err(Unwind::Continue) => {},
+        Err(Unwind::Break) => break,
+        Err(unwind) => return Err(unwind),
+      }
+    }
+
+    Ok(Object::Null)
+  }
+}
+
+// Turns a value into something `get_iteration_item` can step through. Strings are split into
+// their characters and hashes into their keys up front, so that each step is cheap.
+pub fn eval_iterable(object: Object) -> Result<Object, EvalError> {
+  match object {
+    Object::Array(_) | Object::Range(_, _) => Ok(object),
+    Object::String(string) => {
+      Ok(Object::Array(string.chars().map(|char| Object::String(char.to_string())).collect()))
+    },
+    Object::Hash(hash) => Ok(Object::Array(hash.pairs().iter().map(|pair| pair.key.clone()).collect())),
+    x => Err(EvalError::new(format!("Cannot iterate over {}", x.type_name()))),
+  }
+}
+
+pub fn get_iteration_item(iterable: &Object, index: usize) -> Option<Object> {
+  match iterable {
+    Object::Array(elements) => elements.get(index).cloned(),
+    Object::Range(start, end) => {
+      let value = start.checked_add(index as i64)?;
+
+      if value < *end { Some(Object::Integer(value)) } else { None }
+    },
+    _ => None,
+  }
+}
+
 impl EvalObject for ReturnStatement {
-  fn eval(&self, env: &WrappedEnv) -> Result<Object, EvalError> {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
     let return_object = self.return_value.eval(env)?;
-    Ok(Object::Return(Box::new(return_object)))
+    Err(Unwind::Return(return_object))
   }
 }
 
 impl EvalObject for FunctionLiteral {
-  fn eval(&self, env: &WrappedEnv) -> Result<Object, EvalError> {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
     let mut identifiers = vec![];
     for argument in &self.arguments {
       identifiers.push(argument.value.clone());
@@ -152,69 +342,202 @@ impl EvalObject for FunctionLiteral {
 }
 
 impl EvalObject for CallExpression {
-  fn eval(&self, env: &WrappedEnv) -> Result<Object, EvalError> {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
+    if let Some(arguments) = get_special_form_arguments(self, QUOTE) {
+      return Ok(quote(get_quoted_expression(arguments)?, &mut |argument| eval(argument, env))?)
+    }
+
     let function = self.function.eval(env)?;
 
-    if let Object::Function(params, block, outer_env) = function {
-      if params.len() != self.arguments.len() {
-        return Err(EvalError(format!("Expected {} arguments ({}), got {}", params.len(), params.join(", "), self.arguments.len())))
+    let mut arguments = vec![];
+    for argument in &*self.arguments {
+      arguments.push(argument.eval(env)?);
+    }
+
+    Ok(apply_function(function, arguments)?)
+  }
+}
+
+pub fn apply_function(function: Object, arguments: Vec<Object>) -> Result<Object, EvalError> {
+  match function {
+    Object::Function(params, block, outer_env) => {
+      if params.len() != arguments.len() {
+        return Err(EvalError::new(format!("Expected {} arguments ({}), got {}", params.len(), params.join(", "), arguments.len())))
       }
 
       let function_env = Environment::extend(&outer_env);
 
-      let mut current = 0;
-      for argument in &*self.arguments {
-        let name = &params[current];
-        function_env.borrow_mut().set(name, argument.eval(env)?)?;
-        current += 1;
+      for (name, argument) in params.iter().zip(arguments) {
+        function_env.borrow_mut().set(name, argument)?;
       }
 
-      block.eval(&function_env)
-    }
-    else {
-      Err(EvalError(format!("Expected function, found: {:?}", function)))
-    }
+      let depth = CALL_DEPTH.with(Cell::get);
+
+      if depth >= MAX_CALL_DEPTH {
+        return Err(EvalError::new("Maximum call depth exceeded".to_string()))
+      }
+
+      CALL_DEPTH.with(|call_depth| call_depth.set(depth + 1));
+      let result = eval(&*block, &function_env);
+      CALL_DEPTH.with(|call_depth| call_depth.set(depth));
+
+      result
+    },
+    Object::Builtin(_, builtin_function) => builtin_function(arguments, &mut apply_function),
+    x => Err(EvalError::new(format!("Expected function, found: {:?}", x))),
   }
 }
 
-fn eval_bang_operator_expression(right: &Box<Expression>, env: &WrappedEnv) -> Result<Object, EvalError> {
-  let right_object = right.eval(env)?;
-
-  Ok(native_boolean_to_boolean_object(!*right_object.get_boolean_value()?))
+fn eval_bang_operator_expression(right: Object) -> Result<Object, EvalError> {
+  Ok(native_boolean_to_boolean_object(!*right.get_boolean_value()?))
 }
 
-fn eval_minus_operator_expression(right: &Box<Expression>, env: &WrappedEnv) -> Result<Object, EvalError> {
-  let right_object = right.eval(env)?;
-  let numeric_value = right_object.get_numeric_value()?;
+fn eval_minus_operator_expression(right: Object) -> Result<Object, EvalError> {
+  match right {
+    Object::Float(float) => return Ok(Object::Float(-float)),
+    Object::BigInteger(integer) => return Ok(Object::from_big_integer(integer.neg())),
+    _ => {},
+  }
+
+  let numeric_value = right.get_numeric_value()?;
 
-  Ok(Object::Integer(-numeric_value))
+  match numeric_value.checked_neg() {
+    Some(result) => Ok(Object::Integer(result)),
+    None => Ok(Object::from_big_integer(BigInteger::from(numeric_value).neg())),
+  }
 }
 
 impl EvalObject for InfixExpression {
-  fn eval(&self, env: &WrappedEnv) -> Result<Object, EvalError> {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
     let left_object = self.left.eval(&Rc::clone(env))?;
-    let right_object = self.right.eval(&Rc::clone(env))?;
 
-    match left_object {
-      Object::Integer(_) => eval_integer_infix_expression(&self.operator, left_object, right_object),
-      Object::Boolean(_) => eval_boolean_infix_expression(&self.operator, left_object, right_object),
-      Object::String(_) => eval_string_infix_expression(&self.operator, left_object, right_object),
-      x => return Err(EvalError::not_implemented(&format!("InfixExpression for object type: {:?}", x))),
+    // Logical operators only evaluate their right side when the left side does not decide the
+    // result, which is then the value of the side that decided it
+    match self.operator.as_str() {
+      token_types::AND if !*left_object.get_is_truthy() => return Ok(left_object),
+      token_types::OR if *left_object.get_is_truthy() => return Ok(left_object),
+      token_types::AND | token_types::OR => return self.right.eval(env),
+      _ => {},
     }
+
+    let right_object = self.right.eval(&Rc::clone(env))?;
+
+    Ok(eval_infix_expression(&self.operator, left_object, right_object)?)
+  }
+}
+
+pub fn eval_infix_expression(operator: &str, left: Object, right: Object) -> Result<Object, EvalError> {
+  match left {
+    Object::Integer(_) | Object::BigInteger(_) if matches!(right, Object::Float(_)) => {
+      eval_float_infix_expression(operator, left, right)
+    },
+    Object::Integer(_) if matches!(right, Object::BigInteger(_)) => eval_big_integer_infix_expression(operator, left, right),
+    Object::Integer(_) => eval_integer_infix_expression(operator, left, right),
+    Object::BigInteger(_) => eval_big_integer_infix_expression(operator, left, right),
+    Object::Float(_) => eval_float_infix_expression(operator, left, right),
+    Object::Boolean(_) => eval_boolean_infix_expression(operator, left, right),
+    Object::String(_) => eval_string_infix_expression(operator, left, right),
+    x => Err(EvalError::not_implemented(&format!("InfixExpression for object type: {:?}", x))),
   }
 }
 
 impl EvalObject for LetStatement {
-  fn eval(&self, env: &WrappedEnv) -> Result<Object, EvalError> {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
     let object = self.value.eval(env)?;
 
-    env.borrow_mut().set(&self.name.value, object)
+    if self.is_constant() {
+      Ok(env.borrow_mut().set_constant(&self.name.value, object)?)
+    } else {
+      Ok(env.borrow_mut().set(&self.name.value, object)?)
+    }
+  }
+}
+
+impl EvalObject for AssignExpression {
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
+    let object = self.value.eval(env)?;
+
+    Ok(env.borrow_mut().assign(&self.name.value, object)?)
   }
 }
 
 impl EvalObject for Identifier {
-  fn eval(&self, env: &WrappedEnv) -> Result<Object, EvalError> {
-    env.borrow().get(&self.value)
+  fn eval(&self, env: &WrappedEnv) -> Result<Object, Unwind> {
+    let result = env.borrow().get(&self.value);
+
+    Ok(result.or_else(|error| get_builtin(&self.value).ok_or(error))?)
+  }
+}
+
+pub fn eval_index_expression(left: Object, index: Object) -> Result<Object, EvalError> {
+  match (&left, &index) {
+    (Object::Array(elements), Object::Integer(index)) => eval_array_index_expression(elements, *index),
+    // Big integers are always out of bounds
+    (Object::Array(_), Object::BigInteger(_)) => Ok(Object::Null),
+    (Object::Array(_), _) => Err(EvalError::new(format!("Array index must be an integer, found: {:?}", index))),
+    (Object::String(string), Object::Integer(index)) => Ok(eval_string_index_expression(string, *index)),
+    (Object::String(_), Object::BigInteger(_)) => Ok(Object::Null),
+    (Object::String(_), _) => Err(EvalError::new(format!("String index must be an integer, found: {:?}", index))),
+    (Object::Hash(hash), _) => eval_hash_index_expression(hash, &index),
+    _ => Err(EvalError::new(format!("Index operator not supported: {:?}", left))),
+  }
+}
+
+fn eval_array_index_expression(elements: &[Object], index: i64) -> Result<Object, EvalError> {
+  if index < 0 || index as usize >= elements.len() {
+    return Ok(Object::Null)
+  }
+
+  Ok(elements[index as usize].clone())
+}
+
+// Strings are indexed by character rather than by byte
+fn eval_string_index_expression(string: &str, index: i64) -> Object {
+  if index < 0 {
+    return Object::Null
+  }
+
+  match string.chars().nth(index as usize) {
+    Some(ch) => Object::String(ch.to_string()),
+    None => Object::Null,
+  }
+}
+
+// A missing bound is passed as `Null`. Bounds are clamped to the length, so slicing never fails on
+// out of range bounds but returns fewer elements instead, and a start past the end gives nothing.
+pub fn eval_slice_expression(left: Object, start: Object, end: Object) -> Result<Object, EvalError> {
+  let length = match &left {
+    Object::Array(elements) => elements.len(),
+    Object::String(string) => string.chars().count(),
+    _ => return Err(EvalError::new(format!("Slice operator not supported: {:?}", left))),
+  };
+
+  let start = get_slice_bound(&start, 0, length)?;
+  let end = get_slice_bound(&end, length, length)?.max(start);
+
+  match left {
+    Object::Array(elements) => Ok(Object::Array(elements[start..end].to_vec())),
+    Object::String(string) => Ok(Object::String(string.chars().skip(start).take(end - start).collect())),
+    _ => unreachable!(),
+  }
+}
+
+fn get_slice_bound(bound: &Object, default: usize, length: usize) -> Result<usize, EvalError> {
+  match bound {
+    Object::Null => Ok(default),
+    Object::Integer(integer) => Ok((*integer).clamp(0, length as i64) as usize),
+    Object::BigInteger(integer) if integer < &BigInteger::from(0) => Ok(0),
+    Object::BigInteger(_) => Ok(length),
+    x => Err(EvalError::new(format!("Slice bounds must be integers, found: {:?}", x))),
+  }
+}
+
+fn eval_hash_index_expression(hash: &Hash, index: &Object) -> Result<Object, EvalError> {
+  let hash_key = index.get_hash_key()?;
+
+  match hash.get(&hash_key) {
+    Some(value) => Ok(value.clone()),
+    None => Ok(Object::Null),
   }
 }
 
@@ -223,15 +546,98 @@ fn eval_integer_infix_expression(operator: &str, left: Object, right: Object) ->
   let right_value = right.get_numeric_value()?;
 
   match operator {
-    token_types::PLUS => Ok(Object::Integer(left_value + right_value)),
-    token_types::MINUS => Ok(Object::Integer(left_value - right_value)),
-    token_types::ASTERISK => Ok(Object::Integer(left_value * right_value)),
-    token_types::SLASH => Ok(Object::Integer(left_value / right_value)),
+    token_types::PLUS => eval_checked_arithmetic(token_types::PLUS, left_value, right_value, i64::checked_add),
+    token_types::MINUS => eval_checked_arithmetic(token_types::MINUS, left_value, right_value, i64::checked_sub),
+    token_types::ASTERISK => eval_checked_arithmetic(token_types::ASTERISK, left_value, right_value, i64::checked_mul),
+    token_types::SLASH => eval_checked_arithmetic(token_types::SLASH, left_value, right_value, i64::checked_div),
+    token_types::PERCENT => eval_checked_arithmetic(token_types::PERCENT, left_value, right_value, i64::checked_rem),
+    token_types::POWER => eval_checked_arithmetic(token_types::POWER, left_value, right_value, |base, exponent| {
+      u32::try_from(exponent).ok().and_then(|exponent| base.checked_pow(exponent))
+    }),
+    token_types::LT => Ok(native_boolean_to_boolean_object(left_value < right_value)),
+    token_types::GT => Ok(native_boolean_to_boolean_object(left_value > right_value)),
+    token_types::LT_EQ => Ok(native_boolean_to_boolean_object(left_value <= right_value)),
+    token_types::GT_EQ => Ok(native_boolean_to_boolean_object(left_value >= right_value)),
+    token_types::EQ => Ok(native_boolean_to_boolean_object(left_value == right_value)),
+    token_types::NOT_EQ => Ok(native_boolean_to_boolean_object(left_value != right_value)),
+    token_types::RANGE => Ok(Object::Range(left_value, right_value)),
+    _ => Err(EvalError::new(format!("Unknown operation: Integer {} Integer", operator))),
+  }
+}
+
+// Operations that cannot be done with i64, because they overflow or divide by zero, are redone
+// with big integers, which either succeed or report the error.
+fn eval_checked_arithmetic(
+  operator: TokenType,
+  left: i64,
+  right: i64,
+  operation: fn(i64, i64) -> Option<i64>,
+) -> Result<Object, EvalError> {
+  match operation(left, right) {
+    Some(result) => Ok(Object::Integer(result)),
+    None => eval_big_integer_infix_expression(operator, Object::Integer(left), Object::Integer(right)),
+  }
+}
+
+fn eval_big_integer_infix_expression(operator: &str, left: Object, right: Object) -> Result<Object, EvalError> {
+  let left_value = left.get_big_integer_value()?;
+  let right_value = right.get_big_integer_value()?;
+
+  match operator {
+    token_types::PLUS => Ok(Object::from_big_integer(left_value.add(&right_value))),
+    token_types::MINUS => Ok(Object::from_big_integer(left_value.sub(&right_value))),
+    token_types::ASTERISK => Ok(Object::from_big_integer(left_value.mul(&right_value))),
+    token_types::SLASH => match left_value.div_rem(&right_value) {
+      Some((quotient, _)) => Ok(Object::from_big_integer(quotient)),
+      None => Err(EvalError::division_by_zero(token_types::SLASH, left_value)),
+    },
+    token_types::PERCENT => match left_value.div_rem(&right_value) {
+      Some((_, remainder)) => Ok(Object::from_big_integer(remainder)),
+      None => Err(EvalError::division_by_zero(token_types::PERCENT, left_value)),
+    },
+    token_types::POWER => eval_big_integer_power(left_value, right_value),
+    token_types::LT => Ok(native_boolean_to_boolean_object(left_value < right_value)),
+    token_types::GT => Ok(native_boolean_to_boolean_object(left_value > right_value)),
+    token_types::LT_EQ => Ok(native_boolean_to_boolean_object(left_value <= right_value)),
+    token_types::GT_EQ => Ok(native_boolean_to_boolean_object(left_value >= right_value)),
+    token_types::EQ => Ok(native_boolean_to_boolean_object(left_value == right_value)),
+    token_types::NOT_EQ => Ok(native_boolean_to_boolean_object(left_value != right_value)),
+    token_types::RANGE => Err(EvalError::new(format!("Range is too large: {}..{}", left_value, right_value))),
+    _ => Err(EvalError::new(format!("Unknown operation: Integer {} Integer", operator))),
+  }
+}
+
+fn eval_big_integer_power(base: BigInteger, exponent: BigInteger) -> Result<Object, EvalError> {
+  if exponent < BigInteger::from(0) {
+    return Err(EvalError::new(format!("Negative exponent: {} ** {}", base, exponent)))
+  }
+
+  match exponent.to_i64().and_then(|exponent| u32::try_from(exponent).ok()) {
+    Some(exponent) => Ok(Object::from_big_integer(base.pow(exponent))),
+    None => Err(EvalError::new(format!("Exponent is too large: {} ** {}", base, exponent))),
+  }
+}
+
+// Floats follow IEEE 754: dividing by zero gives an infinity, and NaN is unequal to everything,
+// itself included, so every comparison with it is false except `!=`.
+fn eval_float_infix_expression(operator: &str, left: Object, right: Object) -> Result<Object, EvalError> {
+  let left_value = left.get_float_value()?;
+  let right_value = right.get_float_value()?;
+
+  match operator {
+    token_types::PLUS => Ok(Object::Float(left_value + right_value)),
+    token_types::MINUS => Ok(Object::Float(left_value - right_value)),
+    token_types::ASTERISK => Ok(Object::Float(left_value * right_value)),
+    token_types::SLASH => Ok(Object::Float(left_value / right_value)),
+    token_types::PERCENT => Ok(Object::Float(left_value % right_value)),
+    token_types::POWER => Ok(Object::Float(left_value.powf(right_value))),
     token_types::LT => Ok(native_boolean_to_boolean_object(left_value < right_value)),
     token_types::GT => Ok(native_boolean_to_boolean_object(left_value > right_value)),
+    token_types::LT_EQ => Ok(native_boolean_to_boolean_object(left_value <= right_value)),
+    token_types::GT_EQ => Ok(native_boolean_to_boolean_object(left_value >= right_value)),
     token_types::EQ => Ok(native_boolean_to_boolean_object(left_value == right_value)),
     token_types::NOT_EQ => Ok(native_boolean_to_boolean_object(left_value != right_value)),
-    _ => Err(EvalError(format!("Unknown operation: Integer {} Integer", operator))),
+    _ => Err(EvalError::new(format!("Unknown operation: {} {} {}", left.type_name(), operator, right.type_name()))),
   }
 }
 
@@ -242,11 +648,15 @@ fn eval_boolean_infix_expression(operator: &str, left: Object, right: Object) ->
   match operator {
     token_types::EQ => Ok(native_boolean_to_boolean_object(left_value == right_value)),
     token_types::NOT_EQ => Ok(native_boolean_to_boolean_object(left_value != right_value)),
-    _ => Err(EvalError(format!("Unknown operation: Boolean {} Boolean", operator))),
+    _ => Err(EvalError::new(format!("Unknown operation: Boolean {} Boolean", operator))),
   }
 }
 
 fn eval_string_infix_expression(operator: &str, left: Object, right: Object) -> Result<Object, EvalError> {
+  if let token_types::LT | token_types::GT | token_types::LT_EQ | token_types::GT_EQ = operator {
+    return eval_string_comparison(operator, left, right)
+  }
+
   let left_value = left.get_string_value()?;
   let right_value = right.get_string_value()?;
 
@@ -254,10 +664,27 @@ fn eval_string_infix_expression(operator: &str, left: Object, right: Object) ->
     token_types::PLUS => Ok(Object::String(left_value.clone() + &right_value)),
     token_types::EQ => Ok(native_boolean_to_boolean_object(left_value == right_value)),
     token_types::NOT_EQ => Ok(native_boolean_to_boolean_object(left_value != right_value)),
-    _ => Err(EvalError(format!("Unknown operation: String {} String", operator))),
+    _ => Err(EvalError::new(format!("Unknown operation: String {} String", operator))),
   }
 }
 
+// Strings are ordered lexicographically by their characters, and only compare to other strings
+fn eval_string_comparison(operator: &str, left: Object, right: Object) -> Result<Object, EvalError> {
+  let (left_value, right_value) = match (&left, &right) {
+    (Object::String(left_value), Object::String(right_value)) => (left_value, right_value),
+    _ => return Err(EvalError::new(format!("Cannot compare {} with {}", left.type_name(), right.type_name()))),
+  };
+
+  let result = match operator {
+    token_types::LT => left_value < right_value,
+    token_types::GT => left_value > right_value,
+    token_types::LT_EQ => left_value <= right_value,
+    _ => left_value >= right_value,
+  };
+
+  Ok(native_boolean_to_boolean_object(result))
+}
+
 fn native_boolean_to_boolean_object(boolean: bool) -> Object {
   if boolean {
     TRUE_OBJECT
@@ -267,6 +694,12 @@ fn native_boolean_to_boolean_object(boolean: bool) -> Object {
   }
 }
 
+// Evaluates a whole program or function body, which is where a `return` stops. The parser only
+// accepts `break` and `continue` inside a loop of the same function, so they never get this far.
 pub fn eval(node: &impl EvalObject, env: &WrappedEnv) -> Result<Object, EvalError> {
-  node.eval(env)
+  match node.eval(env) {
+    Ok(object) | Err(Unwind::Return(object)) => Ok(object),
+    Err(Unwind::Error(error)) => Err(error),
+    Err(Unwind::Break) | Err(Unwind::Continue) => Err(EvalError::new("Found break or continue outside of a loop".to_string())),
+  }
 }
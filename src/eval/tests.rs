@@ -1,8 +1,13 @@
-use crate::eval::*;
+use crate::eval::MAX_CALL_DEPTH;
+use crate::eval::eval_error::*;
+use crate::eval::macro_expansion::*;
 use crate::lexer::*;
 use crate::parser::*;
 use crate::object::*;
+use crate::object::big_integer::BigInteger;
 use crate::object::environment::*;
+use crate::engine::*;
+use crate::engine::differential::*;
 use std::rc::Rc;
 use std::cell::RefCell;
 
@@ -23,6 +28,16 @@ fn test_eval_integer_expression() -> Result<(), String> {
     ("3 * 3 * 3 + 10", Object::Integer(37)),
     ("3 * (3 * 3) + 10", Object::Integer(37)),
     ("(5 + 10 * 2 + 15 / 3) * 2 + -10", Object::Integer(50)),
+    ("10 % 3", Object::Integer(1)),
+    ("-7 % 3", Object::Integer(-1)),
+    ("2 + 10 % 4 * 3", Object::Integer(8)),
+    ("2 ** 10", Object::Integer(1024)),
+    ("2 ** 3 ** 2", Object::Integer(512)),
+    ("3 * 2 ** 2", Object::Integer(12)),
+    ("-2 ** 2", Object::Integer(-4)),
+    ("(-2) ** 2", Object::Integer(4)),
+    ("-2 ** 2 * 3", Object::Integer(-12)),
+    ("5 ** 0", Object::Integer(1)),
   ];
 
   for test in &tests {
@@ -35,6 +50,97 @@ fn test_eval_integer_expression() -> Result<(), String> {
   Ok(())
 }
 
+#[test]
+fn test_eval_float_expression() -> Result<(), String> {
+  let tests = vec![
+    ("2.75", Object::Float(2.75)),
+    (".5", Object::Float(0.5)),
+    ("1e3", Object::Float(1000.0)),
+    ("-2.5", Object::Float(-2.5)),
+    ("1.5 + 1.5", Object::Float(3.0)),
+    ("1 + 0.5", Object::Float(1.5)),
+    ("0.5 * 4", Object::Float(2.0)),
+    ("7 / 2", Object::Integer(3)),
+    ("7 / 2.0", Object::Float(3.5)),
+    ("7.5 % 2", Object::Float(1.5)),
+    ("2 ** 0.5 ** 2", Object::Float(2f64.powf(0.25))),
+    ("0.1 + 0.2", Object::Float(0.30000000000000004)),
+    ("1 / 0.0", Object::Float(f64::INFINITY)),
+    ("-1 / 0.0", Object::Float(f64::NEG_INFINITY)),
+    ("0.0 / 0.0", Object::Float(f64::NAN)),
+    ("1 == 1.0", Object::Boolean(true)),
+    ("1.5 < 2", Object::Boolean(true)),
+    ("2 >= 2.5", Object::Boolean(false)),
+    ("let nan = 0.0 / 0.0; nan == nan", Object::Boolean(false)),
+    ("let nan = 0.0 / 0.0; nan != nan", Object::Boolean(true)),
+    ("let nan = 0.0 / 0.0; nan < 1 || nan >= 1", Object::Boolean(false)),
+    ("1 / 0.0 > 9223372036854775807", Object::Boolean(true)),
+    ("if (0.0) { 1 } else { 2 }", Object::Integer(2)),
+    ("if (0.0 / 0.0) { 1 } else { 2 }", Object::Integer(2)),
+    ("!0.5", Object::Boolean(false)),
+    ("\"pi: \" + 3.14", Object::String("pi: 3.14".to_string())),
+    ("[1.0, 2.5]", Object::Array(vec![Object::Float(1.0), Object::Float(2.5)])),
+  ];
+
+  for test in &tests {
+    let (input, result) = test;
+    let result_object = test_eval(input);
+    println!("input: {}, result: {:?}", input, result);
+    test_result(&result_object, result);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_big_integers() -> Result<(), String> {
+  let tests = vec![
+    ("9223372036854775807 + 1", "9223372036854775808"),
+    ("-9223372036854775807 - 2", "-9223372036854775809"),
+    ("4611686018427387904 * 2", "9223372036854775808"),
+    ("let min = -9223372036854775807 - 1; min / -1", "9223372036854775808"),
+    ("let min = -9223372036854775807 - 1; min % -1", "0"),
+    ("let min = -9223372036854775807 - 1; -min", "9223372036854775808"),
+    ("2 ** 64", "18446744073709551616"),
+    ("2 ** 100", "1267650600228229401496703205376"),
+    ("(-3) ** 41", "-36472996377170786403"),
+    ("10 ** 30 / 10 ** 21", "1000000000"),
+    ("(10 ** 30 + 7) % 10 ** 20", "7"),
+    ("-(10 ** 30 + 7) % 10 ** 20", "-7"),
+    ("-(10 ** 30) / 7", "-142857142857142857142857142857"),
+    ("(2 ** 64 + 1) * (2 ** 64 - 1)", "340282366920938463463374607431768211455"),
+    ("2 ** 128 / (2 ** 64 + 1)", "18446744073709551615"),
+    ("2 ** 64 - 2 ** 64", "0"),
+    ("(2 ** 64 + 5) - 2 ** 64", "5"),
+    ("10 ** 18 * 10 - 10 ** 19", "0"),
+    ("2 ** 64 > 2 ** 63", "True"),
+    ("-(2 ** 64) < 1", "True"),
+    ("2 ** 64 == 2 ** 64", "True"),
+    ("2 ** 64 != 2 ** 64 + 1", "True"),
+    ("2 ** 64 == 9223372036854775807 * 2 + 2", "True"),
+    ("2 ** 64 * 0.5", "9.223372036854776e18"),
+    ("let factorial = fn(n) { if (n < 2) { 1 } else { n * factorial(n - 1) } }; factorial(30)", "265252859812191058636308480000000"),
+    ("{2 ** 64: \"big\"}[2 ** 64]", "big"),
+    ("[1, 2][2 ** 64]", "Null"),
+    ("if (2 ** 64) { 1 } else { 2 }", "1"),
+    ("\"total: \" + 2 ** 64", "total: 18446744073709551616"),
+    ("10000000000000000000000", "10000000000000000000000"),
+    ("9223372036854775808 - 1", "9223372036854775807"),
+    ("-9223372036854775808", "-9223372036854775808"),
+    ("-9223372036854775808 == -9223372036854775807 - 1", "True"),
+    ("123456789012345678901234567890 % 1000000007", "197434842"),
+    ("99999999999999999999 * 3", "299999999999999999997"),
+    ("000000000000000000000000000042", "42"),
+  ];
+
+  for (input, expected) in tests {
+    let result = match_or_fail!(test_eval(input), Ok(m) => m);
+    assert_eq!(result.inspect(), expected, "input: {}", input);
+  }
+
+  Ok(())
+}
+
 #[test]
 fn test_eval_string_literal_expression() -> Result<(), String> {
   let tests = vec![
@@ -74,6 +180,17 @@ fn test_eval_boolean_expression() -> Result<(), String> {
     ("1 == 2", Object::Boolean(false)),
     ("1 != 1", Object::Boolean(false)),
     ("1 != 2", Object::Boolean(true)),
+    ("1 <= 1", Object::Boolean(true)),
+    ("2 <= 1", Object::Boolean(false)),
+    ("1 >= 2", Object::Boolean(false)),
+    ("2 >= 2", Object::Boolean(true)),
+    ("\"a\" < \"b\"", Object::Boolean(true)),
+    ("\"b\" < \"a\"", Object::Boolean(false)),
+    ("\"apple\" < \"apricot\"", Object::Boolean(true)),
+    ("\"ab\" > \"a\"", Object::Boolean(true)),
+    ("\"Z\" < \"a\"", Object::Boolean(true)),
+    ("\"a\" <= \"a\"", Object::Boolean(true)),
+    ("\"b\" >= \"c\"", Object::Boolean(false)),
   ];
 
   for test in &tests {
@@ -108,6 +225,37 @@ fn test_eval_bang_operator() -> Result<(), String> {
   Ok(())
 }
 
+#[test]
+fn test_logical_operators() -> Result<(), String> {
+  let tests = vec![
+    ("true && true", Object::Boolean(true)),
+    ("true && false", Object::Boolean(false)),
+    ("false || true", Object::Boolean(true)),
+    ("false || false", Object::Boolean(false)),
+    ("1 && 2", Object::Integer(2)),
+    ("0 && 2", Object::Integer(0)),
+    ("0 || \"default\"", Object::String("default".to_string())),
+    ("\"set\" || \"default\"", Object::String("set".to_string())),
+    ("[] && 1 == 1", Object::Boolean(true)),
+    ("false && missing", Object::Boolean(false)),
+    ("true || missing", Object::Boolean(true)),
+    ("let calls = 0; let f = fn() { calls = calls + 1 }; false && f(); true || f(); true && f(); calls", Object::Integer(1)),
+    ("1 < 2 && 2 < 3 || false", Object::Boolean(true)),
+    ("false && true || true", Object::Boolean(true)),
+    ("let nothing = if (false) { 1 }; nothing || 2", Object::Integer(2)),
+    ("let nothing = if (false) { 1 }; nothing && 2", Object::Null),
+  ];
+
+  for test in &tests {
+    let (input, result) = test;
+    let result_object = test_eval(input);
+    println!("input: {}, result: {:?}", input, result);
+    test_result(&result_object, result);
+  }
+
+  Ok(())
+}
+
 #[test]
 fn test_eval_if_expression() -> Result<(), String> {
   let tests = vec![
@@ -119,6 +267,8 @@ fn test_eval_if_expression() -> Result<(), String> {
     ("if (1 > 2) { 10 }", Object::Null),
     ("if (1 < 2) { 10 } else { 20 }", Object::Integer(10)),
     ("if (1 > 2) { 10 } else { 20 }", Object::Integer(20)),
+    ("if (true) { 5 } - 1", Object::Integer(4)),
+    ("if (true) { 5 } else { 0 } * 2", Object::Integer(10)),
   ];
 
   for test in &tests {
@@ -138,6 +288,10 @@ fn test_eval_return_statements() -> Result<(), String> {
     ("return 10; 9", Object::Integer(10)),
     ("return 2 * 5; 9", Object::Integer(10)),
     ("9; return 2 * 5; 9", Object::Integer(10)),
+    ("let f = fn() { let a = [if (true) { return 5 }]; 1 }; f()", Object::Integer(5)),
+    ("let f = fn() { 1 + if (true) { return 5 } else { 2 } }; f() * 2", Object::Integer(10)),
+    ("let f = fn(x) { let h = {\"a\": x}; h[if (x) { return \"early\" } else { \"a\" }] }; f(true)", Object::String("early".to_string())),
+    ("let f = fn() { while (true) { puts(if (true) { return 3 }) } }; f()", Object::Integer(3)),
   ];
 
   for test in &tests {
@@ -169,6 +323,159 @@ fn test_let_statements() -> Result<(), String> {
   Ok(())
 }
 
+#[test]
+fn test_assignments() -> Result<(), String> {
+  let tests = vec![
+    ("let a = 5; a = 10; a;", Object::Integer(10)),
+    ("let a = 5; a = a + 1", Object::Integer(6)),
+    ("let a = 1; let b = 1; a = b = 3; a + b", Object::Integer(6)),
+    ("let a = 1; let f = fn() { a = a + 1 }; f(); f(); a", Object::Integer(3)),
+    ("let a = 1; let f = fn(a) { a = 5; a }; f(2) + a", Object::Integer(6)),
+    ("let a = 1; if (true) { a = 2 }; a", Object::Integer(2)),
+    ("
+      let make_counter = fn() {
+        let count = 0;
+        fn() { count = count + 1 }
+      };
+
+      let counter = make_counter();
+      counter();
+      counter();
+      counter()
+    ", Object::Integer(3)),
+    ("const a = 5; a", Object::Integer(5)),
+    ("const a = 5; let f = fn() { let a = 1; a = 2; a }; f()", Object::Integer(2)),
+  ];
+
+  for test in &tests {
+    let (input, result) = test;
+    let result_object = test_eval(input);
+    println!("input: {}, result: {:?}", input, result);
+    test_result(&result_object, result);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_while_expressions() -> Result<(), String> {
+  let tests = vec![
+    ("let i = 0; while (i < 10) { i = i + 1 }; i", Object::Integer(10)),
+    ("while (false) { 1 }", Object::Null),
+    ("let i = 0; while (true) { i = i + 1; if (i == 5) { break } }; i", Object::Integer(5)),
+    ("
+      let i = 0;
+      let sum = 0;
+      while (i < 10) {
+        i = i + 1;
+        if (i > 3) { continue; }
+        sum = sum + i;
+      }
+      sum
+    ", Object::Integer(6)),
+    ("
+      let count = 0;
+      let i = 0;
+      while (i < 3) {
+        let j = 0;
+        while (true) {
+          if (j == 2) { break }
+          j = j + 1;
+          count = count + 1;
+        }
+        i = i + 1;
+      }
+      count
+    ", Object::Integer(6)),
+    ("let f = fn() { while (true) { return 7 } }; f()", Object::Integer(7)),
+    ("
+      let functions = [];
+      let i = 0;
+      while (i < 3) {
+        let value = i;
+        functions = push(functions, fn() { value });
+        i = i + 1;
+      }
+      functions[0]() + functions[2]()
+    ", Object::Integer(2)),
+    ("let i = 0; while (i < 100000) { i = i + 1 }; i", Object::Integer(100000)),
+    ("let r = 0; while (true) { r = 1 + if (true) { break } }; r", Object::Integer(0)),
+    ("
+      let r = [];
+      let x = 0;
+      while (x < 5) {
+        x = x + 1;
+        let c = x == 3;
+        r = push(r, [if (c) { break } else { x }]);
+      }
+      r
+    ", Object::Array(vec![Object::Array(vec![Object::Integer(1)]), Object::Array(vec![Object::Integer(2)])])),
+  ];
+
+  for test in &tests {
+    let (input, result) = test;
+    let result_object = test_eval(input);
+    println!("input: {}, result: {:?}", input, result);
+    test_result(&result_object, result);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_for_expressions() -> Result<(), String> {
+  let tests = vec![
+    ("let sum = 0; for (x in [1, 2, 3]) { sum = sum + x }; sum", Object::Integer(6)),
+    ("let sum = 0; for (i in 0..5) { sum = sum + i }; sum", Object::Integer(10)),
+    ("let sum = 0; for (i in 5..0) { sum = sum + i }; sum", Object::Integer(0)),
+    ("let result = \"\"; for (c in \"añb\") { result = c + result }; result", Object::String("bña".to_string())),
+    ("let keys = []; for (k in {\"b\": 1, \"a\": 2, 3: 3}) { keys = push(keys, k) }; keys", Object::Array(vec![
+      Object::String("b".to_string()),
+      Object::String("a".to_string()),
+      Object::Integer(3),
+    ])),
+    ("for (x in []) { x }", Object::Null),
+    ("let last = 0; for (i in 0..100) { if (i == 3) { break }; last = i }; last", Object::Integer(2)),
+    ("let sum = 0; for (i in 0..6) { if (i < 4) { continue }; sum = sum + i }; sum", Object::Integer(9)),
+    ("let f = fn() { for (i in 1..10) { if (i * i > 10) { return i } } }; f()", Object::Integer(4)),
+    ("
+      let functions = [];
+      for (i in 0..3) {
+        functions = push(functions, fn() { i * 10 });
+      }
+      functions[0]() + functions[1]() + functions[2]()
+    ", Object::Integer(30)),
+    ("
+      let pairs = [];
+      for (a in 0..3) {
+        for (b in 0..3) {
+          if (b > a) { break }
+          pairs = push(pairs, [a, b]);
+        }
+      }
+      len(pairs)
+    ", Object::Integer(6)),
+    ("let x = 0..3; x", Object::Range(0, 3)),
+    ("let r = []; for (x in 0..3) { r = push(r, if (x == 1) { continue } else { x }) }; r", Object::Array(vec![
+      Object::Integer(0),
+      Object::Integer(2),
+    ])),
+    ("let r = []; for (x in 0..3) { puts(if (x == 1) { continue } else { x }); r = push(r, x) }; r", Object::Array(vec![
+      Object::Integer(0),
+      Object::Integer(2),
+    ])),
+  ];
+
+  for test in &tests {
+    let (input, result) = test;
+    let result_object = test_eval(input);
+    println!("input: {}, result: {:?}", input, result);
+    test_result(&result_object, result);
+  }
+
+  Ok(())
+}
+
 #[test]
 fn test_function_calls() -> Result<(), String> {
   let tests = vec![
@@ -192,6 +499,23 @@ fn test_function_calls() -> Result<(), String> {
   Ok(())
 }
 
+// Both engines stop runaway recursion at the same depth. The tree-walker needs a large native
+// stack to get there, like the one the interpreter runs on.
+#[test]
+fn test_call_depth_limit() {
+  let test = || {
+    let recursion = "let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } };";
+
+    let result = match_or_fail!(test_eval(&format!("{} f({})", recursion, MAX_CALL_DEPTH - 1)), Ok(m) => m);
+    assert_eq!(result.inspect(), (MAX_CALL_DEPTH - 1).to_string());
+
+    let error = match_or_fail!(test_eval(&format!("{} f({})", recursion, MAX_CALL_DEPTH)), Err(m) => m);
+    assert_eq!(error.message, "Maximum call depth exceeded");
+  };
+
+  std::thread::Builder::new().stack_size(STACK_SIZE).spawn(test).unwrap().join().unwrap();
+}
+
 #[test]
 fn test_string_concatenation() -> Result<(), String> {
   let tests = vec![
@@ -213,6 +537,382 @@ fn test_string_concatenation() -> Result<(), String> {
   Ok(())
 }
 
+#[test]
+fn test_interpolated_strings() -> Result<(), String> {
+  let tests = vec![
+    ("let name = \"Ada\"; let age = 36; \"hello ${name}, you are ${age + 1}\"", "hello Ada, you are 37"),
+    ("\"${1}${2}\"", "12"),
+    ("\"list: ${[1, \"two\", 3.5]}, hash: ${{\"a\": true}}\"", "list: [1, \"two\", 3.5], hash: {\"a\": True}"),
+    ("\"nested ${\"inner ${1 + 1}\"}\"", "nested inner 2"),
+    ("\"${if (true) { let x = 2; x * 2 }}\"", "4"),
+    ("let greet = fn(name) { \"hi ${name}\" }; greet(\"you\")", "hi you"),
+    ("\"null: ${if (false) { 1 }}\"", "null: Null"),
+    ("\"escaped \\${1}\"", "escaped ${1}"),
+  ];
+
+  for (input, expected) in tests {
+    let result = match_or_fail!(test_eval(input), Ok(m) => m);
+    assert_eq!(result.inspect(), expected, "input: {}", input);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_array_literals() -> Result<(), String> {
+  let tests = vec![
+    ("[]", Object::Array(vec![])),
+    ("[1, 2 * 2, 3 + 3]", Object::Array(vec![Object::Integer(1), Object::Integer(4), Object::Integer(6)])),
+    ("[\"a\", [true]]", Object::Array(vec![
+      Object::String("a".to_string()),
+      Object::Array(vec![Object::Boolean(true)]),
+    ])),
+  ];
+
+  for test in &tests {
+    let (input, result) = test;
+    let result_object = test_eval(input);
+    println!("input: {}, result: {:?}", input, result);
+    test_result(&result_object, result);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_array_index_expressions() -> Result<(), String> {
+  let tests = vec![
+    ("[1, 2, 3][0]", Object::Integer(1)),
+    ("[1, 2, 3][1]", Object::Integer(2)),
+    ("[1, 2, 3][2]", Object::Integer(3)),
+    ("let i = 0; [1][i];", Object::Integer(1)),
+    ("[1, 2, 3][1 + 1];", Object::Integer(3)),
+    ("let my_array = [1, 2, 3]; my_array[2];", Object::Integer(3)),
+    ("let my_array = [1, 2, 3]; my_array[0] + my_array[1] + my_array[2];", Object::Integer(6)),
+    ("let my_array = [1, 2, 3]; let i = my_array[0]; my_array[i]", Object::Integer(2)),
+    ("[[1, 2], [3, 4]][1][0]", Object::Integer(3)),
+    ("[1, 2, 3][3]", Object::Null),
+    ("[1, 2, 3][-1]", Object::Null),
+  ];
+
+  for test in &tests {
+    let (input, result) = test;
+    let result_object = test_eval(input);
+    println!("input: {}, result: {:?}", input, result);
+    test_result(&result_object, result);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_string_index_and_slice_expressions() -> Result<(), String> {
+  let tests = vec![
+    ("\"hello\"[0]", Object::String("h".to_string())),
+    ("\"héllo\"[1]", Object::String("é".to_string())),
+    ("\"hello\"[5]", Object::Null),
+    ("\"hello\"[-1]", Object::Null),
+    ("\"hello\"[2 ** 64]", Object::Null),
+    ("\"hello\"[1:3]", Object::String("el".to_string())),
+    ("\"hello\"[:2]", Object::String("he".to_string())),
+    ("\"hello\"[3:]", Object::String("lo".to_string())),
+    ("\"hello\"[:]", Object::String("hello".to_string())),
+    ("\"日本語です\"[1:3]", Object::String("本語".to_string())),
+    ("\"hello\"[3:1]", Object::String("".to_string())),
+    ("\"hello\"[-5:100]", Object::String("hello".to_string())),
+    ("\"hello\"[0:2 ** 64]", Object::String("hello".to_string())),
+    ("[1, 2, 3, 4][1:3]", Object::Array(vec![Object::Integer(2), Object::Integer(3)])),
+    ("[1, 2, 3][2:]", Object::Array(vec![Object::Integer(3)])),
+    ("let s = \"abc\"; let i = 1; s[i:i + 1]", Object::String("b".to_string())),
+  ];
+
+  for test in &tests {
+    let (input, result) = test;
+    let result_object = test_eval(input);
+    println!("input: {}, result: {:?}", input, result);
+    test_result(&result_object, result);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_hash_literals() -> Result<(), String> {
+  let tests = vec![
+    ("{}", "{}"),
+    ("
+      let two = \"two\";
+      {
+        \"one\": 10 - 9,
+        two: 1 + 1,
+        \"thr\" + \"ee\": 6 / 2,
+        4: 4,
+        true: 5,
+        false: 6
+      }
+    ", "{\"one\": 1, \"two\": 2, \"three\": 3, 4: 4, True: 5, False: 6}"),
+    ("{1: 1, 2: 2, 1: 3}", "{1: 3, 2: 2}"),
+  ];
+
+  for test in &tests {
+    let (input, expected_inspect) = test;
+    let result_object = test_eval(input);
+    println!("input: {}, result: {:?}", input, expected_inspect);
+
+    let object = match_or_fail!(result_object, Ok(m) => m);
+    assert_eq!(&object.inspect(), expected_inspect);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_hash_index_expressions() -> Result<(), String> {
+  let tests = vec![
+    ("{\"foo\": 5}[\"foo\"]", Object::Integer(5)),
+    ("{\"foo\": 5}[\"bar\"]", Object::Null),
+    ("let key = \"foo\"; {\"foo\": 5}[key]", Object::Integer(5)),
+    ("{}[\"foo\"]", Object::Null),
+    ("{5: 5}[5]", Object::Integer(5)),
+    ("{true: 5}[true]", Object::Integer(5)),
+    ("{false: 5}[false]", Object::Integer(5)),
+  ];
+
+  for test in &tests {
+    let (input, result) = test;
+    let result_object = test_eval(input);
+    println!("input: {}, result: {:?}", input, result);
+    test_result(&result_object, result);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_builtin_functions() -> Result<(), String> {
+  let tests = vec![
+    ("len(\"\")", Object::Integer(0)),
+    ("len(\"four\")", Object::Integer(4)),
+    ("len(\"hello world\")", Object::Integer(11)),
+    ("len([1, 2, 3])", Object::Integer(3)),
+    ("len({1: 2})", Object::Integer(1)),
+    ("first([1, 2, 3])", Object::Integer(1)),
+    ("first([])", Object::Null),
+    ("last([1, 2, 3])", Object::Integer(3)),
+    ("last([])", Object::Null),
+    ("rest([1, 2, 3])", Object::Array(vec![Object::Integer(2), Object::Integer(3)])),
+    ("rest([])", Object::Null),
+    ("push([], 1)", Object::Array(vec![Object::Integer(1)])),
+    ("let a = [1]; push(a, 2); a", Object::Array(vec![Object::Integer(1)])),
+    ("puts(\"hello\")", Object::Null),
+    ("type(1)", Object::String("Integer".to_string())),
+    ("type(\"a\")", Object::String("String".to_string())),
+    ("type([])", Object::String("Array".to_string())),
+    ("type({})", Object::String("Hash".to_string())),
+    ("type(len)", Object::String("Builtin".to_string())),
+    ("type(fn() { 1 })", Object::String("Function".to_string())),
+    ("let len = fn(x) { 42 }; len([1])", Object::Integer(42)),
+    ("let f = fn() { return [1, 2]; }; len(f())", Object::Integer(2)),
+  ];
+
+  for test in &tests {
+    let (input, result) = test;
+    let result_object = test_eval(input);
+    println!("input: {}, result: {:?}", input, result);
+    test_result(&result_object, result);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_string_builtin_functions() -> Result<(), String> {
+  let tests = vec![
+    ("split(\"a,b,,c\", \",\")", "[\"a\", \"b\", \"\", \"c\"]"),
+    ("split(\"añb\", \"\")", "[\"a\", \"ñ\", \"b\"]"),
+    ("join([\"a\", \"b\", \"c\"], \"-\")", "a-b-c"),
+    ("join([], \"-\")", ""),
+    ("trim(\"  hi \\n\")", "hi"),
+    ("upper(\"straße\")", "STRASSE"),
+    ("lower(\"ÀB\")", "àb"),
+    ("replace(\"a-b-c\", \"-\", \"+\")", "a+b+c"),
+    ("contains(\"hello\", \"ell\")", "True"),
+    ("contains(\"hello\", \"xyz\")", "False"),
+    ("starts_with(\"hello\", \"he\")", "True"),
+    ("ends_with(\"hello\", \"he\")", "False"),
+    ("index_of(\"héllo\", \"l\")", "2"),
+    ("index_of(\"hello\", \"z\")", "Null"),
+    ("chars(\"añ\")", "[\"a\", \"ñ\"]"),
+    ("repeat(\"ab\", 3)", "ababab"),
+    ("repeat(\"ab\", 0)", ""),
+    ("repeat(\"\", 2 ** 64)", ""),
+  ];
+
+  for (input, expected) in tests {
+    let result = match_or_fail!(test_eval(input), Ok(m) => m);
+    assert_eq!(result.inspect(), expected, "input: {}", input);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_higher_order_builtin_functions() -> Result<(), String> {
+  let tests = vec![
+    ("map([1, 2, 3], fn(x) { x * 2 })", "[2, 4, 6]"),
+    ("map(1..4, fn(x) { x * x })", "[1, 4, 9]"),
+    ("map(\"ab\", upper)", "[\"A\", \"B\"]"),
+    ("filter([1, 2, 3, 4], fn(x) { x % 2 == 0 })", "[2, 4]"),
+    ("reduce([1, 2, 3], 10, fn(total, x) { total + x })", "16"),
+    ("reduce([], 0, fn(total, x) { total + x })", "0"),
+    ("let total = 0; each([1, 2, 3], fn(x) { total = total + x }); total", "6"),
+    ("sort([3, 1, 2])", "[1, 2, 3]"),
+    ("sort([\"b\", \"c\", \"a\"])", "[\"a\", \"b\", \"c\"]"),
+    ("sort([1, 3, 2], fn(a, b) { b - a })", "[3, 2, 1]"),
+    ("sort([[2, \"b\"], [1, \"a\"], [2, \"a\"]], fn(a, b) { a[0] - b[0] })", "[[1, \"a\"], [2, \"b\"], [2, \"a\"]]"),
+    ("sort([1, 3, 2], fn(a, b) { (b - a) * 2 ** 70 })", "[3, 2, 1]"),
+    ("sort([1.5, 0.5, 1.25], fn(a, b) { a - b })", "[0.5, 1.25, 1.5]"),
+    ("sort([0.0 / 0.0, 2, 0.0 / 0.0, 1.5, -1])", "[-1, 1.5, 2, NaN, NaN]"),
+    (
+      "sort(map(0..24, fn(x) { if (x % 5 == 0) { 0.0 / 0.0 } else { 24 - x } }))",
+      "[1, 2, 3, 5, 6, 7, 8, 10, 11, 12, 13, 15, 16, 17, 18, 20, 21, 22, 23, NaN, NaN, NaN, NaN, NaN]",
+    ),
+    // A comparator that is not a consistent order still gives every element back
+    (
+      "let seed = 1; let r = sort(0..100, fn(a, b) { seed = (seed * 1103515245 + 12345) % 2147483648; seed % 3 - 1 }); [len(r), sum(r)]",
+      "[100, 4950]",
+    ),
+    ("zip([1, 2, 3], [\"a\", \"b\"])", "[[1, \"a\"], [2, \"b\"]]"),
+    ("zip(0..9000000000000, [\"a\", \"b\"])", "[[0, \"a\"], [1, \"b\"]]"),
+    ("enumerate([\"a\", \"b\"])", "[[0, \"a\"], [1, \"b\"]]"),
+    ("any([1, 2, 3], fn(x) { x > 2 })", "True"),
+    ("any([], fn(x) { true })", "False"),
+    ("all([1, 2, 3], fn(x) { x > 0 })", "True"),
+    ("let calls = 0; all([1, 2, 3], fn(x) { calls = calls + 1; x < 1 }); calls", "1"),
+    ("any(0..9000000000000, fn(x) { x == 3 })", "True"),
+    ("let calls = 0; any(0..9000000000000, fn(x) { calls = calls + 1; x == 3 }); calls", "4"),
+    ("all(0..9000000000000, fn(x) { x < 5 })", "False"),
+    ("sum([1, 2, 3])", "6"),
+    ("sum([1, 2.5])", "3.5"),
+    ("sum([])", "0"),
+    ("sum([9223372036854775807, 1])", "9223372036854775808"),
+    ("min([3, 1, 2])", "1"),
+    ("max([3, 1.5, 2])", "3"),
+    ("max([])", "Null"),
+    ("map([[1, 2], [3]], fn(xs) { map(xs, fn(x) { x + 1 }) })", "[[2, 3], [4]]"),
+    ("let add = fn(n) { fn(x) { x + n } }; map([1, 2], add(10))", "[11, 12]"),
+    ("let f = fn(xs) { if (len(xs) == 0) { return 0 } sum(map(xs, fn(x) { return x * 2 })) }; f([1, 2])", "6"),
+  ];
+
+  for (input, expected) in tests {
+    let result = match_or_fail!(test_eval(input), Ok(m) => m);
+    assert_eq!(result.inspect(), expected, "input: {}", input);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_quote() -> Result<(), String> {
+  let tests = vec![
+    ("quote(5)", "Quote(5)"),
+    ("quote(5 + 8)", "Quote((5 + 8))"),
+    ("quote(foobar)", "Quote(foobar)"),
+    ("quote(foobar + barfoo)", "Quote((foobar + barfoo))"),
+    ("quote(unquote(4))", "Quote(4)"),
+    ("quote(unquote(4 + 4))", "Quote(8)"),
+    ("quote(8 + unquote(4 + 4))", "Quote((8 + 8))"),
+    ("quote(unquote(4 + 4) + 8)", "Quote((8 + 8))"),
+    ("let foobar = 8; quote(foobar)", "Quote(foobar)"),
+    ("let foobar = 8; quote(unquote(foobar))", "Quote(8)"),
+    ("quote(unquote(true))", "Quote(true)"),
+    ("quote(unquote(true == false))", "Quote(false)"),
+    ("quote(unquote(1.5))", "Quote(1.5)"),
+    ("quote(unquote([1, \"a\"]))", "Quote([1, a])"),
+    ("quote(unquote(quote(4 + 4)))", "Quote((4 + 4))"),
+    ("let quoted = quote(4 + 4); quote(unquote(4 + 4) + unquote(quoted))", "Quote((8 + (4 + 4)))"),
+    ("let f = fn(x) { quote(unquote(x) * 2) }; f(3)", "Quote((3 * 2))"),
+  ];
+
+  for (input, expected) in tests {
+    let result = match_or_fail!(test_eval(input), Ok(m) => m);
+    assert_eq!(result.inspect(), expected, "input: {}", input);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_define_macros() {
+  let input = "
+    let number = 1;
+    let function = fn(x, y) { x + y };
+    let mymacro = macro(x, y) { x + y; };
+  ";
+
+  let mut program = match_or_fail!(Parser::new(Lexer::new(input)).parse_program(), Ok(m) => m);
+  let env = Rc::new(RefCell::new(Environment::new()));
+
+  match_or_fail!(define_macros(&mut program, &env), Ok(m) => m);
+
+  assert_eq!(program.statements.len(), 2);
+  assert!(env.borrow().get("number").is_err());
+  assert!(env.borrow().get("function").is_err());
+
+  let (parameters, body) = match_or_fail!(env.borrow().get("mymacro"), Ok(Object::Macro(p, b, _)) => (p, b));
+  assert_eq!(parameters, vec!["x", "y"]);
+  assert_eq!(body.to_string(), "{\n(x + y)\n}\n");
+}
+
+#[test]
+fn test_expand_macros() {
+  let tests = vec![
+    ("let infix = macro() { quote(1 + 2); }; infix();", "(1 + 2)"),
+    ("let reverse = macro(a, b) { quote(unquote(b) - unquote(a)); }; reverse(2 + 2, 10 - 5);", "(10 - 5) - (2 + 2)"),
+    ("
+      let unless = macro(condition, consequence, alternative) {
+        quote(if (!(unquote(condition))) { unquote(consequence); } else { unquote(alternative); });
+      };
+
+      unless(10 > 5, puts(\"not greater\"), puts(\"greater\"));
+    ", "if (!(10 > 5)) { puts(\"not greater\") } else { puts(\"greater\") }"),
+    ("let twice = macro(x) { quote(unquote(x) + unquote(x)) }; twice(twice(a))", "(a + a) + (a + a)"),
+  ];
+
+  for (input, expected) in tests {
+    let mut program = match_or_fail!(Parser::new(Lexer::new(input)).parse_program(), Ok(m) => m);
+    let expected = match_or_fail!(Parser::new(Lexer::new(expected)).parse_program(), Ok(m) => m);
+    let env = Rc::new(RefCell::new(Environment::new()));
+
+    match_or_fail!(define_macros(&mut program, &env), Ok(m) => m);
+    let expanded = match_or_fail!(expand_macros(program, &env), Ok(m) => m);
+
+    assert_eq!(expanded.to_string(), expected.to_string(), "input: {}", input);
+  }
+}
+
+#[test]
+fn test_macros() -> Result<(), String> {
+  let tests = vec![
+    ("
+      let unless = macro(condition, consequence, alternative) {
+        quote(if (!(unquote(condition))) { unquote(consequence); } else { unquote(alternative); });
+      };
+
+      unless(10 > 5, \"not greater\", \"greater\");
+    ", "greater"),
+    ("let double = macro(x) { return quote(unquote(x) * 2); }; let y = 4; double(y + 1)", "10"),
+    ("let swap = macro(pair) { quote([unquote(pair)[1], unquote(pair)[0]]) }; swap([1, 2])", "[2, 1]"),
+  ];
+
+  for (input, expected) in tests {
+    let result = match_or_fail!(test_eval(input), Ok(m) => m);
+    assert_eq!(result.inspect(), expected, "input: {}", input);
+  }
+
+  Ok(())
+}
+
 #[test]
 fn test_error_handling() -> Result<(), String> {
   let tests = vec![
@@ -234,8 +934,72 @@ fn test_error_handling() -> Result<(), String> {
     ", "Unknown operation: Boolean + Boolean"),
     ("foobar", "Unknown identifier: foobar"),
     ("let foobar = 1; let foobar = 2;", "Identifier has already been declared: foobar"),
+    ("const foobar = 1; let foobar = 2;", "Identifier has already been declared: foobar"),
+    ("const foobar = 1; foobar = 2;", "Cannot assign to constant: foobar"),
+    ("const foobar = 1; let f = fn() { foobar = 2 }; f()", "Cannot assign to constant: foobar"),
+    ("foobar = 1", "Cannot assign to undeclared identifier: foobar"),
+    ("len = 1", "Cannot assign to undeclared identifier: len"),
+    ("for (x in 5) { x }", "Cannot iterate over Integer"),
+    ("for (x in [1]) { let x = 2 }", "Identifier has already been declared: x"),
+    ("true..false", "Unknown operation: Boolean .. Boolean"),
+    ("1.5..3", "Unknown operation: Float .. Integer"),
+    ("\"a ${missing} b\"", "Unknown identifier: missing"),
+    ("1.5 + true", "Expected number, found: Boolean(true)"),
+    ("{1.5: 1}", "Unusable as hash key: Float"),
+    ("\"a\" < 1", "Cannot compare String with Integer"),
+    ("true <= false", "Unknown operation: Boolean <= Boolean"),
+    ("2 ** -1", "Negative exponent: 2 ** -1"),
+    ("2 ** 4294967296", "Exponent is too large: 2 ** 4294967296"),
+    ("(2 ** 64) ** -1", "Negative exponent: 18446744073709551616 ** -1"),
+    ("1 / 0", "Division by zero: 1 / 0"),
+    ("5 % 0", "Division by zero: 5 % 0"),
+    ("(2 ** 64) / 0", "Division by zero: 18446744073709551616 / 0"),
+    ("(2 ** 64)..(2 ** 65)", "Range is too large: 18446744073709551616..36893488147419103232"),
+    ("2 ** 64 + true", "Expected integer, found: Boolean(true)"),
     ("let foobar = fn(a, b) { a + b }; foobar(1);", "Expected 2 arguments (a, b), got 1"),
     ("let foobar = 2; foobar(1);", "Expected function, found: Integer(2)"),
+    ("[1, 2][true]", "Array index must be an integer, found: Boolean(true)"),
+    ("5[0]", "Index operator not supported: Integer(5)"),
+    ("{\"name\": \"Monkey\"}[fn(x) { x }];", "Unusable as hash key: Function"),
+    ("{[1]: 2}", "Unusable as hash key: Array"),
+    ("len(1)", "Argument to len must be String, Array or Hash, got Integer"),
+    ("len(\"one\", \"two\")", "Expected 1 argument to len, got 2"),
+    ("first(1)", "Argument to first must be Array, got Integer"),
+    ("push(1, 1)", "Argument to push must be Array, got Integer"),
+    ("push([])", "Expected 2 arguments to push, got 1"),
+    ("\"abc\"[true]", "String index must be an integer, found: Boolean(true)"),
+    ("\"abc\"[\"a\":]", "Slice bounds must be integers, found: String(\"a\")"),
+    ("5[1:2]", "Slice operator not supported: Integer(5)"),
+    ("split(1, \",\")", "Argument to split must be String, got Integer"),
+    ("join([1, 2], \",\")", "Argument to join must be String, got Integer"),
+    ("upper()", "Expected 1 argument to upper, got 0"),
+    ("replace(\"abc\", \"\", \"x\")", "Cannot replace an empty string"),
+    ("repeat(\"a\", -1)", "Cannot repeat a string a negative number of times: -1"),
+    ("repeat(\"a\", \"b\")", "Argument to repeat must be Integer, got String"),
+    ("repeat(\"ab\", 9223372036854775807)", "Cannot repeat a string 9223372036854775807 times, the result would be longer than 1073741824 bytes"),
+    ("repeat(\"ab\", 2 ** 30)", "Cannot repeat a string 1073741824 times, the result would be longer than 1073741824 bytes"),
+    ("repeat(\"a\", 2 ** 64)", "Cannot repeat a string 18446744073709551616 times, the result would be longer than 1073741824 bytes"),
+    ("repeat(\"a\", -(2 ** 64))", "Cannot repeat a string a negative number of times: -18446744073709551616"),
+    ("map(1, fn(x) { x })", "Argument to map must be iterable, got Integer"),
+    ("map([1], 2)", "Expected function, found: Integer(2)"),
+    ("map([1], fn(a, b) { a })", "Expected 2 arguments (a, b), got 1"),
+    ("map([1, 0], fn(x) { 1 / x })", "Division by zero: 1 / 0"),
+    ("filter([1], fn(x) { x + true })", "Expected integer, found: Boolean(true)"),
+    ("sort([1, \"a\"])", "Cannot compare String with Integer"),
+    ("sort([1, 2], fn(a, b) { true })", "Comparator must return a number, got Boolean"),
+    ("sort([1, 2], fn(a, b) { missing })", "Unknown identifier: missing"),
+    ("sort(0..1000, fn(a, b) { if (a == 999 || b == 999) { missing } else { a - b } })", "Unknown identifier: missing"),
+    ("sort([1, 2], 1, 2)", "Expected 1 argument to sort, got 3"),
+    ("reduce([1], fn(a, b) { a })", "Expected 3 arguments to reduce, got 2"),
+    ("sum([\"a\"])", "Expected integer, found: String(\"a\")"),
+    ("quote(1, 2)", "Expected 1 argument to quote, got 2"),
+    ("quote(unquote(fn(x) { x }))", "Cannot unquote a value of type Function"),
+    ("quote(unquote(missing))", "Unknown identifier: missing"),
+    ("unquote(1)", "Unknown identifier: unquote"),
+    ("let m = macro(x) { 1 }; m(2)", "Macros must return a quoted expression, got Integer"),
+    ("let m = macro(x) { quote(x) }; m(1, 2)", "Expected 1 arguments (x), got 2"),
+    ("let m = macro(x) { quote(x) }; let m = macro(y) { quote(y) };", "Identifier has already been declared: m"),
+    ("let f = fn() { macro(x) { x } }; f()", "Macros can only be defined by top-level let statements"),
   ];
 
   for test in &tests {
@@ -244,20 +1008,65 @@ fn test_error_handling() -> Result<(), String> {
     println!("input: {}, expected error: {:?}", input, expected_error_message);
 
     let error = match_or_fail!(result_object, Err(m) => m);
-    let error_message = match_or_fail!(error, EvalError(m) => m);
+    let error_message = match_or_fail!(error, EvalError { message: m, .. } => m);
     assert_eq!(&error_message, expected_error_message)
   }
 
   Ok(())
 }
 
+#[test]
+fn test_arithmetic_error_kinds() -> Result<(), String> {
+  let tests = vec![
+    ("10 / 0", EvalErrorKind::DivisionByZero { operator: "/", left: BigInteger::from(10) }),
+    ("1 - 2 % 0", EvalErrorKind::DivisionByZero { operator: "%", left: BigInteger::from(2) }),
+    (
+      "(9223372036854775807 * 2) / 0",
+      EvalErrorKind::DivisionByZero { operator: "/", left: BigInteger::from(i64::MAX).mul(&BigInteger::from(2)) },
+    ),
+  ];
+
+  for (input, expected_kind) in tests {
+    let error = match_or_fail!(test_eval(input), Err(m) => m);
+    assert_eq!(error.kind, expected_kind, "input: {}", input);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_error_positions() -> Result<(), String> {
+  let tests = vec![
+    ("foobar", "1:1: Unknown identifier: foobar"),
+    ("let a = 1;\nlet b = a + true;", "2:9: Expected integer, found: Boolean(true)"),
+    ("let f = fn() {\n  1 + missing\n};\nf()", "2:7: Unknown identifier: missing"),
+    ("let a = 1;\n  let a = 2;", "2:3: Identifier has already been declared: a"),
+  ];
+
+  for test in &tests {
+    let (input, expected_error) = test;
+    let result_object = test_eval(input);
+    println!("input: {}, expected error: {:?}", input, expected_error);
+
+    let error = match_or_fail!(result_object, Err(m) => m);
+    assert_eq!(&error.to_string(), expected_error)
+  }
+
+  Ok(())
+}
+
 fn test_eval(input: &str) -> Result<Object, EvalError> {
   let lexer = Lexer::new(input);
   let mut parser = Parser::new(lexer);
 
   let program = match_or_fail!(parser.parse_program(), Ok(m) => m);
-  let env = Rc::new(RefCell::new(Environment::new()));
-  eval(&program, &env)
+  let result = Session::new(Engine::Eval).run(&program);
+
+  // Every program is also run on the virtual machine, which must agree with the tree-walker
+  let vm_result = Session::new(Engine::Vm).run(&program);
+  assert_eq!(Outcome::from_result(&result), Outcome::from_result(&vm_result), "input: {}", input);
+
+  result
 }
 
 fn test_result(actual_result: &Result<Object, EvalError>, expected_result: &Object) {
@@ -288,6 +1097,13 @@ fn test_result(actual_result: &Result<Object, EvalError>, expected_result: &Obje
         _ => panic!("Expected:\n\t{:?}\nGot:\n\t{:?}", expected_result, actual_result_value)
       }
     },
+    // Inspecting floats makes NaN comparable
+    Object::Float(_) | Object::Array(_) | Object::Range(_, _) => {
+      match expected_result {
+        Object::Float(_) | Object::Array(_) | Object::Range(_, _) => assert_eq!(actual_result_value.inspect(), expected_result.inspect()),
+        _ => panic!("Expected:\n\t{:?}\nGot:\n\t{:?}", expected_result, actual_result_value)
+      }
+    },
     _ => panic!("Expected:\n\t{:?}\nGot:\n\t{:?}", expected_result, actual_result_value)
   }
 }
@@ -0,0 +1,574 @@
+#[cfg(test)]
+mod tests;
+
+use std::cell::Cell;
+
+use crate::ast::*;
+use crate::ast::visitor::*;
+use crate::lexer::Lexer;
+use crate::parser::*;
+use crate::token::*;
+
+use block_statement::BlockStatement;
+use identifier::Identifier;
+use if_expression::IfExpression;
+use interpolated_string::StringPart;
+
+// Call arguments, array elements and hash pairs that would make a line longer than this are put on
+// lines of their own
+pub const MAX_WIDTH: usize = 100;
+const INDENT: &str = "  ";
+
+// Prints a program in the canonical style. The source it was parsed from is needed for what the
+// program itself does not keep: its comments and the blank lines between statements.
+pub fn format(program: &Program, source: &str) -> String {
+  let formatter = Formatter::new(program, source);
+  let mut formatted = formatter.statements(&program.statements, None, 0);
+
+  if !formatted.is_empty() {
+    formatted.push('\n');
+  }
+
+  formatted
+}
+
+struct Comment {
+  text: String,
+  span: Span,
+  // The start offset of the innermost block around the comment, which prints it
+  owner: Option<usize>,
+}
+
+// A statement or a comment on lines of its own, as it will be printed
+struct Item {
+  text: String,
+  start_line: usize,
+  end_line: usize,
+  ends_with_line_comment: bool,
+}
+
+struct Formatter {
+  comments: Vec<Comment>,
+  // The loop that starts the expression statement being formatted, see `leading_loop`
+  leading_loop: Cell<Option<*const Expression>>,
+}
+
+impl Formatter {
+  fn new(program: &Program, source: &str) -> Self {
+    let mut blocks = BlockCollector { spans: vec![] };
+    blocks.visit_program(program);
+
+    let mut lexer = Lexer::new(source).with_comments();
+    let mut comments = vec![];
+
+    loop {
+      let token = lexer.next_token();
+
+      match token.token_type {
+        token_types::EOF => break,
+        token_types::COMMENT => {
+          let offset = token.span.start.offset;
+          let owner = blocks
+            .spans
+            .iter()
+            .filter(|span| span.start.offset < offset && offset < span.end.offset)
+            .min_by_key(|span| span.end.offset - span.start.offset)
+            .map(|span| span.start.offset);
+
+          comments.push(Comment { text: token.literal.trim_end().to_string(), span: token.span, owner });
+        },
+        _ => {},
+      }
+    }
+
+    Formatter { comments, leading_loop: Cell::new(None) }
+  }
+
+  // Comments go before the statement they precede, or after the statement they share a line with
+  // or are inside of. A blank line between two statements is kept, more are merged into one.
+  fn statements(&self, statements: &[Statement], owner: Option<usize>, indent: usize) -> String {
+    let texts: Vec<String> = statements.iter().map(|statement| self.statement(statement, indent)).collect();
+    let mut comments = self.comments.iter().filter(|comment| comment.owner == owner).peekable();
+    let mut items: Vec<Item> = vec![];
+
+    for (index, statement) in statements.iter().enumerate() {
+      let span = statement.span();
+
+      while let Some(comment) = comments.next_if(|comment| comment.span.start.offset < span.start.offset) {
+        push_comment(&mut items, comment, indent);
+      }
+
+      let mut text = texts[index].clone();
+
+      if needs_semicolon(statement, texts.get(index + 1)) {
+        text.push(';');
+      }
+
+      items.push(Item {
+        text,
+        start_line: span.start.line,
+        end_line: span.end.line,
+        ends_with_line_comment: false,
+      });
+
+      while let Some(comment) = comments.next_if(|comment| comment.span.start.offset < span.end.offset) {
+        append_comment(items.last_mut().unwrap(), comment, indent);
+      }
+    }
+
+    for comment in comments {
+      push_comment(&mut items, comment, indent);
+    }
+
+    let mut formatted = String::new();
+
+    for (index, item) in items.iter().enumerate() {
+      if index > 0 {
+        formatted.push('\n');
+
+        if item.start_line > items[index - 1].end_line + 1 {
+          formatted.push('\n');
+        }
+      }
+
+      formatted.push_str(&INDENT.repeat(indent));
+      formatted.push_str(&item.text);
+    }
+
+    formatted
+  }
+
+  fn statement(&self, statement: &Statement, indent: usize) -> String {
+    let column = indent * INDENT.len();
+
+    match statement {
+      Statement::LetStatement(let_statement) => {
+        let prefix = format!("{} {} = ", let_statement.token_literal(), let_statement.name.value);
+        let value = self.expression(&let_statement.value, indent, column + prefix.len());
+
+        prefix + &value
+      },
+      Statement::ReturnStatement(return_statement) => {
+        format!("return {}", self.expression(&return_statement.return_value, indent, column + "return ".len()))
+      },
+      // An `if` on its own always spreads its blocks over several lines, like a loop does
+      Statement::Expression(Expression::IfExpression(if_expression)) => {
+        self.if_expression(if_expression, indent, column, false)
+      },
+      Statement::Expression(expression) => {
+        self.leading_loop.set(leading_loop(expression).map(|loop_expression| loop_expression as *const Expression));
+        self.expression(expression, indent, column)
+      },
+      Statement::BlockStatement(block_statement) => self.block(block_statement, indent, column, false),
+      Statement::BreakStatement(break_statement) => break_statement.token_literal(),
+      Statement::ContinueStatement(continue_statement) => continue_statement.token_literal(),
+    }
+  }
+
+  // Formats an expression that starts at the given column of a line indented `indent` times. Lines
+  // after the first one carry their own indentation.
+  fn expression(&self, expression: &Expression, indent: usize, column: usize) -> String {
+    match expression {
+      Expression::Identifier(identifier) => identifier.value.clone(),
+      Expression::BooleanLiteral(boolean_literal) => boolean_literal.value.to_string(),
+      Expression::IntegerLiteral(integer_literal) => integer_literal.token_literal(),
+      Expression::BigIntegerLiteral(big_integer_literal) => big_integer_literal.token_literal(),
+      Expression::FloatLiteral(float_literal) => float_literal.token_literal(),
+      Expression::StringLiteral(string_literal) => format!("\"{}\"", escape(&string_literal.value)),
+      Expression::InterpolatedString(interpolated_string) => {
+        let mut formatted = String::from("\"");
+
+        for part in &interpolated_string.parts {
+          match part {
+            StringPart::Literal(string) => formatted.push_str(&escape(string)),
+            StringPart::Expression(expression) => {
+              let part_column = end_column(column, &formatted) + "${".len();
+              formatted.push_str(&format!("${{{}}}", self.expression(expression, indent, part_column)));
+            },
+          }
+        }
+
+        formatted + "\""
+      },
+      Expression::PrefixExpression(prefix_expression) => {
+        let right = &prefix_expression.right;
+        let operand_column = column + prefix_expression.operator.len();
+
+        // The operand of a prefix operator may be a `**`, see `parse_prefix_expression`. The same
+        // operator twice in a row is kept apart, so `-(-x)` does not read as a `--` operator.
+        let repeats_operator = matches!(
+          &**right,
+          Expression::PrefixExpression(inner) if inner.operator == prefix_expression.operator
+        );
+        let needs_parentheses = precedence(right) < precedences::POWER || repeats_operator;
+
+        prefix_expression.operator.clone() + &self.operand(right, needs_parentheses, indent, operand_column)
+      },
+      Expression::InfixExpression(infix_expression) => {
+        let operator_precedence = get_operator_precedence(infix_expression.token.token_type);
+        // `**` groups from the right, every other operator from the left
+        let right_associative = infix_expression.operator == token_types::POWER;
+
+        let left = &infix_expression.left;
+        // `-a ** b` is `-(a ** b)`, so a prefix expression is grouped on the left of a `**`
+        let left_needs_parentheses = precedence(left) < operator_precedence
+          || (right_associative && precedence(left) == operator_precedence)
+          || (right_associative && matches!(**left, Expression::PrefixExpression(_)));
+        let left = self.operand(left, left_needs_parentheses, indent, column);
+
+        let operator = if infix_expression.operator == token_types::RANGE {
+          infix_expression.operator.clone()
+        } else {
+          format!(" {} ", infix_expression.operator)
+        };
+
+        let right = &infix_expression.right;
+        let right_needs_parentheses = precedence(right) < operator_precedence
+          || (!right_associative && precedence(right) == operator_precedence);
+        let right_column = end_column(column, &left) + operator.len();
+        let right = self.operand(right, right_needs_parentheses, indent, right_column);
+
+        left + &operator + &right
+      },
+      Expression::AssignExpression(assign_expression) => {
+        let prefix = format!("{} = ", assign_expression.name.value);
+        let value = self.expression(&assign_expression.value, indent, column + prefix.len());
+
+        prefix + &value
+      },
+      Expression::IfExpression(if_expression) => self.if_expression(if_expression, indent, column, true),
+      Expression::WhileExpression(while_expression) => {
+        let condition = self.expression(&while_expression.condition, indent, column + "while (".len());
+        let prefix = format!("while ({}) ", condition);
+        let body = self.block(&while_expression.body, indent, end_column(column, &prefix), false);
+
+        prefix + &body
+      },
+      Expression::ForExpression(for_expression) => {
+        let iterable_column = column + format!("for ({} in ", for_expression.variable.value).len();
+        let iterable = self.expression(&for_expression.iterable, indent, iterable_column);
+        let prefix = format!("for ({} in {}) ", for_expression.variable.value, iterable);
+        let body = self.block(&for_expression.body, indent, end_column(column, &prefix), false);
+
+        prefix + &body
+      },
+      Expression::FunctionLiteral(function_literal) => {
+        self.function("fn", &function_literal.arguments, &function_literal.body, indent, column)
+      },
+      Expression::MacroLiteral(macro_literal) => {
+        self.function("macro", &macro_literal.arguments, &macro_literal.body, indent, column)
+      },
+      Expression::CallExpression(call_expression) => {
+        let function = &call_expression.function;
+        let function = self.operand(function, precedence(function) < precedences::CALL, indent, column);
+
+        // A function literal passed last keeps its body below the call, instead of on lines of its own
+        let hug_last = matches!(call_expression.arguments.last(), Some(Expression::FunctionLiteral(_)));
+        let arguments = self.list(
+          ("(", ")"),
+          &call_expression.arguments,
+          hug_last,
+          indent,
+          end_column(column, &function),
+          &|argument, indent, column| self.expression(argument, indent, column),
+        );
+
+        function + &arguments
+      },
+      Expression::IndexExpression(index_expression) => {
+        let left = &index_expression.left;
+        let left = self.operand(left, precedence(left) < precedences::CALL, indent, column);
+        let index = self.expression(&index_expression.index, indent, end_column(column, &left) + 1);
+
+        format!("{}[{}]", left, index)
+      },
+      Expression::SliceExpression(slice_expression) => {
+        let left = &slice_expression.left;
+        let left = self.operand(left, precedence(left) < precedences::CALL, indent, column);
+        let start_column = end_column(column, &left) + 1;
+        let start = match &slice_expression.start {
+          Some(start) => self.expression(start, indent, start_column),
+          None => String::new(),
+        };
+        let end = match &slice_expression.end {
+          Some(end) => self.expression(end, indent, end_column(start_column, &start) + 1),
+          None => String::new(),
+        };
+
+        format!("{}[{}:{}]", left, start, end)
+      },
+      Expression::ArrayLiteral(array_literal) => {
+        self.list(("[", "]"), &array_literal.elements, false, indent, column, &|element, indent, column| {
+          self.expression(element, indent, column)
+        })
+      },
+      Expression::HashLiteral(hash_literal) => {
+        self.list(("{", "}"), &hash_literal.pairs, false, indent, column, &|(key, value), indent, column| {
+          let key = self.expression(key, indent, column);
+          let value = self.expression(value, indent, end_column(column, &key) + ": ".len());
+
+          format!("{}: {}", key, value)
+        })
+      },
+    }
+  }
+
+  fn operand(&self, expression: &Expression, needs_parentheses: bool, indent: usize, column: usize) -> String {
+    let is_leading_loop = self.leading_loop.get().is_some_and(|leading_loop| std::ptr::eq(leading_loop, expression));
+
+    if needs_parentheses || is_leading_loop {
+      format!("({})", self.expression(expression, indent, column + 1))
+    } else {
+      self.expression(expression, indent, column)
+    }
+  }
+
+  fn if_expression(&self, if_expression: &IfExpression, indent: usize, column: usize, allow_inline: bool) -> String {
+    let condition = self.expression(&if_expression.condition, indent, column + "if (".len());
+    let prefix = format!("if ({}) ", condition);
+    let block_column = end_column(column, &prefix);
+
+    if allow_inline {
+      if let Some(true_block) = self.inline_block(&if_expression.true_block, block_column) {
+        match &*if_expression.false_block_or_none {
+          None => return prefix + &true_block,
+          Some(false_block) => {
+            let false_block_column = block_column + true_block.len() + " else ".len();
+
+            if let Some(false_block) = self.inline_block(false_block, false_block_column) {
+              return format!("{}{} else {}", prefix, true_block, false_block)
+            }
+          },
+        }
+      }
+    }
+
+    let mut formatted = prefix + &self.block(&if_expression.true_block, indent, block_column, false);
+
+    if let Some(false_block) = &*if_expression.false_block_or_none {
+      let false_block_column = end_column(column, &formatted) + " else ".len();
+      formatted.push_str(" else ");
+      formatted.push_str(&self.block(false_block, indent, false_block_column, false));
+    }
+
+    formatted
+  }
+
+  fn function(&self, keyword: &str, arguments: &[Identifier], body: &BlockStatement, indent: usize, column: usize) -> String {
+    let arguments: Vec<String> = arguments.iter().map(|argument| argument.value.clone()).collect();
+    let prefix = format!("{}({}) ", keyword, arguments.join(", "));
+    let body = self.block(body, indent, column + prefix.len(), true);
+
+    prefix + &body
+  }
+
+  fn block(&self, block: &BlockStatement, indent: usize, column: usize, allow_inline: bool) -> String {
+    if allow_inline {
+      if let Some(formatted) = self.inline_block(block, column) {
+        return formatted
+      }
+    }
+
+    let statements = self.statements(&block.statements, Some(block.span.start.offset), indent + 1);
+
+    if statements.is_empty() {
+      return "{}".to_string()
+    }
+
+    format!("{{\n{}\n{}}}", statements, INDENT.repeat(indent))
+  }
+
+  // A block with a single expression, without blocks or comments of its own, fits on one line
+  // when it is short enough
+  fn inline_block(&self, block: &BlockStatement, column: usize) -> Option<String> {
+    let expression = match block.statements.as_slice() {
+      [Statement::Expression(expression)] => expression,
+      _ => return None,
+    };
+
+    let mut blocks = BlockCollector { spans: vec![] };
+    blocks.visit_expression(expression);
+
+    let has_comments = self
+      .comments
+      .iter()
+      .any(|comment| block.span.start.offset < comment.span.start.offset && comment.span.start.offset < block.span.end.offset);
+
+    if !blocks.spans.is_empty() || has_comments {
+      return None
+    }
+
+    let formatted = format!("{{ {} }}", self.expression(expression, 0, column + "{ ".len()));
+
+    if formatted.contains('\n') || column + formatted.chars().count() > MAX_WIDTH {
+      return None
+    }
+
+    Some(formatted)
+  }
+
+  // Puts the items on the line of the delimiters when they fit, or each on a line of its own.
+  // `hug_last` lets the last item spread over several lines and still stay on the first one.
+  fn list<T>(
+    &self,
+    (open, close): (&str, &str),
+    items: &[T],
+    hug_last: bool,
+    indent: usize,
+    column: usize,
+    format_item: &dyn Fn(&T, usize, usize) -> String,
+  ) -> String {
+    if items.is_empty() {
+      return format!("{}{}", open, close)
+    }
+
+    let mut formatted = open.to_string();
+    let mut fits = true;
+
+    for (index, item) in items.iter().enumerate() {
+      if index > 0 {
+        formatted.push_str(", ");
+      }
+
+      let item = format_item(item, indent, end_column(column, &formatted));
+
+      if item.contains('\n') && !(hug_last && index == items.len() - 1) {
+        fits = false;
+      }
+
+      formatted.push_str(&item);
+    }
+
+    formatted.push_str(close);
+
+    let first_line = formatted.lines().next().unwrap_or_default();
+
+    if fits && column + first_line.chars().count() <= MAX_WIDTH {
+      return formatted
+    }
+
+    let item_indentation = INDENT.repeat(indent + 1);
+    let items: Vec<String> = items
+      .iter()
+      .map(|item| format!("{}{}", item_indentation, format_item(item, indent + 1, item_indentation.len())))
+      .collect();
+
+    format!("{}\n{}\n{}{}", open, items.join(",\n"), INDENT.repeat(indent), close)
+  }
+}
+
+struct BlockCollector {
+  spans: Vec<Span>,
+}
+
+impl Visitor for BlockCollector {
+  fn visit_block_statement(&mut self, block_statement: &BlockStatement) {
+    self.spans.push(block_statement.span);
+    walk_block_statement(self, block_statement);
+  }
+}
+
+// A comment on the line a statement or another comment ends on stays there, any other gets a line
+// of its own
+fn push_comment(items: &mut Vec<Item>, comment: &Comment, indent: usize) {
+  if let Some(item) = items.last_mut() {
+    if item.end_line == comment.span.start.line {
+      return append_comment(item, comment, indent)
+    }
+  }
+
+  items.push(Item {
+    text: comment.text.clone(),
+    start_line: comment.span.start.line,
+    end_line: comment.span.end.line,
+    ends_with_line_comment: false,
+  });
+}
+
+// Nothing can follow a line comment on its line, so the comments after one start a new line
+fn append_comment(item: &mut Item, comment: &Comment, indent: usize) {
+  if item.ends_with_line_comment {
+    item.text.push('\n');
+    item.text.push_str(&INDENT.repeat(indent));
+  } else {
+    item.text.push(' ');
+  }
+
+  item.text.push_str(&comment.text);
+  item.end_line = item.end_line.max(comment.span.end.line);
+  item.ends_with_line_comment = comment.text.starts_with("//");
+}
+
+// Statements end with a semicolon, except for `if`s and loops, which end with a block. An `if`
+// still needs one when the next statement would otherwise continue it, like `(a)` calling the `if`.
+fn needs_semicolon(statement: &Statement, next_statement: Option<&String>) -> bool {
+  match statement {
+    Statement::Expression(Expression::IfExpression(_)) => {
+      matches!(next_statement.and_then(|text| text.chars().next()), Some('(') | Some('[') | Some('-'))
+    },
+    Statement::Expression(Expression::WhileExpression(_))
+    | Statement::Expression(Expression::ForExpression(_))
+    | Statement::BlockStatement(_) => false,
+    _ => true,
+  }
+}
+
+// The loop an expression starts with when it is not a loop itself, like the one in
+// `(for (x in y) { x })[0]`. At the start of a statement that loop would end the statement, so it is
+// put in parentheses.
+fn leading_loop(expression: &Expression) -> Option<&Expression> {
+  let first = match expression {
+    Expression::InfixExpression(infix_expression) => &infix_expression.left,
+    Expression::CallExpression(call_expression) => &call_expression.function,
+    Expression::IndexExpression(index_expression) => &index_expression.left,
+    Expression::SliceExpression(slice_expression) => &slice_expression.left,
+    _ => return None,
+  };
+
+  match **first {
+    Expression::WhileExpression(_) | Expression::ForExpression(_) => Some(first),
+    _ => leading_loop(first),
+  }
+}
+
+// How tightly an expression holds together, to tell when it needs parentheses as an operand
+fn precedence(expression: &Expression) -> Precedence {
+  match expression {
+    Expression::InfixExpression(infix_expression) => get_operator_precedence(infix_expression.token.token_type),
+    Expression::AssignExpression(_) => precedences::ASSIGN,
+    Expression::PrefixExpression(_) => precedences::PREFIX,
+    Expression::CallExpression(_) => precedences::CALL,
+    Expression::IndexExpression(_) | Expression::SliceExpression(_) => precedences::INDEX,
+    _ => Precedence::MAX,
+  }
+}
+
+// The column a text ends on when it starts at the given column
+fn end_column(column: usize, text: &str) -> usize {
+  match text.rfind('\n') {
+    Some(position) => text[position + 1..].chars().count(),
+    None => column + text.chars().count(),
+  }
+}
+
+// Writes a string so that the lexer reads it back unchanged
+fn escape(string: &str) -> String {
+  let mut escaped = String::new();
+  let mut chars = string.chars().peekable();
+
+  while let Some(ch) = chars.next() {
+    match ch {
+      '\\' => escaped.push_str("\\\\"),
+      '"' => escaped.push_str("\\\""),
+      '\n' => escaped.push_str("\\n"),
+      '\r' => escaped.push_str("\\r"),
+      '\t' => escaped.push_str("\\t"),
+      '\0' => escaped.push_str("\\0"),
+      '$' if chars.peek() == Some(&'{') => escaped.push_str("\\$"),
+      ch if ch.is_control() => escaped.push_str(&format!("\\u{{{:x}}}", ch as u32)),
+      ch => escaped.push(ch),
+    }
+  }
+
+  escaped
+}
@@ -0,0 +1,163 @@
+use std::fs;
+use std::path::Path;
+
+use crate::ast::Program;
+use crate::formatter::*;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+
+fn parse(input: &str) -> Program {
+  match_or_fail!(Parser::new(Lexer::new(input)).parse_program(), Ok(m) => m)
+}
+
+fn format_source(input: &str) -> String {
+  format(&parse(input), input)
+}
+
+#[test]
+fn test_format() {
+  let tests = vec![
+    ("", ""),
+    ("let   x=1", "let x = 1;\n"),
+    ("const x = 1.50", "const x = 1.50;\n"),
+    ("x = y = 2", "x = y = 2;\n"),
+    ("(1 + 2) * 3 - (4 - 5) - (6 + 7)", "(1 + 2) * 3 - (4 - 5) - (6 + 7);\n"),
+    ("((1 * 2) + 3)", "1 * 2 + 3;\n"),
+    ("2 ** (3 ** 2) + (2 ** 3) ** 2", "2 ** 3 ** 2 + (2 ** 3) ** 2;\n"),
+    ("-(-x) + !(a == b) + -f(x)", "-(-x) + !(a == b) + -f(x);\n"),
+    ("- -x + !!y + -!z + !-w", "-(-x) + !(!y) + -!z + !-w;\n"),
+    ("-(2 ** x) + (-2) ** x + 2 ** -x", "-2 ** x + (-2) ** x + 2 ** -x;\n"),
+    ("(-f)(x)[0] + (a + b)[1]", "(-f)(x)[0] + (a + b)[1];\n"),
+    ("(a = 1) + 2", "(a = 1) + 2;\n"),
+    ("true && (a || b)", "true && (a || b);\n"),
+    ("1 .. 10", "1..10;\n"),
+    ("s[1:] + s[:2] + s[:] + s[1:2]", "s[1:] + s[:2] + s[:] + s[1:2];\n"),
+    ("[ 1,2 ]; {}; { \"a\" :1 }; []", "[1, 2];\n{};\n{\"a\": 1};\n[];\n"),
+    ("\"tab\\t \\\"quoted\\\" \\\\ \\${x}\"", "\"tab\\t \\\"quoted\\\" \\\\ \\${x}\";\n"),
+    ("\"a ${ x + 1 } b ${\"c${d}\"}$\"", "\"a ${x + 1} b ${\"c${d}\"}$\";\n"),
+    ("let f = fn(a,b){a+b}", "let f = fn(a, b) { a + b };\n"),
+    ("let f = fn() {}", "let f = fn() {};\n"),
+    ("let f = fn(x) { return x; }", "let f = fn(x) {\n  return x;\n};\n"),
+    ("let f = fn(x) { fn(y) { x + y } }", "let f = fn(x) {\n  fn(y) { x + y };\n};\n"),
+    ("let m = macro(a) { quote(unquote(a)) }", "let m = macro(a) { quote(unquote(a)) };\n"),
+    ("let a = if (x) { 1 } else { 2 }", "let a = if (x) { 1 } else { 2 };\n"),
+    ("let a = if (x) { 1 } else { let b = 2; b }", "let a = if (x) {\n  1;\n} else {\n  let b = 2;\n  b;\n};\n"),
+    ("if (x) { 1 }", "if (x) {\n  1;\n}\n"),
+    (
+      "while (i < 3) { i = i + 1; if (i == 2) { break } else { continue } }",
+      "while (i < 3) {\n  i = i + 1;\n  if (i == 2) {\n    break;\n  } else {\n    continue;\n  }\n}\n",
+    ),
+    ("for (x in 1..3) { puts(x) }", "for (x in 1..3) {\n  puts(x);\n}\n"),
+    ("while (x) {}", "while (x) {}\n"),
+    ("let a = 1;\n\n\n\nlet b = 2;\nlet c = 3;", "let a = 1;\n\nlet b = 2;\nlet c = 3;\n"),
+    ("(fn(x) { x })(1)", "fn(x) { x }(1);\n"),
+  ];
+
+  for (input, expected) in tests {
+    assert_eq!(format_source(input), expected, "input: {}", input);
+  }
+}
+
+// `if`s and loops end with a block. An `if` only needs a semicolon when the next statement would
+// continue it, and a loop that starts a longer expression is put in parentheses to keep it from
+// ending the statement.
+#[test]
+fn test_format_semicolons() {
+  let tests = vec![
+    ("if (x) { 1 }; -1", "if (x) {\n  1;\n};\n-1;\n"),
+    ("if (x) { 1 }; [1]", "if (x) {\n  1;\n};\n[1];\n"),
+    ("while (x) { 1 }; (a + b) * c", "while (x) {\n  1;\n}\n(a + b) * c;\n"),
+    ("for (x in y) { 1 }; a", "for (x in y) {\n  1;\n}\na;\n"),
+    ("if (x) { 1 } else { 2 } + 3", "if (x) { 1 } else { 2 } + 3;\n"),
+    ("(for (x in y) { x })[0] - 1", "(for (x in y) {\n  x;\n})[0] - 1;\n"),
+    ("let a = (while (x) { 1 }) + 3", "let a = while (x) {\n  1;\n} + 3;\n"),
+  ];
+
+  for (input, expected) in tests {
+    assert_eq!(format_source(input), expected, "input: {}", input);
+  }
+}
+
+#[test]
+fn test_format_comments() {
+  let tests = vec![
+    ("// only a comment", "// only a comment\n"),
+    ("// first\nlet a = 1; // trailing   \n/* block */ let b = 2;", "// first\nlet a = 1; // trailing\n/* block */\nlet b = 2;\n"),
+    ("let a = 1;\n\n// after a blank line\n\n\nlet b = 2;", "let a = 1;\n\n// after a blank line\n\nlet b = 2;\n"),
+    ("let f = fn() {\n// todo\n}", "let f = fn() {\n  // todo\n};\n"),
+    ("let f = fn(x) { /* inside */ x }", "let f = fn(x) {\n  /* inside */\n  x;\n};\n"),
+    ("let f = fn(x) {\n  x // last\n  // before the brace\n}", "let f = fn(x) {\n  x; // last\n  // before the brace\n};\n"),
+    ("foo(1, // one\n  2); /* two */ // three", "foo(1, 2); // one\n/* two */ // three\n"),
+    ("if (x) { 1 } // then\nelse { 2 }", "if (x) {\n  1;\n} else {\n  2;\n} // then\n"),
+    ("/* a\n   b */\nx", "/* a\n   b */\nx;\n"),
+  ];
+
+  for (input, expected) in tests {
+    assert_eq!(format_source(input), expected, "input: {}", input);
+  }
+}
+
+#[test]
+fn test_format_wraps_long_lines() {
+  let tests = vec![
+    (
+      "let result = some_function(first_argument_value, second_argument_value, third_argument_value, 4444);",
+      "let result = some_function(first_argument_value, second_argument_value, third_argument_value, 4444);\n",
+    ),
+    (
+      "let result = some_function(first_argument_value, second_argument_value, third_argument_value, 444444);",
+      "let result = some_function(\n  first_argument_value,\n  second_argument_value,\n  third_argument_value,\n  444444\n);\n",
+    ),
+    (
+      "let nested = outer(inner_function_name(first_argument_value, second_argument_value), third_value_long);",
+      "let nested = outer(\n  inner_function_name(first_argument_value, second_argument_value),\n  third_value_long\n);\n",
+    ),
+    (
+      "let people = [{\"name\": \"Alice\", \"age\": 30}, {\"name\": \"Bob\", \"age\": 25}, {\"name\": \"Carol\", \"age\": 35}];",
+      "let people = [\n  {\"name\": \"Alice\", \"age\": 30},\n  {\"name\": \"Bob\", \"age\": 25},\n  {\"name\": \"Carol\", \"age\": 35}\n];\n",
+    ),
+    (
+      "each(items, fn(item) { puts(item); puts(item * 2) })",
+      "each(items, fn(item) {\n  puts(item);\n  puts(item * 2);\n});\n",
+    ),
+    (
+      "each(items, fn(item) { puts(item) }, fn(item) { puts(item); puts(item) })",
+      "each(items, fn(item) { puts(item) }, fn(item) {\n  puts(item);\n  puts(item);\n});\n",
+    ),
+    (
+      "each(fn(item) { puts(item); puts(item) }, items)",
+      "each(\n  fn(item) {\n    puts(item);\n    puts(item);\n  },\n  items\n);\n",
+    ),
+  ];
+
+  for (input, expected) in tests {
+    assert_eq!(format_source(input), expected, "input: {}", input);
+  }
+}
+
+// Formatting a formatted program changes nothing, and never changes what the program is
+#[test]
+fn test_format_is_idempotent_and_round_trip_safe() {
+  let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+  let mut sources = vec![
+    "let a = (1 + 2) * 3 - -x + -(-x) + !!y; /* a */ /* b\n c */ /* d */\nlet b = a;".to_string(),
+    "let p = (-a) ** 2 - -b ** 2 + -(c ** 2) + (-d) ** -e;".to_string(),
+    "let s = \"line\\nbreak ${a + \"${b}\"} $ {} \\${c}\";".to_string(),
+    "foo(1, // one\n  2); /* two */ // three\n\n/* lonely */\n\n\nbar();".to_string(),
+    "let f = fn(x) {\n  if (x) { return 1; }\n  // trailing\n}; f(1)[0]".to_string(),
+    "if (a) { 1 } else { 2 }; [1, 2]; while (false) { }; -1".to_string(),
+    "(if (a) { 1 } else { 2 }) - 1; (for (x in y) { x })[0]".to_string(),
+    format!("let deep = f(g(h({}, {}), {}), fn(x) {{ x }});", "a".repeat(40), "b".repeat(40), "c".repeat(40)),
+  ];
+
+  for entry in fs::read_dir(directory).unwrap() {
+    sources.push(fs::read_to_string(entry.unwrap().path()).unwrap());
+  }
+
+  for source in sources {
+    let formatted = format_source(&source);
+
+    assert_eq!(format_source(&formatted), formatted, "source: {}", source);
+    assert_eq!(parse(&formatted).to_string(), parse(&source).to_string(), "source: {}", source);
+  }
+}
@@ -8,6 +8,12 @@ pub struct Lexer {
   position: usize,
   read_position: usize,
   ch: Option<char>,
+  current_position: Position,
+  // Comments are skipped like whitespace, unless a tool like a formatter needs to keep them
+  emit_comments: bool,
+  // The number of unclosed braces in each interpolation being lexed, innermost last. A closing
+  // brace without unclosed braces ends the interpolation and continues the string.
+  interpolations: Vec<usize>,
 }
 
 impl Lexer {
@@ -17,6 +23,9 @@ impl Lexer {
       position: 0,
       read_position: 0,
       ch: None,
+      current_position: Position::default(),
+      emit_comments: false,
+      interpolations: vec![],
     };
 
     lexer.read_char();
@@ -24,9 +33,18 @@ impl Lexer {
     return lexer;
   }
 
+  pub fn with_comments(mut self) -> Lexer {
+    self.emit_comments = true;
+    self
+  }
+
   pub fn read_char(&mut self) {
     use std::convert::TryInto;
 
+    if let Some(ch) = self.ch {
+      self.advance_position(ch);
+    }
+
     if self.read_position >= self.chars.len().try_into().unwrap() {
       self.ch = None;
     }
@@ -38,7 +56,25 @@ impl Lexer {
     self.read_position += 1;
   }
 
-  pub fn peak_char(&mut self) -> Option<char> {
+  fn advance_position(&mut self, ch: char) {
+    let position = &mut self.current_position;
+
+    position.offset += ch.len_utf8();
+
+    if ch == '\n' {
+      position.line += 1;
+      position.column = 1;
+    }
+    else {
+      position.column += 1;
+    }
+  }
+
+  pub fn current_position(&self) -> Position {
+    self.current_position
+  }
+
+  pub fn peak_char(&self) -> Option<char> {
     use std::convert::TryInto;
 
     if self.read_position >= self.chars.len().try_into().unwrap() {
@@ -55,7 +91,7 @@ impl Lexer {
     }
     else {
       let ch = self.ch.unwrap();
-      ('a' <= ch && ch <= 'z') || ('A' <= ch && ch <= 'Z') || ch == '_'
+      ch.is_alphabetic() || ch == '_'
     }
   }
 
@@ -96,103 +132,352 @@ impl Lexer {
     self.chars[position..self.position].into_iter().collect()
   }
 
-  pub fn read_string(&mut self) -> String {
+  // Reads an integer or a float like `3.14`, `.5` or `1e-9`. A dot only starts a fraction when a
+  // digit follows it, so that `1..3` is still a range.
+  pub fn read_number(&mut self) -> (TokenType, String) {
+    let position = self.position;
+    let mut token_type = token_types::INT;
+
+    self.read_digit();
+
+    if self.ch == Some('.') && matches!(self.peak_char(), Some(ch) if ch.is_ascii_digit()) {
+      token_type = token_types::FLOAT;
+      self.read_char();
+      self.read_digit();
+    }
+
+    if let Some('e') | Some('E') = self.ch {
+      let exponent_start = match self.peak_char() {
+        Some('+') | Some('-') => self.chars.get(self.read_position + 1).copied(),
+        ch => ch,
+      };
+
+      if matches!(exponent_start, Some(ch) if ch.is_ascii_digit()) {
+        token_type = token_types::FLOAT;
+        self.read_char();
+
+        if let Some('+') | Some('-') = self.ch {
+          self.read_char();
+        }
+
+        self.read_digit();
+      }
+    }
+
+    (token_type, self.chars[position..self.position].iter().collect())
+  }
+
+  // Reads a string, or the part of one up to or after an interpolation, and tells whether it ended
+  // by starting an interpolation.
+  pub fn read_string_token(&mut self, after_interpolation: bool) -> (TokenType, Literal) {
+    let (result, interpolates) = self.read_string();
+
+    if interpolates {
+      self.interpolations.push(0);
+    }
+
+    let token_type = match (after_interpolation, interpolates) {
+      (false, false) => token_types::STRING,
+      (false, true) => token_types::STRING_START,
+      (true, true) => token_types::STRING_MIDDLE,
+      (true, false) => token_types::STRING_END,
+    };
+
+    match result {
+      Ok(string) => (token_type, string),
+      Err(message) => (token_types::ILLEGAL, message),
+    }
+  }
+
+  // Reads until the closing quote or the start of an interpolation, even after an invalid escape,
+  // so that lexing can continue after the string. The error is the message of the first problem in
+  // the string.
+  pub fn read_string(&mut self) -> (Result<String, String>, bool) {
     let mut string_chars = vec![];
+    let mut error = None;
+    let mut interpolates = false;
 
     loop {
       self.read_char();
 
       match self.ch {
-        None => break,
-        Some(ch) => {
-          match ch {
-            '"' => break,
-            '\\' => self.read_escape(&mut string_chars),
-            x => string_chars.push(x),
-          }
-        }
+        None => return (Err("unterminated string".to_string()), false),
+        Some('"') => break,
+        Some('$') if self.peak_char() == Some('{') => {
+          self.read_char();
+          interpolates = true;
+          break
+        },
+        Some('\\') => match self.read_escape() {
+          Ok(ch) => string_chars.push(ch),
+          Err(message) => {
+            error.get_or_insert(message);
+          },
+        },
+        Some(x) => string_chars.push(x),
       }
     }
 
-    string_chars.into_iter().collect()
+    match error {
+      Some(message) => (Err(message), interpolates),
+      None => (Ok(string_chars.into_iter().collect()), interpolates),
+    }
   }
 
-  pub fn read_escape(&mut self, string_chars: &mut Vec<char>) {
-    if let Some(_) = self.peak_char() {
+  // Leaves the lexer on the last character of the escape
+  pub fn read_escape(&mut self) -> Result<char, String> {
+    self.read_char();
+
+    match self.ch {
+      // The string is unterminated, which `read_string` reports once it reaches the end
+      None => Ok('\\'),
+      Some('n') => Ok('\n'),
+      Some('r') => Ok('\r'),
+      Some('t') => Ok('\t'),
+      Some('0') => Ok('\0'),
+      Some('\\') => Ok('\\'),
+      Some('"') => Ok('"'),
+      Some('\'') => Ok('\''),
+      Some('$') => Ok('$'),
+      Some('x') => {
+        let digits = self.read_hex_digits(2);
+
+        // Like in Rust, `\x` is limited to ASCII, larger characters use `\u{..}`
+        match u32::from_str_radix(&digits, 16) {
+          Ok(code) if digits.len() == 2 && code <= 0x7F => Ok(char::from(code as u8)),
+          _ => Err(format!("invalid escape sequence: \\x{}", digits)),
+        }
+      },
+      Some('u') => {
+        if self.peak_char() != Some('{') {
+          return Err("invalid unicode escape: expected \\u{..}".to_string())
+        }
+
+        self.read_char();
+        let digits = self.read_hex_digits(6);
+
+        if self.peak_char() != Some('}') {
+          return Err(format!("invalid unicode escape: \\u{{{}", digits))
+        }
+
+        self.read_char();
+
+        u32::from_str_radix(&digits, 16)
+          .ok()
+          .and_then(char::from_u32)
+          .ok_or_else(|| format!("invalid unicode escape: \\u{{{}}}", digits))
+      },
+      Some(x) => Err(format!("invalid escape sequence: \\{}", x)),
+    }
+  }
+
+  fn read_hex_digits(&mut self, max_count: usize) -> String {
+    let mut digits = String::new();
+
+    while digits.len() < max_count && matches!(self.peak_char(), Some(ch) if ch.is_ascii_hexdigit()) {
       self.read_char();
-      let ch = self.ch.unwrap();
+      digits.push(self.ch.unwrap());
+    }
 
-      match ch {
-        'n' => string_chars.push('\n'),
-        'r' => string_chars.push('\r'),
-        x => string_chars.push(x),
+    digits
+  }
+
+  pub fn current_char_starts_comment(&mut self) -> bool {
+    self.ch == Some('/') && matches!(self.peak_char(), Some('/') | Some('*'))
+  }
+
+  // Reads a `// line` or a `/* block */` comment, including its delimiters. Block comments nest,
+  // and one that is never closed is illegal.
+  pub fn read_comment(&mut self) -> (TokenType, Literal) {
+    let position = self.position;
+
+    if self.peak_char() == Some('/') {
+      while !matches!(self.ch, None | Some('\n')) {
+        self.read_char();
+      }
+
+      return (token_types::COMMENT, self.chars[position..self.position].iter().collect())
+    }
+
+    let mut depth = 0;
+
+    loop {
+      match (self.ch, self.peak_char()) {
+        (None, _) => return (token_types::ILLEGAL, "unterminated block comment".to_string()),
+        (Some('/'), Some('*')) => {
+          depth += 1;
+          self.read_char();
+        },
+        (Some('*'), Some('/')) => {
+          depth -= 1;
+          self.read_char();
+        },
+        _ => {},
+      }
+
+      self.read_char();
+
+      if depth == 0 {
+        return (token_types::COMMENT, self.chars[position..self.position].iter().collect())
       }
     }
   }
 
   pub fn next_token(&mut self) -> Token {
-    use crate::token::*;
+    loop {
+      self.skip_whitespace();
+
+      let start = self.current_position();
+      let (token_type, literal) = if self.current_char_starts_comment() {
+        self.read_comment()
+      } else {
+        self.read_token()
+      };
+
+      if token_type == token_types::COMMENT && !self.emit_comments {
+        continue
+      }
+
+      let span = Span { start, end: self.current_position() };
+
+      return Token { token_type, literal, span }
+    }
+  }
+
+  fn read_token(&mut self) -> (TokenType, Literal) {
     use token_types::*;
 
     let token;
 
-    self.skip_whitespace();
-
     if self.ch == None {
-      token = Token { token_type: EOF, literal: "".to_string() }
+      return (EOF, "".to_string())
     }
     else {
       let ch = self.ch.unwrap();
       let literal = ch.to_string();
 
       match ch {
-        ';' => token = Token { token_type: SEMICOLON, literal },
-        '{' => token = Token { token_type: LBRACE, literal },
-        '}' => token = Token { token_type: RBRACE, literal },
-        '(' => token = Token { token_type: LPAREN, literal },
-        ')' => token = Token { token_type: RPAREN, literal },
-        ',' => token = Token { token_type: COMMA, literal },
-        '+' => token = Token { token_type: PLUS, literal },
-        '-' => token = Token { token_type: MINUS, literal },
-        '*' => token = Token { token_type: ASTERISK, literal },
-        '/' => token = Token { token_type: SLASH, literal },
-        '<' => token = Token { token_type: LT, literal },
-        '>' => token = Token { token_type: GT, literal },
+        ';' => token = (SEMICOLON, literal),
+        ':' => token = (COLON, literal),
+        '{' => {
+          if let Some(depth) = self.interpolations.last_mut() {
+            *depth += 1;
+          }
+
+          token = (LBRACE, literal)
+        },
+        '}' => {
+          match self.interpolations.last_mut() {
+            Some(0) => {
+              self.interpolations.pop();
+              token = self.read_string_token(true)
+            },
+            Some(depth) => {
+              *depth -= 1;
+              token = (RBRACE, literal)
+            },
+            None => token = (RBRACE, literal),
+          }
+        },
+        '[' => token = (LBRACKET, literal),
+        ']' => token = (RBRACKET, literal),
+        '(' => token = (LPAREN, literal),
+        ')' => token = (RPAREN, literal),
+        ',' => token = (COMMA, literal),
+        '+' => token = (PLUS, literal),
+        '-' => token = (MINUS, literal),
+        '*' => {
+          if self.peak_char() == Some('*') {
+            token = (POWER, "**".to_string());
+            self.read_char();
+          }
+          else {
+            token = (ASTERISK, literal);
+          }
+        },
+        '/' => token = (SLASH, literal),
+        '%' => token = (PERCENT, literal),
+        '<' => {
+          if self.peak_char() == Some('=') {
+            token = (LT_EQ, "<=".to_string());
+            self.read_char();
+          }
+          else {
+            token = (LT, literal);
+          }
+        },
+        '>' => {
+          if self.peak_char() == Some('=') {
+            token = (GT_EQ, ">=".to_string());
+            self.read_char();
+          }
+          else {
+            token = (GT, literal);
+          }
+        },
         '=' => {
           if self.peak_char() == Some('=') {
-            token = Token { token_type: EQ, literal: "==".to_string() };
+            token = (EQ, "==".to_string());
             self.read_char();
           }
           else {
-            token = Token { token_type: ASSIGN, literal };
+            token = (ASSIGN, literal);
           }
         },
         '!' => {
           if self.peak_char() == Some('=') {
-            token = Token { token_type: NOT_EQ, literal: "!=".to_string() };
+            token = (NOT_EQ, "!=".to_string());
             self.read_char();
           }
           else {
-            token = Token { token_type: BANG, literal };
+            token = (BANG, literal);
+          }
+        },
+        '&' => {
+          if self.peak_char() == Some('&') {
+            token = (AND, "&&".to_string());
+            self.read_char();
+          }
+          else {
+            token = (ILLEGAL, unexpected_character(ch));
+          }
+        },
+        '|' => {
+          if self.peak_char() == Some('|') {
+            token = (OR, "||".to_string());
+            self.read_char();
+          }
+          else {
+            token = (ILLEGAL, unexpected_character(ch));
+          }
+        },
+        '.' => {
+          if self.peak_char() == Some('.') {
+            token = (RANGE, "..".to_string());
+            self.read_char();
+          }
+          else if matches!(self.peak_char(), Some(ch) if ch.is_ascii_digit()) {
+            return self.read_number()
+          }
+          else {
+            token = (ILLEGAL, unexpected_character(ch));
           }
         },
         '"' => {
-          token = Token { token_type: STRING, literal: self.read_string() }
+          token = self.read_string_token(false)
         },
         _x => {
           if self.current_char_is_letter() {
             let literal = self.read_identifier();
             let token_type = get_token_type_for_string(&literal);
 
-            return Token { token_type, literal }
+            return (token_type, literal)
           }
           else if self.current_char_is_digit() {
-            let literal = self.read_digit();
-
-            return Token { token_type: INT, literal }
+            return self.read_number()
           }
           else {
-            token = Token { token_type: ILLEGAL, literal }
+            token = (ILLEGAL, unexpected_character(ch))
           }
         },
       }
@@ -200,6 +485,11 @@ impl Lexer {
 
     self.read_char();
 
-    return token;
+    token
   }
 }
+
+// Illegal tokens carry a message instead of their text, for the parser to report
+fn unexpected_character(ch: char) -> Literal {
+  format!("unexpected character: {}", ch)
+}
@@ -12,7 +12,7 @@ fn test_next_token() {
     };
 
     let result = add(five, ten);
-    !-/*5;
+    !-/ *5;
     5 < 10 > 5;
 
     if (5 < 10) {
@@ -28,133 +28,185 @@ fn test_next_token() {
     \"foo bar\";
     \"foo\\nbar\";
     \"foo\\\"bar\";
+    [1, 2];
+    {\"foo\": \"bar\"}
+    for (i in 0..10) {}
+    a && b || c;
+    a <= b >= c % d ** e;
   ";
   use token_types::*;
 
   let tests = vec![
     // let five = 5;
-    Token { token_type: LET, literal: "let".to_string() },
-    Token { token_type: IDENT, literal: "five".to_string() },
-    Token { token_type: ASSIGN, literal: "=".to_string() },
-    Token { token_type: INT, literal: "5".to_string() },
-    Token { token_type: SEMICOLON, literal: ";".to_string() },
+    Token { token_type: LET, literal: "let".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "five".to_string(), ..Default::default() },
+    Token { token_type: ASSIGN, literal: "=".to_string(), ..Default::default() },
+    Token { token_type: INT, literal: "5".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
 
     // let ten = 10;
-    Token { token_type: LET, literal: "let".to_string() },
-    Token { token_type: IDENT, literal: "ten".to_string() },
-    Token { token_type: ASSIGN, literal: "=".to_string() },
-    Token { token_type: INT, literal: "10".to_string() },
-    Token { token_type: SEMICOLON, literal: ";".to_string() },
+    Token { token_type: LET, literal: "let".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "ten".to_string(), ..Default::default() },
+    Token { token_type: ASSIGN, literal: "=".to_string(), ..Default::default() },
+    Token { token_type: INT, literal: "10".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
 
     // let add = fn(x, y) {
-    Token { token_type: LET, literal: "let".to_string() },
-    Token { token_type: IDENT, literal: "add".to_string() },
-    Token { token_type: ASSIGN, literal: "=".to_string() },
-    Token { token_type: FUNCTION, literal: "fn".to_string() },
-    Token { token_type: LPAREN, literal: "(".to_string() },
-    Token { token_type: IDENT, literal: "x".to_string() },
-    Token { token_type: COMMA, literal: ",".to_string() },
-    Token { token_type: IDENT, literal: "y".to_string() },
-    Token { token_type: RPAREN, literal: ")".to_string() },
-    Token { token_type: LBRACE, literal: "{".to_string() },
+    Token { token_type: LET, literal: "let".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "add".to_string(), ..Default::default() },
+    Token { token_type: ASSIGN, literal: "=".to_string(), ..Default::default() },
+    Token { token_type: FUNCTION, literal: "fn".to_string(), ..Default::default() },
+    Token { token_type: LPAREN, literal: "(".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "x".to_string(), ..Default::default() },
+    Token { token_type: COMMA, literal: ",".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "y".to_string(), ..Default::default() },
+    Token { token_type: RPAREN, literal: ")".to_string(), ..Default::default() },
+    Token { token_type: LBRACE, literal: "{".to_string(), ..Default::default() },
 
     // x + y
-    Token { token_type: IDENT, literal: "x".to_string() },
-    Token { token_type: PLUS, literal: "+".to_string() },
-    Token { token_type: IDENT, literal: "y".to_string() },
-    Token { token_type: SEMICOLON, literal: ";".to_string() },
+    Token { token_type: IDENT, literal: "x".to_string(), ..Default::default() },
+    Token { token_type: PLUS, literal: "+".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "y".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
 
     // }
-    Token { token_type: RBRACE, literal: "}".to_string() },
-    Token { token_type: SEMICOLON, literal: ";".to_string() },
+    Token { token_type: RBRACE, literal: "}".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
 
     // let result = add(five, ten);
-    Token { token_type: LET, literal: "let".to_string() },
-    Token { token_type: IDENT, literal: "result".to_string() },
-    Token { token_type: ASSIGN, literal: "=".to_string() },
-    Token { token_type: IDENT, literal: "add".to_string() },
-    Token { token_type: LPAREN, literal: "(".to_string() },
-    Token { token_type: IDENT, literal: "five".to_string() },
-    Token { token_type: COMMA, literal: ",".to_string() },
-    Token { token_type: IDENT, literal: "ten".to_string() },
-    Token { token_type: RPAREN, literal: ")".to_string() },
-    Token { token_type: SEMICOLON, literal: ";".to_string() },
+    Token { token_type: LET, literal: "let".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "result".to_string(), ..Default::default() },
+    Token { token_type: ASSIGN, literal: "=".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "add".to_string(), ..Default::default() },
+    Token { token_type: LPAREN, literal: "(".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "five".to_string(), ..Default::default() },
+    Token { token_type: COMMA, literal: ",".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "ten".to_string(), ..Default::default() },
+    Token { token_type: RPAREN, literal: ")".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
 
     // !-/*5;
-    Token { token_type: BANG, literal: "!".to_string() },
-    Token { token_type: MINUS, literal: "-".to_string() },
-    Token { token_type: SLASH, literal: "/".to_string() },
-    Token { token_type: ASTERISK, literal: "*".to_string() },
-    Token { token_type: INT, literal: "5".to_string() },
-    Token { token_type: SEMICOLON, literal: ";".to_string() },
+    Token { token_type: BANG, literal: "!".to_string(), ..Default::default() },
+    Token { token_type: MINUS, literal: "-".to_string(), ..Default::default() },
+    Token { token_type: SLASH, literal: "/".to_string(), ..Default::default() },
+    Token { token_type: ASTERISK, literal: "*".to_string(), ..Default::default() },
+    Token { token_type: INT, literal: "5".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
 
     // 5 < 10 > 5;
-    Token { token_type: INT, literal: "5".to_string() },
-    Token { token_type: LT, literal: "<".to_string() },
-    Token { token_type: INT, literal: "10".to_string() },
-    Token { token_type: GT, literal: ">".to_string() },
-    Token { token_type: INT, literal: "5".to_string() },
-    Token { token_type: SEMICOLON, literal: ";".to_string() },
+    Token { token_type: INT, literal: "5".to_string(), ..Default::default() },
+    Token { token_type: LT, literal: "<".to_string(), ..Default::default() },
+    Token { token_type: INT, literal: "10".to_string(), ..Default::default() },
+    Token { token_type: GT, literal: ">".to_string(), ..Default::default() },
+    Token { token_type: INT, literal: "5".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
 
     // if (5 < 10) {
-    Token { token_type: IF, literal: "if".to_string() },
-    Token { token_type: LPAREN, literal: "(".to_string() },
-    Token { token_type: INT, literal: "5".to_string() },
-    Token { token_type: LT, literal: "<".to_string() },
-    Token { token_type: INT, literal: "10".to_string() },
-    Token { token_type: RPAREN, literal: ")".to_string() },
-    Token { token_type: LBRACE, literal: "{".to_string() },
+    Token { token_type: IF, literal: "if".to_string(), ..Default::default() },
+    Token { token_type: LPAREN, literal: "(".to_string(), ..Default::default() },
+    Token { token_type: INT, literal: "5".to_string(), ..Default::default() },
+    Token { token_type: LT, literal: "<".to_string(), ..Default::default() },
+    Token { token_type: INT, literal: "10".to_string(), ..Default::default() },
+    Token { token_type: RPAREN, literal: ")".to_string(), ..Default::default() },
+    Token { token_type: LBRACE, literal: "{".to_string(), ..Default::default() },
 
     // return true;
-    Token { token_type: RETURN, literal: "return".to_string() },
-    Token { token_type: TRUE, literal: "true".to_string() },
-    Token { token_type: SEMICOLON, literal: ";".to_string() },
+    Token { token_type: RETURN, literal: "return".to_string(), ..Default::default() },
+    Token { token_type: TRUE, literal: "true".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
 
     // }
-    Token { token_type: RBRACE, literal: "}".to_string() },
+    Token { token_type: RBRACE, literal: "}".to_string(), ..Default::default() },
 
     // else {
-    Token { token_type: ELSE, literal: "else".to_string() },
-    Token { token_type: LBRACE, literal: "{".to_string() },
+    Token { token_type: ELSE, literal: "else".to_string(), ..Default::default() },
+    Token { token_type: LBRACE, literal: "{".to_string(), ..Default::default() },
 
     //   return false;
-    Token { token_type: RETURN, literal: "return".to_string() },
-    Token { token_type: FALSE, literal: "false".to_string() },
-    Token { token_type: SEMICOLON, literal: ";".to_string() },
+    Token { token_type: RETURN, literal: "return".to_string(), ..Default::default() },
+    Token { token_type: FALSE, literal: "false".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
 
     // }
-    Token { token_type: RBRACE, literal: "}".to_string() },
+    Token { token_type: RBRACE, literal: "}".to_string(), ..Default::default() },
 
     // 10 == 10;
-    Token { token_type: INT, literal: "10".to_string() },
-    Token { token_type: EQ, literal: "==".to_string() },
-    Token { token_type: INT, literal: "10".to_string() },
-    Token { token_type: SEMICOLON, literal: ";".to_string() },
+    Token { token_type: INT, literal: "10".to_string(), ..Default::default() },
+    Token { token_type: EQ, literal: "==".to_string(), ..Default::default() },
+    Token { token_type: INT, literal: "10".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
 
     // 10 != 9;
-    Token { token_type: INT, literal: "10".to_string() },
-    Token { token_type: NOT_EQ, literal: "!=".to_string() },
-    Token { token_type: INT, literal: "9".to_string() },
-    Token { token_type: SEMICOLON, literal: ";".to_string() },
+    Token { token_type: INT, literal: "10".to_string(), ..Default::default() },
+    Token { token_type: NOT_EQ, literal: "!=".to_string(), ..Default::default() },
+    Token { token_type: INT, literal: "9".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
 
     // "foobar";
-    Token { token_type: STRING, literal: "foobar".to_string() },
-    Token { token_type: SEMICOLON, literal: ";".to_string() },
+    Token { token_type: STRING, literal: "foobar".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
 
     // "foo bar";
-    Token { token_type: STRING, literal: "foo bar".to_string() },
-    Token { token_type: SEMICOLON, literal: ";".to_string() },
+    Token { token_type: STRING, literal: "foo bar".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
 
     // "foo\nbar";
-    Token { token_type: STRING, literal: "foo\nbar".to_string() },
-    Token { token_type: SEMICOLON, literal: ";".to_string() },
+    Token { token_type: STRING, literal: "foo\nbar".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
 
     // "foo\tbar";
-    Token { token_type: STRING, literal: "foo\"bar".to_string() },
-    Token { token_type: SEMICOLON, literal: ";".to_string() },
+    Token { token_type: STRING, literal: "foo\"bar".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
+
+    // [1, 2];
+    Token { token_type: LBRACKET, literal: "[".to_string(), ..Default::default() },
+    Token { token_type: INT, literal: "1".to_string(), ..Default::default() },
+    Token { token_type: COMMA, literal: ",".to_string(), ..Default::default() },
+    Token { token_type: INT, literal: "2".to_string(), ..Default::default() },
+    Token { token_type: RBRACKET, literal: "]".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
+
+    // {"foo": "bar"}
+    Token { token_type: LBRACE, literal: "{".to_string(), ..Default::default() },
+    Token { token_type: STRING, literal: "foo".to_string(), ..Default::default() },
+    Token { token_type: COLON, literal: ":".to_string(), ..Default::default() },
+    Token { token_type: STRING, literal: "bar".to_string(), ..Default::default() },
+    Token { token_type: RBRACE, literal: "}".to_string(), ..Default::default() },
+
+    // for (i in 0..10) {}
+    Token { token_type: FOR, literal: "for".to_string(), ..Default::default() },
+    Token { token_type: LPAREN, literal: "(".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "i".to_string(), ..Default::default() },
+    Token { token_type: IN, literal: "in".to_string(), ..Default::default() },
+    Token { token_type: INT, literal: "0".to_string(), ..Default::default() },
+    Token { token_type: RANGE, literal: "..".to_string(), ..Default::default() },
+    Token { token_type: INT, literal: "10".to_string(), ..Default::default() },
+    Token { token_type: RPAREN, literal: ")".to_string(), ..Default::default() },
+    Token { token_type: LBRACE, literal: "{".to_string(), ..Default::default() },
+    Token { token_type: RBRACE, literal: "}".to_string(), ..Default::default() },
+
+    // a && b || c;
+    Token { token_type: IDENT, literal: "a".to_string(), ..Default::default() },
+    Token { token_type: AND, literal: "&&".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "b".to_string(), ..Default::default() },
+    Token { token_type: OR, literal: "||".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "c".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
+
+    // a <= b >= c % d ** e;
+    Token { token_type: IDENT, literal: "a".to_string(), ..Default::default() },
+    Token { token_type: LT_EQ, literal: "<=".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "b".to_string(), ..Default::default() },
+    Token { token_type: GT_EQ, literal: ">=".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "c".to_string(), ..Default::default() },
+    Token { token_type: PERCENT, literal: "%".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "d".to_string(), ..Default::default() },
+    Token { token_type: POWER, literal: "**".to_string(), ..Default::default() },
+    Token { token_type: IDENT, literal: "e".to_string(), ..Default::default() },
+    Token { token_type: SEMICOLON, literal: ";".to_string(), ..Default::default() },
 
     // END
-    Token { token_type: EOF, literal: "".to_string() },
+    Token { token_type: EOF, literal: "".to_string(), ..Default::default() },
   ];
 
   let mut lexer = Lexer::new(input);
@@ -168,3 +220,186 @@ fn test_next_token() {
     assert_eq!(token.literal, test.literal);
   }
 }
+
+#[test]
+fn test_numbers() {
+  let input = "3.14 1e-9 .5 2E+3 10e2 1..3 1.e 7";
+  use token_types::*;
+
+  let tests = vec![
+    (FLOAT, "3.14"),
+    (FLOAT, "1e-9"),
+    (FLOAT, ".5"),
+    (FLOAT, "2E+3"),
+    (FLOAT, "10e2"),
+    (INT, "1"),
+    (RANGE, ".."),
+    (INT, "3"),
+    (INT, "1"),
+    (ILLEGAL, "unexpected character: ."),
+    (IDENT, "e"),
+    (INT, "7"),
+    (EOF, ""),
+  ];
+
+  let mut lexer = Lexer::new(input);
+
+  for (token_type, literal) in tests {
+    let token = lexer.next_token();
+
+    assert_eq!(token.token_type, token_type);
+    assert_eq!(token.literal, literal);
+  }
+}
+
+#[test]
+fn test_strings() {
+  let input = r#"
+    "tab\there" "nul\0" "quote\"s\\" "it\'s" "\x41\x7a" "\u{1F600}\u{e9}" "line\r\n"
+    "bad \q escape" "\x80" "\x4" "\u{110000}" "\u{}" "\u41" "\u{1234567}" "après" ünïcode _snake "unterminated
+  "#;
+  use token_types::*;
+
+  let tests = vec![
+    (STRING, "tab\there"),
+    (STRING, "nul\0"),
+    (STRING, "quote\"s\\"),
+    (STRING, "it's"),
+    (STRING, "Az"),
+    (STRING, "😀é"),
+    (STRING, "line\r\n"),
+    (ILLEGAL, "invalid escape sequence: \\q"),
+    (ILLEGAL, "invalid escape sequence: \\x80"),
+    (ILLEGAL, "invalid escape sequence: \\x4"),
+    (ILLEGAL, "invalid unicode escape: \\u{110000}"),
+    (ILLEGAL, "invalid unicode escape: \\u{}"),
+    (ILLEGAL, "invalid unicode escape: expected \\u{..}"),
+    (ILLEGAL, "invalid unicode escape: \\u{123456"),
+    (STRING, "après"),
+    (IDENT, "ünïcode"),
+    (IDENT, "_snake"),
+    (ILLEGAL, "unterminated string"),
+    (EOF, ""),
+  ];
+
+  let mut lexer = Lexer::new(input);
+
+  for (token_type, literal) in tests {
+    let token = lexer.next_token();
+
+    assert_eq!(token.token_type, token_type);
+    assert_eq!(token.literal, literal);
+  }
+}
+
+#[test]
+fn test_interpolated_strings() {
+  let input = r#""hi ${name}, ${ {"a": "${x}"}["a"] }!" "${y}" "cost: \${5} $ {}""#;
+  use token_types::*;
+
+  let tests = vec![
+    (STRING_START, "hi "),
+    (IDENT, "name"),
+    (STRING_MIDDLE, ", "),
+    (LBRACE, "{"),
+    (STRING, "a"),
+    (COLON, ":"),
+    (STRING_START, ""),
+    (IDENT, "x"),
+    (STRING_END, ""),
+    (RBRACE, "}"),
+    (LBRACKET, "["),
+    (STRING, "a"),
+    (RBRACKET, "]"),
+    (STRING_END, "!"),
+    (STRING_START, ""),
+    (IDENT, "y"),
+    (STRING_END, ""),
+    (STRING, "cost: ${5} $ {}"),
+    (EOF, ""),
+  ];
+
+  let mut lexer = Lexer::new(input);
+
+  for (token_type, literal) in tests {
+    let token = lexer.next_token();
+
+    assert_eq!(token.token_type, token_type);
+    assert_eq!(token.literal, literal);
+  }
+}
+
+#[test]
+fn test_comments() {
+  let input = "// leading\nlet a = 1; // trailing\n/* block /* nested */ still comment */ a / 2; /*/ never closed";
+  use token_types::*;
+
+  let tests = vec![
+    (COMMENT, "// leading"),
+    (LET, "let"),
+    (IDENT, "a"),
+    (ASSIGN, "="),
+    (INT, "1"),
+    (SEMICOLON, ";"),
+    (COMMENT, "// trailing"),
+    (COMMENT, "/* block /* nested */ still comment */"),
+    (IDENT, "a"),
+    (SLASH, "/"),
+    (INT, "2"),
+    (SEMICOLON, ";"),
+    (ILLEGAL, "unterminated block comment"),
+    (EOF, ""),
+  ];
+
+  // Without asking for them, comments are skipped like whitespace
+  let mut lexer = Lexer::new(input);
+
+  for (token_type, literal) in tests.iter().filter(|(token_type, _)| *token_type != COMMENT) {
+    let token = lexer.next_token();
+
+    assert_eq!(token.token_type, *token_type);
+    assert_eq!(token.literal, *literal);
+  }
+
+  let mut lexer = Lexer::new(input).with_comments();
+
+  for (token_type, literal) in tests {
+    let token = lexer.next_token();
+
+    assert_eq!(token.token_type, token_type);
+    assert_eq!(token.literal, literal);
+  }
+}
+
+#[test]
+fn test_token_positions() {
+  let input = "let x = 5;\n  \"é\" + x;";
+  use token_types::*;
+
+  // (token type, line, column, offset, end column, end offset)
+  let tests = vec![
+    (LET, 1, 1, 0, 4, 3),
+    (IDENT, 1, 5, 4, 6, 5),
+    (ASSIGN, 1, 7, 6, 8, 7),
+    (INT, 1, 9, 8, 10, 9),
+    (SEMICOLON, 1, 10, 9, 11, 10),
+    (STRING, 2, 3, 13, 6, 17),
+    (PLUS, 2, 7, 18, 8, 19),
+    (IDENT, 2, 9, 20, 10, 21),
+    (SEMICOLON, 2, 10, 21, 11, 22),
+    (EOF, 2, 11, 22, 11, 22),
+  ];
+
+  let mut lexer = Lexer::new(input);
+
+  for test in &tests {
+    let (token_type, line, column, offset, end_column, end_offset) = *test;
+    let token = lexer.next_token();
+
+    println!("Type: {}, Span: {:?}", token.token_type, token.span);
+
+    assert_eq!(token.token_type, token_type);
+    assert_eq!(token.span.start, Position { line, column, offset });
+    assert_eq!(token.span.end, Position { line, column: end_column, offset: end_offset });
+  }
+}
@@ -7,10 +7,70 @@ mod object;
 mod lexer;
 mod parser;
 mod eval;
+mod builtins;
+mod code;
+mod compiler;
+mod vm;
+mod engine;
+mod optimizer;
+mod formatter;
 
+mod cli;
+mod diagnostics;
 mod repl;
+mod runner;
+
+use std::env;
+use std::io;
+use std::io::IsTerminal;
+use std::process;
+use std::thread;
+
+use cli::Command;
+use diagnostics::Style;
 
 fn main() {
-  println!("Type in commands and see how they get parsed by the lexer");
-  repl::start()
+  let exit_code = thread::Builder::new()
+    .stack_size(engine::STACK_SIZE)
+    .spawn(run)
+    .map(|handle| handle.join().unwrap_or(runner::EXIT_INTERNAL_ERROR))
+    .unwrap_or(runner::EXIT_INTERNAL_ERROR);
+
+  process::exit(exit_code)
+}
+
+fn run() -> i32 {
+  let arguments: Vec<String> = env::args().skip(1).collect();
+
+  let options = match cli::parse_arguments(&arguments) {
+    Ok(options) => options,
+    Err(message) => {
+      eprintln!("{}\n\n{}", message, cli::USAGE);
+      return 2
+    },
+  };
+
+  let style = if options.plain || !io::stderr().is_terminal() || env::var_os("NO_COLOR").is_some() {
+    Style::Plain
+  } else {
+    Style::Color
+  };
+
+  match options.command {
+    Command::Help => {
+      println!("{}", cli::USAGE);
+      runner::EXIT_SUCCESS
+    },
+    Command::RunFile(path) => runner::run_file(&path, style, options.engine, options.optimization),
+    Command::RunSource(source) => runner::run_source(&source, "<command line>", style, options.engine, options.optimization),
+    Command::Format { paths, check } if paths.is_empty() => runner::format_stdin(check, style),
+    Command::Format { paths, check } => runner::format_files(&paths, check, style),
+    Command::RunStdin => runner::run_stdin(style, options.engine, options.optimization),
+    Command::Repl if !io::stdin().is_terminal() => runner::run_stdin(style, options.engine, options.optimization),
+    Command::Repl => {
+      println!("Type in commands and see how they get parsed by the lexer");
+      repl::start(style, options.engine, options.optimization);
+      runner::EXIT_SUCCESS
+    },
+  }
 }
@@ -0,0 +1,340 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+
+// Base of a digit, each digit holds 32 bits of the magnitude
+const BASE: u64 = 1 << 32;
+// The largest power of ten that fits in a digit, used to print nine decimals at a time
+const DECIMAL_CHUNK: u32 = 1_000_000_000;
+
+// An integer of any size, stored as a sign and a magnitude. The magnitude is a list of base 2^32
+// digits with the least significant digit first and without trailing zeros, so that every value
+// has exactly one representation and zero has no digits at all.
+//
+// The digits are kept in a boxed slice, which keeps the value small enough to be carried around
+// by errors.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BigInteger {
+  negative: bool,
+  digits: Box<[u32]>,
+}
+
+impl BigInteger {
+  fn from_parts(negative: bool, mut digits: Vec<u32>) -> Self {
+    trim(&mut digits);
+
+    BigInteger {
+      negative: negative && !digits.is_empty(),
+      digits: digits.into_boxed_slice(),
+    }
+  }
+
+  // Parses decimal digits with an optional leading `-`, nine at a time
+  pub fn from_decimal(text: &str) -> Option<Self> {
+    let (negative, decimals) = match text.strip_prefix('-') {
+      Some(decimals) => (true, decimals),
+      None => (false, text),
+    };
+
+    if decimals.is_empty() || !decimals.bytes().all(|byte| byte.is_ascii_digit()) {
+      return None
+    }
+
+    let mut digits = vec![];
+    let mut rest = decimals;
+
+    while !rest.is_empty() {
+      // The first chunk takes the leftover decimals, so that every later one has nine
+      let length = match rest.len() % 9 {
+        0 => 9,
+        length => length,
+      };
+      let (chunk, remaining) = rest.split_at(length);
+
+      multiply_add_digit(&mut digits, 10u32.pow(length as u32), chunk.parse().ok()?);
+      rest = remaining;
+    }
+
+    Some(BigInteger::from_parts(negative, digits))
+  }
+
+  pub fn is_zero(&self) -> bool {
+    self.digits.is_empty()
+  }
+
+  pub fn to_i64(&self) -> Option<i64> {
+    if self.digits.len() > 2 {
+      return None
+    }
+
+    let magnitude = self.digits.iter().rev().fold(0, |result, digit| (result << 32) | *digit as u64);
+
+    if self.negative {
+      // The magnitude of the smallest i64 does not fit in an i64 itself
+      match magnitude.cmp(&(1 << 63)) {
+        Ordering::Less => Some(-(magnitude as i64)),
+        Ordering::Equal => Some(i64::MIN),
+        Ordering::Greater => None,
+      }
+    } else {
+      i64::try_from(magnitude).ok()
+    }
+  }
+
+  pub fn to_f64(&self) -> f64 {
+    let magnitude = self.digits.iter().rev().fold(0.0, |result, digit| result * BASE as f64 + *digit as f64);
+
+    if self.negative { -magnitude } else { magnitude }
+  }
+
+  pub fn neg(&self) -> Self {
+    BigInteger::from_parts(!self.negative, self.digits.to_vec())
+  }
+
+  pub fn add(&self, other: &Self) -> Self {
+    if self.negative == other.negative {
+      return BigInteger::from_parts(self.negative, add_magnitudes(&self.digits, &other.digits))
+    }
+
+    match compare_magnitudes(&self.digits, &other.digits) {
+      Ordering::Less => BigInteger::from_parts(other.negative, subtract_magnitudes(&other.digits, &self.digits)),
+      _ => BigInteger::from_parts(self.negative, subtract_magnitudes(&self.digits, &other.digits)),
+    }
+  }
+
+  pub fn sub(&self, other: &Self) -> Self {
+    self.add(&other.neg())
+  }
+
+  pub fn mul(&self, other: &Self) -> Self {
+    BigInteger::from_parts(self.negative != other.negative, multiply_magnitudes(&self.digits, &other.digits))
+  }
+
+  // Divides like i64 does: the quotient is truncated toward zero and the remainder takes the sign
+  // of the dividend. Returns `None` when dividing by zero.
+  pub fn div_rem(&self, other: &Self) -> Option<(Self, Self)> {
+    if other.is_zero() {
+      return None
+    }
+
+    let (quotient, remainder) = divide_magnitudes(&self.digits, &other.digits);
+
+    Some((
+      BigInteger::from_parts(self.negative != other.negative, quotient),
+      BigInteger::from_parts(self.negative, remainder),
+    ))
+  }
+
+  pub fn pow(&self, mut exponent: u32) -> Self {
+    let mut result = BigInteger::from(1);
+    let mut base = self.clone();
+
+    while exponent > 0 {
+      if exponent & 1 == 1 {
+        result = result.mul(&base);
+      }
+
+      exponent >>= 1;
+
+      if exponent > 0 {
+        base = base.mul(&base);
+      }
+    }
+
+    result
+  }
+}
+
+impl From<i64> for BigInteger {
+  fn from(value: i64) -> Self {
+    let magnitude = value.unsigned_abs();
+
+    BigInteger::from_parts(value < 0, vec![magnitude as u32, (magnitude >> 32) as u32])
+  }
+}
+
+impl Ord for BigInteger {
+  fn cmp(&self, other: &Self) -> Ordering {
+    match (self.negative, other.negative) {
+      (false, true) => Ordering::Greater,
+      (true, false) => Ordering::Less,
+      (false, false) => compare_magnitudes(&self.digits, &other.digits),
+      (true, true) => compare_magnitudes(&other.digits, &self.digits),
+    }
+  }
+}
+
+impl PartialOrd for BigInteger {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl fmt::Display for BigInteger {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.is_zero() {
+      return write!(f, "0")
+    }
+
+    // Split off nine decimals at a time, starting with the least significant ones
+    let mut chunks = vec![];
+    let mut digits = self.digits.to_vec();
+
+    while !digits.is_empty() {
+      let (quotient, remainder) = divide_by_digit(&digits, DECIMAL_CHUNK);
+      chunks.push(remainder);
+      digits = quotient;
+    }
+
+    if self.negative {
+      write!(f, "-")?;
+    }
+
+    write!(f, "{}", chunks.pop().unwrap())?;
+
+    for chunk in chunks.iter().rev() {
+      write!(f, "{:09}", chunk)?;
+    }
+
+    Ok(())
+  }
+}
+
+fn trim(digits: &mut Vec<u32>) {
+  while digits.last() == Some(&0) {
+    digits.pop();
+  }
+}
+
+fn compare_magnitudes(a: &[u32], b: &[u32]) -> Ordering {
+  a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+  let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+  let mut carry = 0;
+
+  for index in 0..a.len().max(b.len()) {
+    let sum = *a.get(index).unwrap_or(&0) as u64 + *b.get(index).unwrap_or(&0) as u64 + carry;
+    result.push(sum as u32);
+    carry = sum >> 32;
+  }
+
+  result.push(carry as u32);
+  trim(&mut result);
+  result
+}
+
+// Expects `a` to be at least as large as `b`
+fn subtract_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+  let mut result = Vec::with_capacity(a.len());
+  let mut borrow = false;
+
+  for (index, digit) in a.iter().enumerate() {
+    let (difference, borrowed_digit) = digit.overflowing_sub(*b.get(index).unwrap_or(&0));
+    let (difference, borrowed_carry) = difference.overflowing_sub(borrow as u32);
+    result.push(difference);
+    borrow = borrowed_digit || borrowed_carry;
+  }
+
+  trim(&mut result);
+  result
+}
+
+fn multiply_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+  if a.is_empty() || b.is_empty() {
+    return vec![]
+  }
+
+  let mut result = vec![0; a.len() + b.len()];
+
+  for (i, a_digit) in a.iter().enumerate() {
+    let mut carry = 0;
+
+    for (j, b_digit) in b.iter().enumerate() {
+      // Cannot overflow: (2^32 - 1)^2 + 2 * (2^32 - 1) is exactly 2^64 - 1
+      let product = result[i + j] as u64 + *a_digit as u64 * *b_digit as u64 + carry;
+      result[i + j] = product as u32;
+      carry = product >> 32;
+    }
+
+    result[i + b.len()] = carry as u32;
+  }
+
+  trim(&mut result);
+  result
+}
+
+// Computes `digits * multiplier + addend` in place
+fn multiply_add_digit(digits: &mut Vec<u32>, multiplier: u32, addend: u32) {
+  let mut carry = addend as u64;
+
+  for digit in digits.iter_mut() {
+    let product = *digit as u64 * multiplier as u64 + carry;
+    *digit = product as u32;
+    carry = product >> 32;
+  }
+
+  if carry > 0 {
+    digits.push(carry as u32);
+  }
+}
+
+fn divide_by_digit(a: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+  let mut quotient = vec![0; a.len()];
+  let mut remainder = 0;
+
+  for (index, digit) in a.iter().enumerate().rev() {
+    let current = (remainder << 32) | *digit as u64;
+    quotient[index] = (current / divisor as u64) as u32;
+    remainder = current % divisor as u64;
+  }
+
+  trim(&mut quotient);
+  (quotient, remainder as u32)
+}
+
+// Long division one bit at a time, which is slow for huge numbers but simple to get right
+fn divide_magnitudes(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+  if b.len() == 1 {
+    let (quotient, remainder) = divide_by_digit(a, b[0]);
+    return (quotient, vec![remainder])
+  }
+
+  let mut quotient = vec![0; a.len()];
+  let mut remainder: Vec<u32> = vec![];
+
+  for bit in (0..a.len() * 32).rev() {
+    shift_left(&mut remainder);
+
+    if a[bit / 32] & (1 << (bit % 32)) != 0 {
+      if remainder.is_empty() {
+        remainder.push(1);
+      } else {
+        remainder[0] |= 1;
+      }
+    }
+
+    if compare_magnitudes(&remainder, b) != Ordering::Less {
+      remainder = subtract_magnitudes(&remainder, b);
+      quotient[bit / 32] |= 1 << (bit % 32);
+    }
+  }
+
+  trim(&mut quotient);
+  (quotient, remainder)
+}
+
+fn shift_left(digits: &mut Vec<u32>) {
+  let mut carry = 0;
+
+  for digit in digits.iter_mut() {
+    let shifted = (*digit as u64) << 1 | carry;
+    *digit = shifted as u32;
+    carry = shifted >> 32;
+  }
+
+  if carry > 0 {
+    digits.push(carry as u32);
+  }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use crate::eval::eval_error::*;
 use crate::object::Object;
 use std::rc::Rc;
@@ -9,6 +10,7 @@ pub type WrappedEnv = Rc<RefCell<Environment>>;
 #[derive(Debug)]
 pub struct Environment {
   store: HashMap<String, Object>,
+  constants: HashSet<String>,
   outer: Option<WrappedEnv>
 }
 
@@ -16,6 +18,7 @@ impl Environment {
   pub fn extend(env: &WrappedEnv) -> WrappedEnv {
     Rc::new(RefCell::new(Environment {
       store: HashMap::new(),
+      constants: HashSet::new(),
       outer: Some(env.clone())
     }))
   }
@@ -23,6 +26,7 @@ impl Environment {
   pub fn new() -> Self {
     Self {
       store: HashMap::new(),
+      constants: HashSet::new(),
       outer: None,
     }
   }
@@ -35,19 +39,43 @@ impl Environment {
       None => {
         match &self.outer {
           Some(outer_env) => outer_env.borrow().get(key),
-          None => Err(EvalError(format!("Unknown identifier: {}", key))),
+          None => Err(EvalError::new(format!("Unknown identifier: {}", key))),
         }
       }
     }
   }
 
   pub fn set(&mut self, key: &str, value: Object) -> Result<Object, EvalError> {
-    let previous_or_none = self.store.insert(key.to_string(), value);
-
-    if let Some(_) = previous_or_none {
-      return Err(EvalError(format!("Identifier has already been declared: {}", key)))
+    // A failed declaration must leave the existing binding, which may be a constant, untouched
+    if self.store.contains_key(key) {
+      return Err(EvalError::new(format!("Identifier has already been declared: {}", key)))
     }
 
+    self.store.insert(key.to_string(), value);
     Ok(Object::Null)
   }
+
+  pub fn set_constant(&mut self, key: &str, value: Object) -> Result<Object, EvalError> {
+    let result = self.set(key, value)?;
+    self.constants.insert(key.to_string());
+
+    Ok(result)
+  }
+
+  // Updates the nearest existing binding, unlike `set` which declares a new one in this scope
+  pub fn assign(&mut self, key: &str, value: Object) -> Result<Object, EvalError> {
+    if self.store.contains_key(key) {
+      if self.constants.contains(key) {
+        return Err(EvalError::new(format!("Cannot assign to constant: {}", key)))
+      }
+
+      self.store.insert(key.to_string(), value.clone());
+      return Ok(value)
+    }
+
+    match &self.outer {
+      Some(outer_env) => outer_env.borrow_mut().assign(key, value),
+      None => Err(EvalError::new(format!("Cannot assign to undeclared identifier: {}", key))),
+    }
+  }
 }
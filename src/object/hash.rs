@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+use crate::object::Object;
+use crate::object::big_integer::BigInteger;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum HashKey {
+  Integer(i64),
+  BigInteger(BigInteger),
+  String(String),
+  Boolean(bool),
+}
+
+#[derive(Debug, Clone)]
+pub struct HashPair {
+  pub key: Object,
+  pub value: Object,
+}
+
+// Pairs are kept in insertion order so that inspecting (and later iterating) a hash is
+// deterministic, while the index map keeps lookups constant time.
+#[derive(Debug, Clone, Default)]
+pub struct Hash {
+  pairs: Vec<HashPair>,
+  indices: HashMap<HashKey, usize>,
+}
+
+impl Hash {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn insert(&mut self, hash_key: HashKey, key: Object, value: Object) {
+    match self.indices.get(&hash_key) {
+      Some(index) => self.pairs[*index] = HashPair { key, value },
+      None => {
+        self.indices.insert(hash_key, self.pairs.len());
+        self.pairs.push(HashPair { key, value });
+      }
+    }
+  }
+
+  pub fn get(&self, hash_key: &HashKey) -> Option<&Object> {
+    self.indices.get(hash_key).map(|index| &self.pairs[*index].value)
+  }
+
+  pub fn pairs(&self) -> &[HashPair] {
+    &self.pairs
+  }
+}
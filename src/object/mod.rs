@@ -1,45 +1,184 @@
 use crate::eval::eval_error::*;
+use crate::ast::Expression;
 use crate::ast::block_statement::*;
+use crate::builtins::BuiltinFunction;
+use crate::code::CompiledFunction;
+use big_integer::BigInteger;
 use environment::*;
+use hash::*;
+use scope::*;
+use std::fmt;
+use std::rc::Rc;
 
+pub mod big_integer;
 pub mod environment;
+pub mod hash;
+pub mod scope;
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub enum Object {
   Integer(i64),
+  // Integers that do not fit in an i64. Operations return an `Integer` whenever the result fits,
+  // so small values stay on the fast path.
+  BigInteger(BigInteger),
+  Float(f64),
   String(String),
   Boolean(bool),
   Null,
-  Return(Box<Object>),
   Function(Vec<String>, Box<BlockStatement>, WrappedEnv),
+  Array(Vec<Object>),
+  Hash(Hash),
+  // An integer range, excluding the end
+  Range(i64, i64),
+  Builtin(&'static str, BuiltinFunction),
+  CompiledFunction(Rc<CompiledFunction>),
+  Closure(Rc<CompiledFunction>, WrappedScope),
+  // The unevaluated expression given to `quote`
+  Quote(Box<Expression>),
+  // Macros only exist while macros are expanded, before the program runs
+  Macro(Vec<String>, Box<BlockStatement>, WrappedEnv),
+}
+
+// Functions are printed without their body and environment: the environment usually contains
+// the function itself, and both engines have to describe their functions the same way.
+impl fmt::Debug for Object {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Object::Integer(integer) => write!(f, "Integer({})", integer),
+      Object::BigInteger(integer) => write!(f, "Integer({})", integer),
+      Object::Float(float) => write!(f, "Float({:?})", float),
+      Object::String(string) => write!(f, "String({:?})", string),
+      Object::Boolean(is_true) => write!(f, "Boolean({})", is_true),
+      Object::Null => write!(f, "Null"),
+      Object::Function(_, _, _) => write!(f, "Function"),
+      Object::Array(elements) => f.debug_tuple("Array").field(elements).finish(),
+      Object::Hash(hash) => {
+        let pairs: Vec<String> = hash
+          .pairs()
+          .iter()
+          .map(|pair| format!("{:?}: {:?}", pair.key, pair.value))
+          .collect();
+        write!(f, "Hash({{{}}})", pairs.join(", "))
+      },
+      Object::Range(start, end) => write!(f, "Range({}..{})", start, end),
+      Object::Builtin(name, _) => write!(f, "Builtin({})", name),
+      Object::CompiledFunction(_) => write!(f, "CompiledFunction"),
+      Object::Closure(_, _) => write!(f, "Function"),
+      Object::Quote(expression) => write!(f, "Quote({})", expression.to_string()),
+      Object::Macro(_, _, _) => write!(f, "Macro"),
+    }
+  }
 }
 
 impl Object {
   pub fn inspect(&self) -> String {
     match self {
       Object::Integer(integer) => integer.to_string(),
+      Object::BigInteger(integer) => integer.to_string(),
+      // Whole floats keep their fraction, like `2.0`, and the special values show as `NaN`, `inf` and `-inf`
+      Object::Float(float) => format!("{:?}", float),
       Object::Boolean(is_true) => if *is_true { "True".to_string() } else { "False".to_string() },
-      Object::Return(object) => object.inspect(),
       Object::Function(_, _, _) => "Function".to_string(),
       Object::String(string) => string.clone(),
       Object::Null => "Null".to_string(),
+      Object::Array(elements) => {
+        let elements: Vec<String> = elements.iter().map(|element| element.inspect_element()).collect();
+        format!("[{}]", elements.join(", "))
+      },
+      Object::Hash(hash) => {
+        let pairs: Vec<String> = hash
+          .pairs()
+          .iter()
+          .map(|pair| format!("{}: {}", pair.key.inspect_element(), pair.value.inspect_element()))
+          .collect();
+        format!("{{{}}}", pairs.join(", "))
+      },
+      Object::Range(start, end) => format!("{}..{}", start, end),
+      Object::Builtin(name, _) => format!("Builtin function: {}", name),
+      Object::CompiledFunction(_) => "CompiledFunction".to_string(),
+      Object::Closure(_, _) => "Function".to_string(),
+      Object::Quote(expression) => format!("Quote({})", expression.to_string()),
+      Object::Macro(_, _, _) => "Macro".to_string(),
+    }
+  }
+
+  pub fn type_name(&self) -> &'static str {
+    match self {
+      Object::Integer(_) => "Integer",
+      Object::BigInteger(_) => "Integer",
+      Object::Float(_) => "Float",
+      Object::String(_) => "String",
+      Object::Boolean(_) => "Boolean",
+      Object::Null => "Null",
+      Object::Function(_, _, _) => "Function",
+      Object::Array(_) => "Array",
+      Object::Hash(_) => "Hash",
+      Object::Range(_, _) => "Range",
+      Object::Builtin(_, _) => "Builtin",
+      Object::CompiledFunction(_) => "CompiledFunction",
+      Object::Closure(_, _) => "Function",
+      Object::Quote(_) => "Quote",
+      Object::Macro(_, _, _) => "Macro",
+    }
+  }
+
+  pub fn get_hash_key(&self) -> Result<HashKey, EvalError> {
+    match self {
+      Object::Integer(integer) => Ok(HashKey::Integer(*integer)),
+      Object::BigInteger(integer) => Ok(HashKey::BigInteger(integer.clone())),
+      Object::String(string) => Ok(HashKey::String(string.clone())),
+      Object::Boolean(is_true) => Ok(HashKey::Boolean(*is_true)),
+      _ => Err(EvalError::new(format!("Unusable as hash key: {}", self.type_name()))),
+    }
+  }
+
+  // Strings are quoted when they are shown as part of a collection, so that `["a, b"]` and
+  // `["a", "b"]` can be told apart.
+  fn inspect_element(&self) -> String {
+    match self {
+      Object::String(string) => format!("{:?}", string),
+      _ => self.inspect(),
     }
   }
 
   pub fn get_boolean_value(&self) -> Result<&bool, EvalError> {
     match self {
       Object::Integer(integer) => if integer == &0 { Ok(&false) } else { Ok(&true) },
+      Object::BigInteger(_) | Object::Float(_) => Ok(self.get_is_truthy()),
       Object::Boolean(is_true) => Ok(is_true),
-      Object::Return(object) => object.get_boolean_value(),
-      _ => Err(EvalError(format!("Expected boolean, found: {:?}", self))),
+      _ => Err(EvalError::new(format!("Expected boolean, found: {:?}", self))),
     }
   }
 
   pub fn get_numeric_value(&self) -> Result<i64, EvalError> {
     match self {
       Object::Integer(integer) => Ok(integer.clone()),
-      Object::Return(object) => object.get_numeric_value(),
-      _ => Err(EvalError(format!("Expected integer, found: {:?}", self))),
+      _ => Err(EvalError::new(format!("Expected integer, found: {:?}", self))),
+    }
+  }
+
+  // Integers are promoted to floats when they meet a float in an operation
+  pub fn get_float_value(&self) -> Result<f64, EvalError> {
+    match self {
+      Object::Float(float) => Ok(*float),
+      Object::Integer(integer) => Ok(*integer as f64),
+      Object::BigInteger(integer) => Ok(integer.to_f64()),
+      _ => Err(EvalError::new(format!("Expected number, found: {:?}", self))),
+    }
+  }
+
+  pub fn get_big_integer_value(&self) -> Result<BigInteger, EvalError> {
+    match self {
+      Object::Integer(integer) => Ok(BigInteger::from(*integer)),
+      Object::BigInteger(integer) => Ok(integer.clone()),
+      _ => Err(EvalError::new(format!("Expected integer, found: {:?}", self))),
+    }
+  }
+
+  pub fn from_big_integer(integer: BigInteger) -> Object {
+    match integer.to_i64() {
+      Some(integer) => Object::Integer(integer),
+      None => Object::BigInteger(integer),
     }
   }
 
@@ -47,19 +186,30 @@ impl Object {
     match self {
       Object::String(string) => Ok(string.clone()),
       Object::Integer(integer) => Ok(integer.to_string()),
-      Object::Return(object) => object.get_string_value(),
-      _ => Err(EvalError(format!("Expected integer, found: {:?}", self))),
+      Object::BigInteger(_) | Object::Float(_) => Ok(self.inspect()),
+      _ => Err(EvalError::new(format!("Expected integer, found: {:?}", self))),
     }
   }
 
   pub fn get_is_truthy(&self) -> &bool {
     match self {
       Object::Integer(integer) => if integer == &0 { &false } else { &true },
+      // Big integers are never zero
+      Object::BigInteger(_) => &true,
+      // NaN is neither zero nor anything else, and counts as falsy like zero
+      Object::Float(float) => if *float == 0.0 || float.is_nan() { &false } else { &true },
       Object::String(_) => &true,
       Object::Boolean(is_true) => &is_true,
-      Object::Return(object) => object.get_is_truthy(),
       Object::Function(_, _, _) => &true,
       Object::Null => &false,
+      Object::Array(_) => &true,
+      Object::Hash(_) => &true,
+      Object::Range(_, _) => &true,
+      Object::Builtin(_, _) => &true,
+      Object::CompiledFunction(_) => &true,
+      Object::Closure(_, _) => &true,
+      Object::Quote(_) => &true,
+      Object::Macro(_, _, _) => &true,
     }
   }
 }
@@ -0,0 +1,94 @@
+use crate::object::Object;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub type WrappedScope = Rc<RefCell<Scope>>;
+
+pub enum Assignment {
+  Assigned,
+  Unbound,
+  Constant,
+}
+
+// The virtual machine's counterpart to `Environment`: names are resolved to slot indices by the
+// compiler, so a scope only has to store values. A slot is `None` until its binding is defined.
+pub struct Scope {
+  slots: Vec<Option<Object>>,
+  constant_slots: Vec<bool>,
+  parent: Option<WrappedScope>,
+}
+
+impl Scope {
+  pub fn new(slot_count: usize) -> WrappedScope {
+    Rc::new(RefCell::new(Scope {
+      slots: vec![None; slot_count],
+      constant_slots: vec![false; slot_count],
+      parent: None,
+    }))
+  }
+
+  pub fn extend(parent: &WrappedScope, slot_count: usize) -> WrappedScope {
+    Rc::new(RefCell::new(Scope {
+      slots: vec![None; slot_count],
+      constant_slots: vec![false; slot_count],
+      parent: Some(Rc::clone(parent)),
+    }))
+  }
+
+  // Scopes that live across several compilations (like the globals of the REPL) grow as new
+  // names get declared.
+  pub fn ensure_slots(&mut self, slot_count: usize) {
+    if self.slots.len() < slot_count {
+      self.slots.resize(slot_count, None);
+      self.constant_slots.resize(slot_count, false);
+    }
+  }
+
+  pub fn get(&self, depth: usize, slot: usize) -> Option<Object> {
+    if depth == 0 {
+      return self.slots[slot].clone()
+    }
+
+    match &self.parent {
+      Some(parent) => parent.borrow().get(depth - 1, slot),
+      None => None,
+    }
+  }
+
+  // Returns false when the slot has already been bound.
+  pub fn define(&mut self, slot: usize, value: Object) -> bool {
+    if self.slots[slot].is_some() {
+      return false
+    }
+
+    self.slots[slot] = Some(value);
+    true
+  }
+
+  pub fn define_constant(&mut self, slot: usize, value: Object) -> bool {
+    if !self.define(slot, value) {
+      return false
+    }
+
+    self.constant_slots[slot] = true;
+    true
+  }
+
+  pub fn assign(&mut self, depth: usize, slot: usize, value: Object) -> Assignment {
+    if depth > 0 {
+      return match &self.parent {
+        Some(parent) => parent.borrow_mut().assign(depth - 1, slot, value),
+        None => Assignment::Unbound,
+      }
+    }
+
+    match self.slots[slot] {
+      None => Assignment::Unbound,
+      Some(_) if self.constant_slots[slot] => Assignment::Constant,
+      Some(_) => {
+        self.slots[slot] = Some(value);
+        Assignment::Assigned
+      },
+    }
+  }
+}
@@ -0,0 +1,195 @@
+#[cfg(test)]
+mod tests;
+
+use std::mem;
+
+use crate::ast::*;
+use crate::ast::visitor_mut::*;
+use crate::eval::*;
+use crate::eval::macro_expansion::object_to_expression;
+use crate::object::*;
+use crate::token::Span;
+use crate::token::token_types::{AND, OR};
+
+use block_statement::BlockStatement;
+use if_expression::IfExpression;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Optimization {
+  Off,
+  On,
+  // Optimizes and reports every change that was made
+  Debug,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+  pub span: Span,
+  pub description: String,
+}
+
+// Rewrites a program into a simpler one that gives the same results: constant arithmetic is
+// folded, `if`s with a constant condition are replaced by the branch they take and statements
+// that can never run are removed. Returns what was changed, in the order it was changed.
+pub fn optimize(program: &mut Program) -> Vec<Change> {
+  let mut optimizer = Optimizer { changes: vec![] };
+  optimizer.visit_program_mut(program);
+  optimizer.changes
+}
+
+struct Optimizer {
+  changes: Vec<Change>,
+}
+
+impl Optimizer {
+  fn record(&mut self, span: Span, description: String) {
+    self.changes.push(Change { span, description });
+  }
+
+  fn simplify_expression(&mut self, expression: &Expression) -> Option<Expression> {
+    match expression {
+      // `&&` and `||` only evaluate their right side when needed, so they are left alone
+      Expression::InfixExpression(infix_expression) if ![AND, OR].contains(&infix_expression.operator.as_str()) => {
+        let left = get_constant(&infix_expression.left)?;
+        let right = get_constant(&infix_expression.right)?;
+        let result = eval_infix_expression(&infix_expression.operator, left, right).ok()?;
+
+        self.fold(expression, result)
+      },
+      Expression::PrefixExpression(prefix_expression) => {
+        let right = get_constant(&prefix_expression.right)?;
+        let result = eval_prefix_expression(&prefix_expression.operator, right).ok()?;
+
+        self.fold(expression, result)
+      },
+      // An `if` whose taken branch is a single expression is that expression
+      Expression::IfExpression(if_expression) => {
+        let (taken, branch) = get_taken_branch(if_expression)?;
+
+        match branch.map(|block| block.statements.as_slice()) {
+          Some([Statement::Expression(expression)]) => {
+            self.record(if_expression.span, format!("Replaced `if` with its {} branch", taken));
+            Some(expression.clone())
+          },
+          _ => None,
+        }
+      },
+      _ => None,
+    }
+  }
+
+  // Errors, like a division by zero, are left for the program to run into
+  fn fold(&mut self, expression: &Expression, result: Object) -> Option<Expression> {
+    let folded = match result {
+      Object::Integer(_) | Object::BigInteger(_) | Object::String(_) | Object::Boolean(_) => object_to_expression(result, expression.span()).ok()?,
+      _ => return None,
+    };
+
+    self.record(expression.span(), format!("Folded `{}` into `{}`", expression.to_string(), folded.to_string()));
+    Some(folded)
+  }
+
+  fn simplify_statements(&mut self, statements: &mut Vec<Statement>) {
+    let count = statements.len();
+    let mut simplified = vec![];
+
+    for (index, statement) in mem::take(statements).into_iter().enumerate() {
+      let if_expression = match &statement {
+        Statement::Expression(Expression::IfExpression(if_expression)) => if_expression,
+        _ => {
+          simplified.push(statement);
+          continue
+        },
+      };
+
+      let (taken, branch) = match get_taken_branch(if_expression) {
+        Some(taken_branch) => taken_branch,
+        None => {
+          simplified.push(statement);
+          continue
+        },
+      };
+
+      let branch_statements = branch.map(|block| block.statements.clone()).unwrap_or_default();
+
+      // The last statement gives the value of the block, and an empty branch gives null
+      if branch_statements.is_empty() && index == count - 1 {
+        simplified.push(statement);
+        continue
+      }
+
+      if branch_statements.is_empty() {
+        self.record(if_expression.span, format!("Removed `if` whose {} branch is empty", taken));
+      } else {
+        self.record(if_expression.span, format!("Replaced `if` with its {} branch", taken));
+      }
+
+      simplified.extend(branch_statements);
+    }
+
+    let exit = simplified.iter().position(|statement| {
+      matches!(statement, Statement::ReturnStatement(_) | Statement::BreakStatement(_) | Statement::ContinueStatement(_))
+    });
+
+    if let Some(position) = exit {
+      let unreachable = simplified.len() - position - 1;
+
+      if unreachable > 0 {
+        self.record(
+          simplified[position + 1].span(),
+          format!(
+            "Removed {} unreachable statement{} after `{}`",
+            unreachable,
+            if unreachable == 1 { "" } else { "s" },
+            simplified[position].token_literal()
+          ),
+        );
+        simplified.truncate(position + 1);
+      }
+    }
+
+    *statements = simplified;
+  }
+}
+
+impl VisitorMut for Optimizer {
+  fn visit_program_mut(&mut self, program: &mut Program) {
+    walk_program_mut(self, program);
+    self.simplify_statements(&mut program.statements);
+  }
+
+  fn visit_block_statement_mut(&mut self, block_statement: &mut BlockStatement) {
+    walk_block_statement_mut(self, block_statement);
+    self.simplify_statements(&mut block_statement.statements);
+  }
+
+  // Children are simplified first, so `1 + 2 * 3` folds one operator at a time
+  fn visit_expression_mut(&mut self, expression: &mut Expression) {
+    walk_expression_mut(self, expression);
+
+    if let Some(simplified) = self.simplify_expression(expression) {
+      *expression = simplified;
+    }
+  }
+}
+
+fn get_constant(expression: &Expression) -> Option<Object> {
+  match expression {
+    Expression::IntegerLiteral(integer_literal) => Some(Object::Integer(integer_literal.value)),
+    Expression::BigIntegerLiteral(big_integer_literal) => Some(Object::BigInteger(big_integer_literal.value.clone())),
+    Expression::StringLiteral(string_literal) => Some(Object::String(string_literal.value.clone())),
+    Expression::BooleanLiteral(boolean_literal) => Some(Object::Boolean(boolean_literal.value)),
+    _ => None,
+  }
+}
+
+// For an `if` with a constant condition, which branch it takes and that branch, if there is one
+fn get_taken_branch(if_expression: &IfExpression) -> Option<(&'static str, Option<&BlockStatement>)> {
+  let condition = get_constant(&if_expression.condition)?;
+
+  if *condition.get_is_truthy() {
+    Some(("true", Some(&if_expression.true_block)))
+  } else {
+    Some(("false", if_expression.false_block_or_none.as_ref().as_ref()))
+  }
+}
@@ -0,0 +1,106 @@
+use std::fs;
+use std::path::Path;
+
+use crate::ast::Program;
+use crate::engine::*;
+use crate::engine::differential::*;
+use crate::lexer::Lexer;
+use crate::optimizer::*;
+use crate::parser::Parser;
+
+fn parse(input: &str) -> Program {
+  match_or_fail!(Parser::new(Lexer::new(input)).parse_program(), Ok(m) => m)
+}
+
+#[test]
+fn test_optimize() {
+  let tests = vec![
+    ("1 + 2 * 3", "7"),
+    ("-(5 - 10)", "5"),
+    ("!true", "false"),
+    ("\"mon\" + \"key\"", "\"monkey\""),
+    ("1 < 2 == true", "true"),
+    ("x + 1 * 2", "x + 2"),
+    ("10 / 0", "10 / 0"),
+    ("9223372036854775807 + 1", "9223372036854775808"),
+    ("2 ** 64 - 1", "18446744073709551615"),
+    ("true && x", "true && x"),
+    ("1 + 1.5", "1 + 1.5"),
+    ("let a = if (true) { 1 } else { 2 }", "let a = 1"),
+    ("let a = if (1 > 2) { 1 } else { 2 }", "let a = 2"),
+    ("if (false) { 1 }", "if (false) { 1 }"),
+    ("if (false) { 1 }; 2", "2"),
+    ("if (0) { 1 } else { let a = 2; a }", "let a = 2; a"),
+    ("fn() { return 1; 2; 3 }", "fn() { return 1; }"),
+    ("while (x) { break; puts(1) }", "while (x) { break; }"),
+    ("for (x in y) { continue; puts(x) }", "for (x in y) { continue; }"),
+    ("fn(x) { if (true) { return x; } x + 1 }", "fn(x) { return x; }"),
+    ("return 1; puts(2)", "return 1;"),
+  ];
+
+  for (input, expected) in tests {
+    let mut program = parse(input);
+    optimize(&mut program);
+
+    assert_eq!(program.to_string(), parse(expected).to_string(), "input: {}", input);
+  }
+}
+
+#[test]
+fn test_optimize_changes() {
+  let tests = vec![
+    ("1 + 2", vec!["1:1: Folded `(1 + 2)` into `3`"]),
+    (
+      "let a = 60 * 60 * 24;",
+      vec!["1:9: Folded `(60 * 60)` into `3600`", "1:9: Folded `(3600 * 24)` into `86400`"],
+    ),
+    ("let a = if (true) { 1 } else { 2 };", vec!["1:9: Replaced `if` with its true branch"]),
+    ("if (false) { puts(1) }; 2", vec!["1:1: Removed `if` whose false branch is empty"]),
+    ("fn() { return 1; 2; 3 }", vec!["1:18: Removed 2 unreachable statements after `return`"]),
+    ("x + y", vec![]),
+  ];
+
+  for (input, expected) in tests {
+    let mut program = parse(input);
+    let changes = optimize(&mut program);
+    let changes: Vec<String> = changes.iter().map(|change| format!("{}: {}", change.span, change.description)).collect();
+
+    assert_eq!(changes, expected, "input: {}", input);
+  }
+}
+
+// An optimized program must give the same result as the program it came from, on both engines
+#[test]
+fn test_optimized_programs_give_same_results() {
+  let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+  let mut sources = vec![
+    "let a = 2 * 3; a + 1".to_string(),
+    "if (true) { let a = 1; } a".to_string(),
+    "let f = fn(x) { if (false) { return 1 } else { return x } x }; f(5)".to_string(),
+    "let i = 0; while (true) { i = i + 1; if (i > 3) { break; i } } i".to_string(),
+    "if (true) { }".to_string(),
+    "1; if (false) { 2 }".to_string(),
+    "10 / (5 - 5)".to_string(),
+    "-true".to_string(),
+  ];
+
+  for entry in fs::read_dir(directory).unwrap() {
+    sources.push(fs::read_to_string(entry.unwrap().path()).unwrap());
+  }
+
+  // The examples recurse deeper than the stack of a test thread allows
+  let test = move || {
+    for source in sources {
+      let program = parse(&source);
+
+      for engine in [Engine::Eval, Engine::Vm] {
+        let expected = Outcome::from_result(&Session::new(engine).run(&program));
+        let optimized = Outcome::from_result(&Session::new(engine).with_optimization(Optimization::On).run(&program));
+
+        assert_eq!(optimized, expected, "engine: {:?}, source: {}", engine, source);
+      }
+    }
+  };
+
+  std::thread::Builder::new().stack_size(STACK_SIZE).spawn(test).unwrap().join().unwrap();
+}
@@ -7,19 +7,33 @@ use std::collections::HashMap;
 
 use crate::ast::*;
 use crate::lexer::Lexer;
+use crate::object::big_integer::BigInteger;
 use crate::token::*;
 
+use array_literal::ArrayLiteral;
+use assign_expression::AssignExpression;
+use big_integer_literal::BigIntegerLiteral;
 use boolean_literal::BooleanLiteral;
 use call_expression::CallExpression;
+use float_literal::FloatLiteral;
+use for_expression::ForExpression;
 use function_literal::FunctionLiteral;
+use hash_literal::HashLiteral;
 use identifier::Identifier;
 use if_expression::IfExpression;
+use index_expression::IndexExpression;
+use slice_expression::SliceExpression;
 use infix_expression::InfixExpression;
+use interpolated_string::*;
 use integer_literal::IntegerLiteral;
+use macro_literal::MacroLiteral;
 use prefix_expression::PrefixExpression;
 use string_literal::StringLiteral;
+use while_expression::WhileExpression;
 
 use block_statement::BlockStatement;
+use break_statement::BreakStatement;
+use continue_statement::ContinueStatement;
 use let_statement::LetStatement;
 use return_statement::ReturnStatement;
 
@@ -31,25 +45,40 @@ pub type Precedence = u8;
 
 pub mod precedences {
   pub const LOWEST: u8 = 0;
-  pub const EQUALS: u8 = 1;
-  pub const LESS_OR_GREATER: u8 = 2;
-  pub const SUM: u8 = 3;
-  pub const PRODUCT: u8 = 4;
-  pub const PREFIX: u8 = 5;
-  pub const CALL: u8 = 6;
+  pub const ASSIGN: u8 = 1;
+  pub const LOGICAL_OR: u8 = 2;
+  pub const LOGICAL_AND: u8 = 3;
+  pub const EQUALS: u8 = 4;
+  pub const LESS_OR_GREATER: u8 = 5;
+  pub const RANGE: u8 = 6;
+  pub const SUM: u8 = 7;
+  pub const PRODUCT: u8 = 8;
+  pub const POWER: u8 = 9;
+  pub const PREFIX: u8 = 10;
+  pub const CALL: u8 = 11;
+  pub const INDEX: u8 = 12;
 }
 
 pub fn get_operator_precedence(token_type: TokenType) -> Precedence {
   match token_type {
+    ASSIGN => precedences::ASSIGN,
+    OR => precedences::LOGICAL_OR,
+    AND => precedences::LOGICAL_AND,
     EQ => precedences::EQUALS,
     NOT_EQ => precedences::EQUALS,
     LT => precedences::LESS_OR_GREATER,
     GT => precedences::LESS_OR_GREATER,
+    LT_EQ => precedences::LESS_OR_GREATER,
+    GT_EQ => precedences::LESS_OR_GREATER,
+    RANGE => precedences::RANGE,
     PLUS => precedences::SUM,
     MINUS => precedences::SUM,
     SLASH => precedences::PRODUCT,
     ASTERISK => precedences::PRODUCT,
+    PERCENT => precedences::PRODUCT,
+    POWER => precedences::POWER,
     LPAREN => precedences::CALL,
+    LBRACKET => precedences::INDEX,
     _x => precedences::LOWEST,
   }
 }
@@ -59,6 +88,8 @@ pub struct Parser {
   pub current_token: Token,
   pub peek_token: Token,
   pub errors: Vec<ParserError>,
+  // The number of loops around the current token, within the current function
+  pub loop_depth: usize,
   pub prefix_parser_functions:
     HashMap<TokenType, fn(&mut Parser) -> Result<Expression, ParserError>>,
   pub infix_parser_functions:
@@ -68,6 +99,7 @@ pub struct Parser {
 pub fn parse_boolean(parser: &mut Parser) -> Result<Expression, ParserError> {
   return Ok(Expression::BooleanLiteral(BooleanLiteral {
     token: parser.current_token.clone(),
+    span: parser.current_token.span,
     value: parser.current_token_is(token_types::TRUE),
   }));
 }
@@ -75,20 +107,46 @@ pub fn parse_boolean(parser: &mut Parser) -> Result<Expression, ParserError> {
 pub fn parse_identifier(parser: &mut Parser) -> Result<Expression, ParserError> {
   let token = parser.current_token.clone();
   let value = token.literal.clone();
+  let span = token.span;
 
   return Ok(Expression::Identifier(Identifier {
     token,
+    span,
     value,
   }));
 }
 
+pub fn parse_illegal(parser: &mut Parser) -> Result<Expression, ParserError> {
+  Err(ParserError::illegal_token(&parser.current_token))
+}
+
 pub fn parse_integer_literal(parser: &mut Parser) -> Result<Expression, ParserError> {
   let token = parser.current_token.clone();
 
-  let value = token.literal.parse::<i64>()?;
+  let span = token.span;
+
+  if let Ok(value) = token.literal.parse::<i64>() {
+    return Ok(Expression::IntegerLiteral(IntegerLiteral { token, span, value }))
+  }
+
+  // Literals too large for an i64 are kept exactly, like the results of arithmetic that overflows
+  let value = BigInteger::from_decimal(&token.literal).ok_or_else(|| ParserError::invalid_integer(&token))?;
+
+  Ok(Expression::BigIntegerLiteral(BigIntegerLiteral { token, span, value }))
+}
+
+pub fn parse_float_literal(parser: &mut Parser) -> Result<Expression, ParserError> {
+  let token = parser.current_token.clone();
+
+  let value = token
+    .literal
+    .parse::<f64>()
+    .map_err(|_| ParserError::new(format!("could not parse {} as float", token.literal)))?;
+  let span = token.span;
 
-  Ok(Expression::IntegerLiteral(IntegerLiteral {
+  Ok(Expression::FloatLiteral(FloatLiteral {
     token,
+    span,
     value,
   }))
 }
@@ -96,9 +154,11 @@ pub fn parse_integer_literal(parser: &mut Parser) -> Result<Expression, ParserEr
 pub fn parse_string_literal(parser: &mut Parser) -> Result<Expression, ParserError> {
   let token = parser.current_token.clone();
   let value = token.literal.clone();
+  let span = token.span;
 
   Ok(Expression::StringLiteral(StringLiteral {
     token,
+    span,
     value,
   }))
 }
@@ -109,10 +169,13 @@ pub fn parse_prefix_expression(parser: &mut Parser) -> Result<Expression, Parser
 
   parser.next_token();
 
-  let expression = parser.parse_expression(precedences::PREFIX)?;
+  // The operand takes in a `**`, so `-2 ** 2` is `-(2 ** 2)` like in math
+  let expression = parser.parse_expression(precedences::POWER - 1)?;
+  let span = parser.span_from(token.span);
 
   Ok(Expression::PrefixExpression(PrefixExpression {
     token,
+    span,
     operator,
     right: Box::new(expression),
   }))
@@ -128,31 +191,201 @@ pub fn parse_infix_expression(
   let precedence = parser.current_precedence();
   parser.next_token();
 
-  let right = parser.parse_expression(precedence)?;
+  // `**` is right-associative: `2 ** 3 ** 2` is `2 ** (3 ** 2)`
+  let right = if operator == token_types::POWER {
+    parser.parse_expression(precedence - 1)?
+  } else {
+    parser.parse_expression(precedence)?
+  };
+  let span = parser.span_from(left.span());
 
   Ok(Expression::InfixExpression(InfixExpression {
     token,
+    span,
     left: Box::new(left),
     operator,
     right: Box::new(right),
   }))
 }
 
+// Assignment is right-associative, so `a = b = 1` assigns 1 to both
+pub fn parse_assign_expression(
+  parser: &mut Parser,
+  left: Expression,
+) -> Result<Expression, ParserError> {
+  let token = parser.current_token.clone();
+
+  let name = match left {
+    Expression::Identifier(identifier) => identifier,
+    target => return Err(ParserError::invalid_assignment_target(&target)),
+  };
+
+  parser.next_token();
+
+  let value = parser.parse_expression(precedences::ASSIGN - 1)?;
+  let span = parser.span_from(name.span);
+
+  Ok(Expression::AssignExpression(AssignExpression {
+    token,
+    span,
+    name,
+    value: Box::new(value),
+  }))
+}
+
 pub fn parse_call_expression(
   parser: &mut Parser,
   function: Expression,
 ) -> Result<Expression, ParserError> {
   let token = parser.current_token.clone();
 
-  let arguments = parser.parse_call_arguments()?;
+  let arguments = parser.parse_expression_list(token_types::RPAREN)?;
+  let span = parser.span_from(function.span());
 
   return Ok(Expression::CallExpression(CallExpression {
     token,
+    span,
     function: Box::new(function),
     arguments: Box::new(arguments),
   }));
 }
 
+pub fn parse_index_expression(
+  parser: &mut Parser,
+  left: Expression,
+) -> Result<Expression, ParserError> {
+  let token = parser.current_token.clone();
+
+  parser.next_token();
+
+  if parser.current_token_is(token_types::COLON) {
+    return parse_slice_expression(parser, token, left, None)
+  }
+
+  let index = parser.parse_expression(precedences::LOWEST)?;
+
+  if parser.peek_token_is(token_types::COLON) {
+    parser.next_token();
+    return parse_slice_expression(parser, token, left, Some(Box::new(index)))
+  }
+
+  parser.expect_peek(token_types::RBRACKET)?;
+  let span = parser.span_from(left.span());
+
+  Ok(Expression::IndexExpression(IndexExpression {
+    token,
+    span,
+    left: Box::new(left),
+    index: Box::new(index),
+  }))
+}
+
+// Continues after the colon of `left[start:end]`, where both bounds are optional
+fn parse_slice_expression(
+  parser: &mut Parser,
+  token: Token,
+  left: Expression,
+  start: Option<Box<Expression>>,
+) -> Result<Expression, ParserError> {
+  let end = if parser.peek_token_is(token_types::RBRACKET) {
+    None
+  } else {
+    parser.next_token();
+    Some(Box::new(parser.parse_expression(precedences::LOWEST)?))
+  };
+
+  parser.expect_peek(token_types::RBRACKET)?;
+  let span = parser.span_from(left.span());
+
+  Ok(Expression::SliceExpression(SliceExpression {
+    token,
+    span,
+    left: Box::new(left),
+    start,
+    end,
+  }))
+}
+
+pub fn parse_interpolated_string(parser: &mut Parser) -> Result<Expression, ParserError> {
+  let token = parser.current_token.clone();
+  let mut parts = vec![];
+
+  // Empty literal parts, like the one before `${x}` in `"${x}!"`, are left out
+  if !token.literal.is_empty() {
+    parts.push(StringPart::Literal(token.literal.clone()));
+  }
+
+  loop {
+    parser.next_token();
+    parts.push(StringPart::Expression(Box::new(parser.parse_expression(precedences::LOWEST)?)));
+
+    if !parser.peek_token_is(token_types::STRING_MIDDLE) {
+      parser.expect_peek(token_types::STRING_END)?;
+    } else {
+      parser.next_token();
+    }
+
+    if !parser.current_token.literal.is_empty() {
+      parts.push(StringPart::Literal(parser.current_token.literal.clone()));
+    }
+
+    if parser.current_token_is(token_types::STRING_END) {
+      break
+    }
+  }
+
+  let span = parser.span_from(token.span);
+
+  Ok(Expression::InterpolatedString(InterpolatedString {
+    token,
+    span,
+    parts,
+  }))
+}
+
+pub fn parse_array_literal(parser: &mut Parser) -> Result<Expression, ParserError> {
+  let token = parser.current_token.clone();
+
+  let elements = parser.parse_expression_list(token_types::RBRACKET)?;
+  let span = parser.span_from(token.span);
+
+  Ok(Expression::ArrayLiteral(ArrayLiteral {
+    token,
+    span,
+    elements,
+  }))
+}
+
+pub fn parse_hash_literal(parser: &mut Parser) -> Result<Expression, ParserError> {
+  let token = parser.current_token.clone();
+  let mut pairs = vec![];
+
+  while !parser.peek_token_is(token_types::RBRACE) {
+    parser.next_token();
+    let key = parser.parse_expression(precedences::LOWEST)?;
+
+    parser.expect_peek(token_types::COLON)?;
+
+    parser.next_token();
+    let value = parser.parse_expression(precedences::LOWEST)?;
+
+    pairs.push((key, value));
+
+    if !parser.peek_token_is(token_types::RBRACE) {
+      parser.expect_peek(token_types::COMMA)?;
+    }
+  }
+
+  parser.expect_peek(token_types::RBRACE)?;
+  let span = parser.span_from(token.span);
+
+  Ok(Expression::HashLiteral(HashLiteral {
+    token,
+    span,
+    pairs,
+  }))
+}
+
 pub fn parse_grouped_expression(parser: &mut Parser) -> Result<Expression, ParserError> {
   parser.next_token();
 
@@ -183,15 +416,78 @@ pub fn parse_if_expression(parser: &mut Parser) -> Result<Expression, ParserErro
   } else {
     None
   };
+  let span = parser.span_from(token.span);
 
   Ok(Expression::IfExpression(IfExpression {
     token,
+    span,
     condition: Box::new(condition),
     true_block: Box::new(true_block),
     false_block_or_none: Box::new(false_block_or_none),
   }))
 }
 
+pub fn parse_while_expression(parser: &mut Parser) -> Result<Expression, ParserError> {
+  let token = parser.current_token.clone();
+
+  parser.expect_peek(token_types::LPAREN)?;
+  parser.next_token();
+
+  let condition = parser.parse_expression(precedences::LOWEST)?;
+
+  parser.expect_peek(token_types::RPAREN)?;
+  parser.expect_peek(token_types::LBRACE)?;
+
+  parser.loop_depth += 1;
+  let body = parser.parse_block_statement();
+  parser.loop_depth -= 1;
+
+  let span = parser.span_from(token.span);
+
+  Ok(Expression::WhileExpression(WhileExpression {
+    token,
+    span,
+    condition: Box::new(condition),
+    body: Box::new(body),
+  }))
+}
+
+pub fn parse_for_expression(parser: &mut Parser) -> Result<Expression, ParserError> {
+  let token = parser.current_token.clone();
+
+  parser.expect_peek(token_types::LPAREN)?;
+  parser.expect_peek(token_types::IDENT)?;
+
+  let variable_token = parser.current_token.clone();
+  let variable = Identifier {
+    span: variable_token.span,
+    value: variable_token.literal.clone(),
+    token: variable_token,
+  };
+
+  parser.expect_peek(token_types::IN)?;
+  parser.next_token();
+
+  let iterable = parser.parse_expression(precedences::LOWEST)?;
+
+  parser.expect_peek(token_types::RPAREN)?;
+  parser.expect_peek(token_types::LBRACE)?;
+
+  parser.loop_depth += 1;
+  let body = parser.parse_block_statement();
+  parser.loop_depth -= 1;
+
+  let span = parser.span_from(token.span);
+
+  Ok(Expression::ForExpression(ForExpression {
+    token,
+    span,
+    variable,
+    iterable: Box::new(iterable),
+    body: Box::new(body),
+  }))
+}
+
 pub fn parse_function_literal(parser: &mut Parser) -> Result<Expression, ParserError> {
   let token = parser.current_token.clone();
 
@@ -201,10 +497,41 @@ pub fn parse_function_literal(parser: &mut Parser) -> Result<Expression, ParserE
 
   parser.expect_peek(token_types::LBRACE)?;
 
+  // `break` and `continue` cannot reach a loop outside the function
+  let loop_depth = parser.loop_depth;
+  parser.loop_depth = 0;
   let body = parser.parse_block_statement();
+  parser.loop_depth = loop_depth;
+
+  let span = parser.span_from(token.span);
 
   Ok(Expression::FunctionLiteral(FunctionLiteral {
     token,
+    span,
+    arguments,
+    body: Box::new(body),
+  }))
+}
+
+pub fn parse_macro_literal(parser: &mut Parser) -> Result<Expression, ParserError> {
+  let token = parser.current_token.clone();
+
+  parser.expect_peek(token_types::LPAREN)?;
+
+  let arguments = parser.parse_function_arguments()?;
+
+  parser.expect_peek(token_types::LBRACE)?;
+
+  let loop_depth = parser.loop_depth;
+  parser.loop_depth = 0;
+  let body = parser.parse_block_statement();
+  parser.loop_depth = loop_depth;
+
+  let span = parser.span_from(token.span);
+
+  Ok(Expression::MacroLiteral(MacroLiteral {
+    token,
+    span,
     arguments,
     body: Box::new(body),
   }))
@@ -220,22 +547,32 @@ impl Parser {
       current_token,
       peek_token,
       errors: vec![],
+      loop_depth: 0,
       prefix_parser_functions: HashMap::new(),
       infix_parser_functions: HashMap::new(),
     };
 
     parser.register_prefix(token_types::IDENT, parse_identifier);
     parser.register_prefix(token_types::INT, parse_integer_literal);
+    parser.register_prefix(token_types::FLOAT, parse_float_literal);
     parser.register_prefix(token_types::STRING, parse_string_literal);
+    parser.register_prefix(token_types::STRING_START, parse_interpolated_string);
     parser.register_prefix(token_types::TRUE, parse_boolean);
     parser.register_prefix(token_types::FALSE, parse_boolean);
 
+    parser.register_prefix(token_types::ILLEGAL, parse_illegal);
+
     parser.register_prefix(token_types::MINUS, parse_prefix_expression);
     parser.register_prefix(token_types::BANG, parse_prefix_expression);
     parser.register_prefix(token_types::LPAREN, parse_grouped_expression);
+    parser.register_prefix(token_types::LBRACKET, parse_array_literal);
+    parser.register_prefix(token_types::LBRACE, parse_hash_literal);
 
     parser.register_prefix(token_types::IF, parse_if_expression);
+    parser.register_prefix(token_types::WHILE, parse_while_expression);
+    parser.register_prefix(token_types::FOR, parse_for_expression);
     parser.register_prefix(token_types::FUNCTION, parse_function_literal);
+    parser.register_prefix(token_types::MACRO, parse_macro_literal);
 
     parser.register_infix(token_types::EQ, parse_infix_expression);
     parser.register_infix(token_types::NOT_EQ, parse_infix_expression);
@@ -245,7 +582,16 @@ impl Parser {
     parser.register_infix(token_types::MINUS, parse_infix_expression);
     parser.register_infix(token_types::SLASH, parse_infix_expression);
     parser.register_infix(token_types::ASTERISK, parse_infix_expression);
+    parser.register_infix(token_types::PERCENT, parse_infix_expression);
+    parser.register_infix(token_types::POWER, parse_infix_expression);
+    parser.register_infix(token_types::LT_EQ, parse_infix_expression);
+    parser.register_infix(token_types::GT_EQ, parse_infix_expression);
+    parser.register_infix(token_types::RANGE, parse_infix_expression);
+    parser.register_infix(token_types::AND, parse_infix_expression);
+    parser.register_infix(token_types::OR, parse_infix_expression);
     parser.register_infix(token_types::LPAREN, parse_call_expression);
+    parser.register_infix(token_types::LBRACKET, parse_index_expression);
+    parser.register_infix(token_types::ASSIGN, parse_assign_expression);
 
     parser
   }
@@ -270,17 +616,10 @@ impl Parser {
       self.next_token();
     }
 
-    let error_count = self.errors.len();
-    if error_count == 0 {
+    if self.errors.is_empty() {
       Ok(program)
     } else {
-      println!("Parser has {} error(s):", error_count);
-
-      for error in &self.errors {
-        println!("parser error: {}", error);
-      }
-
-      Err(ParserError("Invalid program".to_string()))
+      Err(ParserError::new(format!("Invalid program, found {} error(s)", self.errors.len())))
     }
   }
 
@@ -301,16 +640,20 @@ impl Parser {
       self.next_token();
     }
 
+    let span = self.span_from(token.span);
+
     BlockStatement {
       token,
+      span,
       statements,
     }
   }
 
   pub fn parse_statement(&mut self) -> Result<Statement, ParserError> {
     match self.current_token.token_type {
-      LET => self.parse_let_statement(),
+      LET | CONST => self.parse_let_statement(),
       RETURN => self.parse_return_statement(),
+      BREAK | CONTINUE => self.parse_loop_control_statement(),
       _x => self.parse_expression_statement(),
     }
   }
@@ -323,6 +666,7 @@ impl Parser {
     let name_token = self.current_token.clone();
     let name_value = name_token.literal.clone();
     let name = Identifier {
+      span: name_token.span,
       token: name_token,
       value: name_value,
     };
@@ -336,13 +680,36 @@ impl Parser {
       self.next_token();
     }
 
+    let span = self.span_from(token.span);
+
     Ok(Statement::LetStatement(LetStatement {
       token,
+      span,
       name,
-      value: expression,
+      value: Box::new(expression),
     }))
   }
 
+  pub fn parse_loop_control_statement(&mut self) -> Result<Statement, ParserError> {
+    let token = self.current_token.clone();
+
+    if self.loop_depth == 0 {
+      return Err(ParserError::outside_loop(&token))
+    }
+
+    if self.peek_token_is(SEMICOLON) {
+      self.next_token();
+    }
+
+    let span = token.span;
+
+    if token.token_type == BREAK {
+      Ok(Statement::BreakStatement(BreakStatement { token, span }))
+    } else {
+      Ok(Statement::ContinueStatement(ContinueStatement { token, span }))
+    }
+  }
+
   pub fn parse_return_statement(&mut self) -> Result<Statement, ParserError> {
     let token = self.current_token.clone();
 
@@ -354,8 +721,11 @@ impl Parser {
       self.next_token();
     }
 
+    let span = self.span_from(token.span);
+
     let return_statement = ReturnStatement {
       token,
+      span,
       return_value: Box::new(expression),
     };
 
@@ -363,7 +733,15 @@ impl Parser {
   }
 
   pub fn parse_expression_statement(&mut self) -> Result<Statement, ParserError> {
-    let expression = self.parse_expression(precedences::LOWEST)?;
+    // A statement starting with a loop ends with its block, so a next line starting with `(`, `[` or
+    // `-` is a statement of its own rather than a call, index or subtraction. An `if` has a value, and
+    // goes on like any other expression.
+    let expression = if [WHILE, FOR].contains(&self.current_token.token_type) {
+      let prefix_parser_function = self.prefix_parser_function_for(self.current_token.token_type)?;
+      prefix_parser_function(self)?
+    } else {
+      self.parse_expression(precedences::LOWEST)?
+    };
 
     if self.peek_token_is(SEMICOLON) {
       self.next_token();
@@ -411,6 +789,7 @@ impl Parser {
 
       let identifier = Identifier {
         token: self.current_token.clone(),
+        span: self.current_token.span,
         value: self.current_token.literal.clone(),
       };
       identifiers.push(identifier);
@@ -426,20 +805,20 @@ impl Parser {
     Ok(identifiers)
   }
 
-  pub fn parse_call_arguments(&mut self) -> Result<Vec<Expression>, ParserError> {
-    let mut arguments: Vec<Expression> = Vec::new();
+  pub fn parse_expression_list(&mut self, end: TokenType) -> Result<Vec<Expression>, ParserError> {
+    let mut expressions: Vec<Expression> = Vec::new();
 
-    if self.peek_token_is(token_types::RPAREN) {
+    if self.peek_token_is(end) {
       self.next_token();
-      return Ok(arguments);
+      return Ok(expressions);
     }
 
     loop {
       self.next_token();
 
-      arguments.push(self.parse_expression(precedences::LOWEST)?);
+      expressions.push(self.parse_expression(precedences::LOWEST)?);
 
-      if self.peek_token_is(token_types::RPAREN) {
+      if self.peek_token_is(end) {
         self.next_token();
         break;
       };
@@ -447,7 +826,7 @@ impl Parser {
       self.expect_peek(token_types::COMMA)?;
     }
 
-    Ok(arguments)
+    Ok(expressions)
   }
 
   pub fn current_token_is(&mut self, token_type: TokenType) -> bool {
@@ -463,15 +842,16 @@ impl Parser {
       self.next_token();
       Ok(())
     } else {
-      let error = ParserError(format!(
-        "expected next token to be {}, got {} instead",
-        token_type, self.peek_token.token_type
-      ));
-
-      Err(error)
+      Err(ParserError::unexpected_token(token_type, &self.peek_token))
     }
   }
 
+  // Spans a node from its first token up to and including the current token, which is the last
+  // token a parse function consumed.
+  pub fn span_from(&self, start: Span) -> Span {
+    start.to(self.current_token.span)
+  }
+
   pub fn current_precedence(&mut self) -> Precedence {
     get_operator_precedence(self.current_token.token_type)
   }
@@ -487,8 +867,7 @@ impl Parser {
     if let Some(parser_function) = self.prefix_parser_functions.get(token_type) {
       Ok(parser_function)
     } else {
-      let error = ParserError(format!("no prefix parse function found for {}", token_type));
-      Err(error)
+      Err(ParserError::no_prefix_parse_function(&self.current_token))
     }
   }
 
@@ -1,16 +1,92 @@
 use std::fmt;
 
-pub struct ParserError(pub String);
+use crate::ast::Expression;
+use crate::token::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParserErrorKind {
+  UnexpectedToken { expected: TokenType, found: TokenType },
+  NoPrefixParseFunction(TokenType),
+  InvalidInteger,
+  InvalidAssignmentTarget,
+  OutsideLoop(TokenType),
+  IllegalToken,
+  Other,
+}
+
+pub struct ParserError {
+  pub kind: ParserErrorKind,
+  pub message: String,
+  pub span: Option<Span>,
+}
+
+impl ParserError {
+  pub fn new(message: String) -> Self {
+    ParserError { kind: ParserErrorKind::Other, message, span: None }
+  }
+
+  pub fn unexpected_token(expected: TokenType, found: &Token) -> Self {
+    ParserError {
+      kind: ParserErrorKind::UnexpectedToken { expected, found: found.token_type },
+      message: format!("expected next token to be {}, got {} instead", expected, found.token_type),
+      span: Some(found.span),
+    }
+  }
+
+  pub fn no_prefix_parse_function(token: &Token) -> Self {
+    ParserError {
+      kind: ParserErrorKind::NoPrefixParseFunction(token.token_type),
+      message: format!("no prefix parse function found for {}", token.token_type),
+      span: Some(token.span),
+    }
+  }
+
+  pub fn invalid_integer(token: &Token) -> Self {
+    ParserError {
+      kind: ParserErrorKind::InvalidInteger,
+      message: format!("could not parse {} as integer", token.literal),
+      span: Some(token.span),
+    }
+  }
+
+  pub fn invalid_assignment_target(target: &Expression) -> Self {
+    ParserError {
+      kind: ParserErrorKind::InvalidAssignmentTarget,
+      message: format!("cannot assign to {}", target.to_string()),
+      span: Some(target.span()),
+    }
+  }
+
+  pub fn outside_loop(token: &Token) -> Self {
+    ParserError {
+      kind: ParserErrorKind::OutsideLoop(token.token_type),
+      message: format!("{} outside of a loop", token.literal),
+      span: Some(token.span),
+    }
+  }
+
+  // The lexer describes what is wrong with an illegal token in its literal
+  pub fn illegal_token(token: &Token) -> Self {
+    ParserError {
+      kind: ParserErrorKind::IllegalToken,
+      message: token.literal.clone(),
+      span: Some(token.span),
+    }
+  }
+}
 
 impl From<std::num::ParseIntError> for ParserError {
   fn from(_error: std::num::ParseIntError) -> Self {
-    ParserError("ParseIntError".to_string())
+    ParserError::new("ParseIntError".to_string())
   }
 }
 
 impl fmt::Display for ParserError {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    write!(f, "{}", self.0)
+    match &self.span {
+      Some(span) => write!(f, "{}: {}", span, self.message),
+      None => write!(f, "{}", self.message),
+    }
   }
 }
 
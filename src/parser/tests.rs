@@ -1,4 +1,5 @@
 use crate::ast::*;
+use crate::ast::interpolated_string::StringPart;
 use crate::lexer::Lexer;
 use crate::parser::Parser;
 use crate::parser::ParserError;
@@ -101,6 +102,67 @@ fn test_integer_literal_expression() -> Result<(), ParserError> {
   Ok(())
 }
 
+#[test]
+fn test_float_literal_expression() -> Result<(), ParserError> {
+  let tests = vec![
+    ("3.5;", 3.5, "3.5"),
+    (".5;", 0.5, "0.5"),
+    ("2e3;", 2000.0, "2000.0"),
+    ("1e-9;", 1e-9, "1e-9"),
+  ];
+
+  for (input, value, output) in tests {
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    let program = parser.parse_program()?;
+    let expression = match_or_fail!(&program.statements[0], Statement::Expression(m) => m);
+    let float_literal = match_or_fail!(expression, Expression::FloatLiteral(m) => m);
+
+    assert_eq!(float_literal.value, value);
+    assert_eq!(float_literal.to_string(), output);
+  }
+
+  Ok(())
+}
+
+// Integers too large for an i64 are parsed exactly
+#[test]
+fn test_big_integer_literal_expression() -> Result<(), ParserError> {
+  let tests = vec!["9223372036854775808", "10000000000000000000000", "123456789012345678901234567890"];
+
+  for input in tests {
+    let program = Parser::new(Lexer::new(input)).parse_program()?;
+    let expression = match_or_fail!(&program.statements[0], Statement::Expression(m) => m);
+    let big_integer_literal = match_or_fail!(expression, Expression::BigIntegerLiteral(m) => m);
+
+    assert_eq!(big_integer_literal.to_string(), input);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_interpolated_string() -> Result<(), ParserError> {
+  let input = "\"hello ${name}, you are ${age + 1}\"";
+
+  let lexer = Lexer::new(input);
+  let mut parser = Parser::new(lexer);
+
+  let program = parser.parse_program()?;
+  let expression = match_or_fail!(&program.statements[0], Statement::Expression(m) => m);
+  let interpolated_string = match_or_fail!(expression, Expression::InterpolatedString(m) => m);
+
+  assert_eq!(interpolated_string.parts.len(), 4);
+  match_or_fail!(&interpolated_string.parts[0], StringPart::Literal(m) => assert_eq!(m, "hello "));
+  match_or_fail!(&interpolated_string.parts[1], StringPart::Expression(m) => assert_identifier(m, "name"));
+  match_or_fail!(&interpolated_string.parts[2], StringPart::Literal(m) => assert_eq!(m, ", you are "));
+  match_or_fail!(&interpolated_string.parts[3], StringPart::Expression(m) => assert_eq!(m.to_string(), "(age + 1)"));
+  assert_eq!(interpolated_string.to_string(), "hello ${name}, you are ${(age + 1)}");
+
+  Ok(())
+}
+
 #[test]
 fn test_string_literal_expression() -> Result<(), ParserError> {
   let input = "\"hello world\"";
@@ -297,6 +359,28 @@ fn test_operator_precedence_parsing() -> Result<(), ParserError> {
       "add(a + b + c * d / f + g)",
       "add((((a + b) + ((c * d) / f)) + g))",
     ),
+    (
+      "a * [1, 2, 3, 4][b * c] * d",
+      "((a * ([1, 2, 3, 4][(b * c)])) * d)",
+    ),
+    (
+      "add(a * b[2], b[1], 2 * [1, 2][1])",
+      "add((a * (b[2])), (b[1]), (2 * ([1, 2][1])))",
+    ),
+    ("a = b = 1 + 2", "(a = (b = (1 + 2)))"),
+    ("a = b == c", "(a = (b == c))"),
+    ("0..n + 1 < m", "((0 .. (n + 1)) < m)"),
+    ("a || b && c == d", "(a || (b && (c == d)))"),
+    ("a && b || c && d", "((a && b) || (c && d))"),
+    ("x = a || b", "(x = (a || b))"),
+    ("a <= b == b >= c", "((a <= b) == (b >= c))"),
+    ("a + b % c", "(a + (b % c))"),
+    ("a ** b ** c", "(a ** (b ** c))"),
+    ("a * b ** c", "(a * (b ** c))"),
+    ("-a ** b", "(-(a ** b))"),
+    ("(-a) ** b", "((-a) ** b)"),
+    ("-a ** b * c", "((-(a ** b)) * c)"),
+    ("a ** -b ** c", "(a ** (-(b ** c)))"),
   ];
 
   for test in &tests {
@@ -313,6 +397,43 @@ fn test_operator_precedence_parsing() -> Result<(), ParserError> {
   Ok(())
 }
 
+#[test]
+fn test_assign_expression() -> Result<(), ParserError> {
+  let input = "x = 5;";
+
+  let lexer = Lexer::new(input);
+  let mut parser = Parser::new(lexer);
+
+  let program = parser.parse_program()?;
+
+  let assign_expression = match_or_fail!(
+    &program.statements[0],
+    Statement::Expression(Expression::AssignExpression(m)) => m
+  );
+
+  assert_eq!(assign_expression.name.value, "x");
+  assert_literal(&assign_expression.value, &LiteralValue::Integer(5));
+
+  Ok(())
+}
+
+#[test]
+fn test_const_statement() -> Result<(), ParserError> {
+  let lexer = Lexer::new("const x = 5; let y = 1;");
+  let mut parser = Parser::new(lexer);
+
+  let program = parser.parse_program()?;
+
+  let const_statement = match_or_fail!(&program.statements[0], Statement::LetStatement(m) => m);
+  assert!(const_statement.is_constant());
+  assert_eq!(const_statement.to_string(), "const x = 5;");
+
+  let let_statement = match_or_fail!(&program.statements[1], Statement::LetStatement(m) => m);
+  assert!(!let_statement.is_constant());
+
+  Ok(())
+}
+
 #[test]
 fn test_if_expression() -> Result<(), ParserError> {
   let input = "if (x < y) { x }";
@@ -384,6 +505,76 @@ fn test_if_else_expression() -> Result<(), ParserError> {
   Ok(())
 }
 
+#[test]
+fn test_while_expression() -> Result<(), ParserError> {
+  let input = "while (x < y) { break; continue }";
+
+  let lexer = Lexer::new(input);
+  let mut parser = Parser::new(lexer);
+
+  let program = parser.parse_program()?;
+
+  let while_expression = match_or_fail!(
+    &program.statements[0],
+    Statement::Expression(Expression::WhileExpression(m)) => m
+  );
+
+  assert_infix(
+    &while_expression.condition,
+    &LiteralValue::Identifier("x"),
+    "<",
+    &LiteralValue::Identifier("y"),
+  );
+  match_or_fail!(&while_expression.body.statements[0], Statement::BreakStatement(_) => ());
+  match_or_fail!(&while_expression.body.statements[1], Statement::ContinueStatement(_) => ());
+
+  Ok(())
+}
+
+#[test]
+fn test_for_expression() -> Result<(), ParserError> {
+  let input = "for (x in 0..len(xs)) { x }";
+
+  let lexer = Lexer::new(input);
+  let mut parser = Parser::new(lexer);
+
+  let program = parser.parse_program()?;
+
+  let for_expression = match_or_fail!(
+    &program.statements[0],
+    Statement::Expression(Expression::ForExpression(m)) => m
+  );
+
+  assert_eq!(for_expression.variable.value, "x");
+  assert_eq!(for_expression.iterable.to_string(), "(0 .. len(xs))");
+  assert_eq!(for_expression.body.statements.len(), 1);
+
+  Ok(())
+}
+
+// A statement starting with a loop ends with its block, whatever the next line starts with, while
+// one starting with an `if` goes on
+#[test]
+fn test_block_expression_statements_end_with_their_block() -> Result<(), ParserError> {
+  let tests = vec![
+    ("for (x in xs) { x }\n[1, 2]", 2),
+    ("while (x) { x }\n(a + b)", 2),
+    ("while (x) { x }\n-1", 2),
+    ("(for (x in xs) { x })[0] - 1", 1),
+    ("if (x) { 1 } else { 2 }\n-1", 1),
+    ("if (x) { f }\n(1)[0]", 1),
+    ("let a = if (x) { f } else { g }(1) - 1", 1),
+  ];
+
+  for (input, expected) in tests {
+    let program = Parser::new(Lexer::new(input)).parse_program()?;
+
+    assert_eq!(program.statements.len(), expected, "input: {}", input);
+  }
+
+  Ok(())
+}
+
 #[test]
 fn test_function_literal() -> Result<(), ParserError> {
   let input = "fn (x, y) { x + y };";
@@ -425,6 +616,39 @@ fn test_function_literal() -> Result<(), ParserError> {
   Ok(())
 }
 
+#[test]
+fn test_macro_literal() -> Result<(), ParserError> {
+  let input = "macro(x, y) { x + y; }";
+
+  let lexer = Lexer::new(input);
+  let mut parser = Parser::new(lexer);
+
+  let program = parser.parse_program()?;
+
+  assert_eq!(program.statements.len(), 1);
+
+  let macro_literal = match_or_fail!(
+    &program.statements[0],
+    Statement::Expression(Expression::MacroLiteral(m)) => m
+  );
+
+  assert_eq!(macro_literal.token.literal, "macro");
+  assert_eq!(macro_literal.arguments.len(), 2);
+  assert_eq!(macro_literal.arguments[0].value, "x");
+  assert_eq!(macro_literal.arguments[1].value, "y");
+
+  assert_eq!(macro_literal.body.statements.len(), 1);
+  let body_expression = match_or_fail!(&macro_literal.body.statements[0], Statement::Expression(m) => m);
+  assert_infix(
+    body_expression,
+    &LiteralValue::Identifier("x"),
+    "+",
+    &LiteralValue::Identifier("y"),
+  );
+
+  Ok(())
+}
+
 #[test]
 fn test_function_parameter_parsing() -> Result<(), ParserError> {
   let tests = vec![
@@ -497,6 +721,253 @@ fn test_call_expression() -> Result<(), ParserError> {
   Ok(())
 }
 
+#[test]
+fn test_array_literal() -> Result<(), ParserError> {
+  let input = "[1, 2 * 2, 3 + 3]";
+
+  let lexer = Lexer::new(input);
+  let mut parser = Parser::new(lexer);
+
+  let program = parser.parse_program()?;
+
+  assert_eq!(program.statements.len(), 1);
+
+  let first_statement = &program.statements[0];
+  let array_literal = match_or_fail!(
+    first_statement,
+    Statement::Expression(Expression::ArrayLiteral(m)) => m
+  );
+
+  assert_eq!(array_literal.elements.len(), 3);
+  assert_integer_literal(&array_literal.elements[0], &1);
+  assert_infix(
+    &array_literal.elements[1],
+    &LiteralValue::Integer(2),
+    "*",
+    &LiteralValue::Integer(2),
+  );
+  assert_infix(
+    &array_literal.elements[2],
+    &LiteralValue::Integer(3),
+    "+",
+    &LiteralValue::Integer(3),
+  );
+
+  Ok(())
+}
+
+#[test]
+fn test_empty_array_literal() -> Result<(), ParserError> {
+  let input = "[]";
+
+  let lexer = Lexer::new(input);
+  let mut parser = Parser::new(lexer);
+
+  let program = parser.parse_program()?;
+
+  let first_statement = &program.statements[0];
+  let array_literal = match_or_fail!(
+    first_statement,
+    Statement::Expression(Expression::ArrayLiteral(m)) => m
+  );
+
+  assert_eq!(array_literal.elements.len(), 0);
+
+  Ok(())
+}
+
+#[test]
+fn test_index_expression() -> Result<(), ParserError> {
+  let input = "my_array[1 + 1]";
+
+  let lexer = Lexer::new(input);
+  let mut parser = Parser::new(lexer);
+
+  let program = parser.parse_program()?;
+
+  assert_eq!(program.statements.len(), 1);
+
+  let first_statement = &program.statements[0];
+  let index_expression = match_or_fail!(
+    first_statement,
+    Statement::Expression(Expression::IndexExpression(m)) => m
+  );
+
+  assert_identifier(&index_expression.left, "my_array");
+  assert_infix(
+    &index_expression.index,
+    &LiteralValue::Integer(1),
+    "+",
+    &LiteralValue::Integer(1),
+  );
+
+  Ok(())
+}
+
+#[test]
+fn test_slice_expressions() -> Result<(), ParserError> {
+  let tests = vec![
+    ("s[1:2]", "(s[1:2])"),
+    ("s[:2]", "(s[:2])"),
+    ("s[1:]", "(s[1:])"),
+    ("s[:]", "(s[:])"),
+    ("s[a + 1:len(s) - 1][0]", "((s[(a + 1):(len(s) - 1)])[0])"),
+  ];
+
+  for (input, expected) in tests {
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    let program = parser.parse_program()?;
+    assert_eq!(program.statements.len(), 1);
+    assert_eq!(program.to_string(), expected);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_hash_literal_string_keys() -> Result<(), ParserError> {
+  let input = "{\"one\": 1, \"two\": 2, \"three\": 3}";
+
+  let lexer = Lexer::new(input);
+  let mut parser = Parser::new(lexer);
+
+  let program = parser.parse_program()?;
+
+  assert_eq!(program.statements.len(), 1);
+
+  let first_statement = &program.statements[0];
+  let hash_literal = match_or_fail!(
+    first_statement,
+    Statement::Expression(Expression::HashLiteral(m)) => m
+  );
+
+  let expected = [("one", 1), ("two", 2), ("three", 3)];
+  assert_eq!(hash_literal.pairs.len(), expected.len());
+
+  for (i, (key, value)) in expected.iter().enumerate() {
+    let (key_expression, value_expression) = &hash_literal.pairs[i];
+    assert_string_literal(key_expression, key);
+    assert_integer_literal(value_expression, value);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_empty_hash_literal() -> Result<(), ParserError> {
+  let input = "{}";
+
+  let lexer = Lexer::new(input);
+  let mut parser = Parser::new(lexer);
+
+  let program = parser.parse_program()?;
+
+  let first_statement = &program.statements[0];
+  let hash_literal = match_or_fail!(
+    first_statement,
+    Statement::Expression(Expression::HashLiteral(m)) => m
+  );
+
+  assert_eq!(hash_literal.pairs.len(), 0);
+
+  Ok(())
+}
+
+#[test]
+fn test_hash_literal_with_expressions() -> Result<(), ParserError> {
+  let input = "{1: 0 + 1, true: 10 - 8, \"three\": 15 / 5}";
+
+  let lexer = Lexer::new(input);
+  let mut parser = Parser::new(lexer);
+
+  let program = parser.parse_program()?;
+
+  let first_statement = &program.statements[0];
+  let hash_literal = match_or_fail!(
+    first_statement,
+    Statement::Expression(Expression::HashLiteral(m)) => m
+  );
+
+  assert_eq!(hash_literal.pairs.len(), 3);
+
+  let (first_key, first_value) = &hash_literal.pairs[0];
+  assert_integer_literal(first_key, &1);
+  assert_infix(first_value, &LiteralValue::Integer(0), "+", &LiteralValue::Integer(1));
+
+  let (second_key, second_value) = &hash_literal.pairs[1];
+  assert_boolean(second_key, &true);
+  assert_infix(second_value, &LiteralValue::Integer(10), "-", &LiteralValue::Integer(8));
+
+  let (third_key, third_value) = &hash_literal.pairs[2];
+  assert_string_literal(third_key, "three");
+  assert_infix(third_value, &LiteralValue::Integer(15), "/", &LiteralValue::Integer(5));
+
+  Ok(())
+}
+
+#[test]
+fn test_node_spans() -> Result<(), ParserError> {
+  let input = "let add = fn(a, b) {\n  a + b\n};\nadd(1, 2 * 3)";
+
+  let lexer = Lexer::new(input);
+  let mut parser = Parser::new(lexer);
+
+  let program = parser.parse_program()?;
+
+  let let_statement = match_or_fail!(&program.statements[0], Statement::LetStatement(m) => m);
+  assert_eq!(let_statement.span.to_string(), "1:1");
+  assert_eq!(let_statement.span.end.offset, 31);
+  assert_eq!(let_statement.name.span.to_string(), "1:5");
+
+  let function_literal = match_or_fail!(&*let_statement.value, Expression::FunctionLiteral(m) => m);
+  assert_eq!(function_literal.span.to_string(), "1:11");
+  assert_eq!(function_literal.body.span.end.line, 3);
+
+  let body_expression = match_or_fail!(&function_literal.body.statements[0], Statement::Expression(m) => m);
+  assert_eq!(body_expression.span().to_string(), "2:3");
+  assert_eq!(body_expression.span().end.column, 8);
+
+  let call_expression = match_or_fail!(
+    &program.statements[1],
+    Statement::Expression(Expression::CallExpression(m)) => m
+  );
+  assert_eq!(call_expression.span.to_string(), "4:1");
+  assert_eq!(call_expression.span.end.column, 14);
+  assert_eq!(call_expression.arguments[1].span().to_string(), "4:8");
+
+  Ok(())
+}
+
+#[test]
+fn test_parser_error_positions() {
+  let tests = vec![
+    ("let x 5;", "1:7: expected next token to be =, got INT instead"),
+    ("let x = 1;\nlet y = add(1,\n  2", "3:4: expected next token to be ,, got EOF instead"),
+    ("let x = 1;\n  let = 2;", "2:7: expected next token to be IDENT, got = instead"),
+    ("1 + ;", "1:5: no prefix parse function found for ;"),
+    ("x + 1 = 2", "1:1: cannot assign to (x + 1)"),
+    ("if (true) { break }", "1:13: break outside of a loop"),
+    ("while (true) { fn() { continue } }", "1:23: continue outside of a loop"),
+    ("let a = 1 @ 2;", "1:11: unexpected character: @"),
+    ("let s = \"a\\qb\";", "1:9: invalid escape sequence: \\q"),
+    ("let s = \"open", "1:9: unterminated string"),
+    ("\"a ${1 2}\"", "1:8: expected next token to be STRING_END, got INT instead"),
+    ("\"a ${}\"", "1:6: no prefix parse function found for STRING_END"),
+  ];
+
+  for test in &tests {
+    let (input, expected) = test;
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    match_or_fail!(parser.parse_program(), Err(_) => ());
+    assert_eq!(&parser.errors[0].to_string(), expected);
+  }
+}
+
 fn assert_boolean(expression: &Expression, value: &bool) {
   if let Expression::BooleanLiteral(boolean_literal) = expression {
     assert_eq!(&boolean_literal.value, value);
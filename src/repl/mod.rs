@@ -2,17 +2,16 @@ use std::io;
 use std::io::prelude::*;
 use std::process;
 
+use crate::diagnostics::*;
+use crate::engine::*;
 use crate::lexer::Lexer;
+use crate::optimizer::Optimization;
 use crate::parser::Parser;
-use crate::eval::eval;
-use crate::object::environment::*;
-use std::rc::Rc;
-use std::cell::RefCell;
 
-pub fn start() {
+pub fn start(style: Style, engine: Engine, optimization: Optimization) {
   let stdin = io::stdin();
   let mut stdout = io::stdout();
-  let env: WrappedEnv = Rc::new(RefCell::new(Environment::new()));
+  let mut session = Session::new(engine).with_optimization(optimization);
 
   loop {
     if write!(&mut stdout, ">> ").is_err() {
@@ -37,13 +36,16 @@ pub fn start() {
     let program_result = parser.parse_program();
 
     match program_result {
-      Err(_error) => continue,
+      Err(_error) => {
+        print!("{}", render_parser_errors(&parser.errors, &input, "<repl>", style));
+        continue
+      },
       Ok(program) => {
-        let eval_result = eval(&program, &env);
+        let eval_result = session.run(&program);
 
         match eval_result {
           Err(error) => {
-            println!("Evaluation error {:?}", error);
+            print!("{}", Diagnostic::from(&error).render(&input, "<repl>", style));
             continue
           },
           Ok(object) => println!("{}", object.inspect())
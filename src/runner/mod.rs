@@ -0,0 +1,150 @@
+use std::fs;
+use std::io;
+use std::io::prelude::*;
+
+use crate::diagnostics::*;
+use crate::engine::*;
+use crate::formatter;
+use crate::lexer::Lexer;
+use crate::optimizer::Optimization;
+use crate::parser::Parser;
+
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_UNFORMATTED: i32 = 1;
+pub const EXIT_PARSER_ERROR: i32 = 65;
+pub const EXIT_EVAL_ERROR: i32 = 70;
+// A bug in the interpreter itself, with the code Rust uses for panics
+pub const EXIT_INTERNAL_ERROR: i32 = 101;
+pub const EXIT_IO_ERROR: i32 = 74;
+
+pub fn run_file(path: &str, style: Style, engine: Engine, optimization: Optimization) -> i32 {
+  match fs::read_to_string(path) {
+    Ok(source) => run_source(&source, path, style, engine, optimization),
+    Err(error) => {
+      eprintln!("Could not read {}: {}", path, error);
+      EXIT_IO_ERROR
+    },
+  }
+}
+
+pub fn run_stdin(style: Style, engine: Engine, optimization: Optimization) -> i32 {
+  let mut source = String::new();
+
+  match io::stdin().read_to_string(&mut source) {
+    Ok(_) => run_source(&source, "<stdin>", style, engine, optimization),
+    Err(error) => {
+      eprintln!("Could not read stdin: {}", error);
+      EXIT_IO_ERROR
+    },
+  }
+}
+
+// Lexes, parses and evaluates a whole program, returning the process exit code. `origin` names
+// the source in diagnostics, e.g. the path of the script.
+pub fn run_source(source: &str, origin: &str, style: Style, engine: Engine, optimization: Optimization) -> i32 {
+  let lexer = Lexer::new(source);
+  let mut parser = Parser::new(lexer);
+
+  let program = match parser.parse_program() {
+    Ok(program) => program,
+    Err(_error) => {
+      eprint!("{}", render_parser_errors(&parser.errors, source, origin, style));
+      return EXIT_PARSER_ERROR
+    },
+  };
+
+  match Session::new(engine).with_optimization(optimization).run(&program) {
+    Ok(_) => EXIT_SUCCESS,
+    Err(error) => {
+      eprint!("{}", Diagnostic::from(&error).render(source, origin, style));
+      EXIT_EVAL_ERROR
+    },
+  }
+}
+
+// Formats the scripts in place, or with `check` only lists the ones that are not formatted
+pub fn format_files(paths: &[String], check: bool, style: Style) -> i32 {
+  let mut exit_code = EXIT_SUCCESS;
+
+  for path in paths {
+    let source = match fs::read_to_string(path) {
+      Ok(source) => source,
+      Err(error) => {
+        eprintln!("Could not read {}: {}", path, error);
+        exit_code = EXIT_IO_ERROR;
+        continue
+      },
+    };
+
+    let formatted = match format_source(&source, path, style) {
+      Ok(formatted) => formatted,
+      Err(error_code) => {
+        exit_code = error_code;
+        continue
+      },
+    };
+
+    if formatted == source {
+      continue
+    }
+
+    if check {
+      eprintln!("{} is not formatted", path);
+      exit_code = EXIT_UNFORMATTED;
+    } else if let Err(error) = fs::write(path, formatted) {
+      eprintln!("Could not write {}: {}", path, error);
+      exit_code = EXIT_IO_ERROR;
+    }
+  }
+
+  exit_code
+}
+
+pub fn format_stdin(check: bool, style: Style) -> i32 {
+  let mut source = String::new();
+
+  if let Err(error) = io::stdin().read_to_string(&mut source) {
+    eprintln!("Could not read stdin: {}", error);
+    return EXIT_IO_ERROR
+  }
+
+  let formatted = match format_source(&source, "<stdin>", style) {
+    Ok(formatted) => formatted,
+    Err(error_code) => return error_code,
+  };
+
+  if check {
+    if formatted != source {
+      eprintln!("<stdin> is not formatted");
+      return EXIT_UNFORMATTED
+    }
+  } else {
+    print!("{}", formatted);
+  }
+
+  EXIT_SUCCESS
+}
+
+// Formats a whole program, or reports why it could not be formatted and returns the exit code
+fn format_source(source: &str, origin: &str, style: Style) -> Result<String, i32> {
+  let mut parser = Parser::new(Lexer::new(source));
+
+  let program = match parser.parse_program() {
+    Ok(program) => program,
+    Err(_error) => {
+      eprint!("{}", render_parser_errors(&parser.errors, source, origin, style));
+      return Err(EXIT_PARSER_ERROR)
+    },
+  };
+
+  let formatted = formatter::format(&program, source);
+
+  // Formatting only ever changes the layout, never the program itself
+  match Parser::new(Lexer::new(&formatted)).parse_program() {
+    Ok(formatted_program) if formatted_program.to_string() == program.to_string() => Ok(formatted),
+    _ => {
+      eprintln!("Could not format {}: the formatted program would differ from the original", origin);
+      Err(EXIT_INTERNAL_ERROR)
+    },
+  }
+}
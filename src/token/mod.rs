@@ -1,22 +1,72 @@
+use std::fmt;
+
 pub type TokenType = &'static str;
 pub type Literal = String;
 
 #[derive(Clone)]
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Token {
   pub token_type: TokenType,
   pub literal: Literal,
+  pub span: Span,
+}
+
+// Lines and columns are 1-based and count characters, while the offset counts bytes from the
+// start of the source.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Position {
+  pub line: usize,
+  pub column: usize,
+  pub offset: usize,
+}
+
+impl Default for Position {
+  fn default() -> Self {
+    Position { line: 1, column: 1, offset: 0 }
+  }
+}
+
+impl fmt::Display for Position {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}:{}", self.line, self.column)
+  }
+}
+
+// The end position is exclusive, so an empty span (like the one of EOF) has start == end.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Span {
+  pub start: Position,
+  pub end: Position,
+}
+
+impl Span {
+  pub fn to(&self, other: Span) -> Span {
+    Span { start: self.start, end: other.end }
+  }
+}
+
+impl fmt::Display for Span {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.start)
+  }
 }
 
 pub fn get_token_type_for_string(string: &str) -> TokenType {
   match string {
     "fn" => token_types::FUNCTION,
+    "macro" => token_types::MACRO,
     "let" => token_types::LET,
+    "const" => token_types::CONST,
     "true" => token_types::TRUE,
     "false" => token_types::FALSE,
     "if" => token_types::IF,
     "else" => token_types::ELSE,
     "return" => token_types::RETURN,
+    "while" => token_types::WHILE,
+    "for" => token_types::FOR,
+    "in" => token_types::IN,
+    "break" => token_types::BREAK,
+    "continue" => token_types::CONTINUE,
     _x => token_types::IDENT,
   }
 }
@@ -24,11 +74,19 @@ pub fn get_token_type_for_string(string: &str) -> TokenType {
 pub mod token_types {
   pub const ILLEGAL: &str = "ILLEGAL";
   pub const EOF: &str = "EOF";
+  // Only produced when the lexer is asked to keep comments
+  pub const COMMENT: &str = "COMMENT";
 
   // Identifiers + literals
   pub const IDENT: &str = "IDENT";
   pub const INT: &str = "INT";
+  pub const FLOAT: &str = "FLOAT";
   pub const STRING: &str = "STRING";
+  // The literal parts of an interpolated string like `"a ${x} b ${y} c"`, which are lexed as
+  // STRING_START("a "), x, STRING_MIDDLE(" b "), y, STRING_END(" c")
+  pub const STRING_START: &str = "STRING_START";
+  pub const STRING_MIDDLE: &str = "STRING_MIDDLE";
+  pub const STRING_END: &str = "STRING_END";
 
   // Operators
   pub const ASSIGN: &str = "=";
@@ -37,28 +95,45 @@ pub mod token_types {
   pub const BANG: &str = "!";
   pub const ASTERISK: &str = "*";
   pub const SLASH: &str = "/";
+  pub const PERCENT: &str = "%";
+  pub const POWER: &str = "**";
   pub const LT: &str = "<";
   pub const GT: &str = ">";
+  pub const LT_EQ: &str = "<=";
+  pub const GT_EQ: &str = ">=";
   pub const EQ: &str = "==";
   pub const NOT_EQ: &str = "!=";
+  pub const RANGE: &str = "..";
+  pub const AND: &str = "&&";
+  pub const OR: &str = "||";
 
   // Delimiters
   pub const COMMA: &str = ",";
   pub const SEMICOLON: &str = ";";
+  pub const COLON: &str = ":";
 
   pub const LPAREN: &str = "(";
   pub const RPAREN: &str = ")";
   pub const LBRACE: &str = "{";
   pub const RBRACE: &str = "}";
+  pub const LBRACKET: &str = "[";
+  pub const RBRACKET: &str = "]";
 
   // Keywords
   pub const FUNCTION: &str = "FUNCTION";
+  pub const MACRO: &str = "MACRO";
   pub const LET: &str = "LET";
+  pub const CONST: &str = "CONST";
   pub const TRUE: &str = "TRUE";
   pub const FALSE: &str = "FALSE";
   pub const IF: &str = "IF";
   pub const ELSE: &str = "ELSE";
   pub const RETURN: &str = "RETURN";
+  pub const WHILE: &str = "WHILE";
+  pub const FOR: &str = "FOR";
+  pub const IN: &str = "IN";
+  pub const BREAK: &str = "BREAK";
+  pub const CONTINUE: &str = "CONTINUE";
 }
 
 pub const WHITESPACE_CHARS: [char; 4] = [' ', '\t', '\n', '\r'];
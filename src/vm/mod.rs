@@ -0,0 +1,398 @@
+#[cfg(test)]
+mod tests;
+
+use std::rc::Rc;
+
+use crate::builtins::get_builtin;
+use crate::code::*;
+use crate::compiler::Bytecode;
+use crate::eval::eval_error::EvalError;
+use crate::eval::*;
+use crate::eval::macro_expansion::quote;
+use crate::object::hash::*;
+use crate::object::scope::*;
+use crate::object::*;
+use crate::token::*;
+
+struct Frame {
+  function: Rc<CompiledFunction>,
+  ip: usize,
+  // The height of the stack when the frame was entered. Anything above it belongs to the frame.
+  base_pointer: usize,
+  scope: WrappedScope,
+  // The scopes the frame has entered from, with the height the stack had at the time
+  outer_scopes: Vec<(WrappedScope, usize)>,
+}
+
+// Executes bytecode with a value stack and a stack of call frames. The globals survive between
+// calls to `run`, so the REPL can run each line against the same state.
+pub struct VM {
+  constants: Vec<Object>,
+  stack: Vec<Object>,
+  frames: Vec<Frame>,
+  globals: WrappedScope,
+}
+
+impl VM {
+  pub fn new() -> Self {
+    VM {
+      constants: vec![],
+      stack: vec![],
+      frames: vec![],
+      globals: Scope::new(0),
+    }
+  }
+
+  pub fn run(&mut self, bytecode: Bytecode) -> Result<Object, EvalError> {
+    self.constants = bytecode.constants;
+    self.globals.borrow_mut().ensure_slots(bytecode.main.slot_count);
+
+    self.stack.clear();
+    self.frames = vec![Frame {
+      function: bytecode.main,
+      ip: 0,
+      base_pointer: 0,
+      scope: Rc::clone(&self.globals),
+      outer_scopes: vec![],
+    }];
+
+    let result = self.execute(0);
+
+    self.stack.clear();
+    self.frames.clear();
+
+    result
+  }
+
+  // Runs until the frame stack is back at `depth`, which is how calls from builtins wait for the
+  // function they called to return.
+  fn execute(&mut self, depth: usize) -> Result<Object, EvalError> {
+    loop {
+      if self.frames.len() == depth {
+        return Ok(self.pop())
+      }
+
+      let frame = self.frames.last_mut().unwrap();
+
+      // Only the main function can run past its last instruction, functions end with a return.
+      if frame.ip >= frame.function.instructions.len() {
+        return Ok(self.stack.pop().unwrap_or(Object::Null))
+      }
+
+      let instruction = frame.function.instructions[frame.ip];
+      let span = frame.function.spans[frame.ip];
+      frame.ip += 1;
+
+      match self.execute_instruction(instruction) {
+        Ok(Some(result)) => return Ok(result),
+        Ok(None) => {},
+        Err(error) => return Err(error.with_span_or_keep(span)),
+      }
+    }
+  }
+
+  // Returns the result of the program once the main function returns.
+  fn execute_instruction(&mut self, instruction: Instruction) -> Result<Option<Object>, EvalError> {
+    match instruction {
+      Instruction::Constant(index) => self.push(self.constants[index].clone()),
+      Instruction::Null => self.push(Object::Null),
+      Instruction::True => self.push(Object::Boolean(true)),
+      Instruction::False => self.push(Object::Boolean(false)),
+      Instruction::Pop => {
+        self.pop();
+      },
+
+      Instruction::Add => self.execute_infix(token_types::PLUS)?,
+      Instruction::Subtract => self.execute_infix(token_types::MINUS)?,
+      Instruction::Multiply => self.execute_infix(token_types::ASTERISK)?,
+      Instruction::Divide => self.execute_infix(token_types::SLASH)?,
+      Instruction::Modulo => self.execute_infix(token_types::PERCENT)?,
+      Instruction::Power => self.execute_infix(token_types::POWER)?,
+      Instruction::Equal => self.execute_infix(token_types::EQ)?,
+      Instruction::NotEqual => self.execute_infix(token_types::NOT_EQ)?,
+      Instruction::LessThan => self.execute_infix(token_types::LT)?,
+      Instruction::GreaterThan => self.execute_infix(token_types::GT)?,
+      Instruction::LessThanOrEqual => self.execute_infix(token_types::LT_EQ)?,
+      Instruction::GreaterThanOrEqual => self.execute_infix(token_types::GT_EQ)?,
+      Instruction::Range => self.execute_infix(token_types::RANGE)?,
+      Instruction::Minus => self.execute_prefix(token_types::MINUS)?,
+      Instruction::Bang => self.execute_prefix(token_types::BANG)?,
+
+      Instruction::Jump(target) => self.current_frame().ip = target,
+      Instruction::JumpIfFalsy(target) => {
+        let condition = self.pop();
+
+        if !*condition.get_is_truthy() {
+          self.current_frame().ip = target;
+        }
+      },
+
+      Instruction::EnterScope(slot_count) => {
+        let stack_height = self.stack.len();
+        let frame = self.current_frame();
+        let scope = Scope::extend(&frame.scope, slot_count);
+
+        frame.outer_scopes.push((std::mem::replace(&mut frame.scope, scope), stack_height));
+      },
+      Instruction::LeaveScope => {
+        let frame = self.current_frame();
+        let (scope, stack_height) = frame.outer_scopes.pop().unwrap();
+
+        frame.scope = scope;
+        self.stack.truncate(stack_height);
+      },
+
+      Instruction::PrepareIteration => {
+        let iterable = eval_iterable(self.pop())?;
+
+        self.push(iterable);
+        self.push(Object::Integer(0));
+      },
+      Instruction::Iterate(target) => {
+        // The current scope was entered after the iterable and the index were pushed
+        let stack_height = self.current_frame().outer_scopes.last().unwrap().1;
+        let index = match &self.stack[stack_height - 1] {
+          Object::Integer(index) => *index as usize,
+          x => return Err(EvalError::new(format!("Expected iteration index, found: {:?}", x))),
+        };
+
+        match get_iteration_item(&self.stack[stack_height - 2], index) {
+          Some(item) => {
+            self.stack[stack_height - 1] = Object::Integer(index as i64 + 1);
+            self.push(item);
+          },
+          None => self.current_frame().ip = target,
+        }
+      },
+
+      Instruction::JumpIfFalsyOrPop(target) | Instruction::JumpIfTruthyOrPop(target) => {
+        let jump_when = matches!(instruction, Instruction::JumpIfTruthyOrPop(_));
+
+        if *self.stack.last().unwrap().get_is_truthy() == jump_when {
+          self.current_frame().ip = target;
+        } else {
+          self.pop();
+        }
+      },
+
+      Instruction::GetName(index) => {
+        let value = self.get_name(index)?;
+        self.push(value);
+      },
+      Instruction::SetName(index) => {
+        let value = self.pop();
+        self.set_name(index, value.clone())?;
+        self.push(value);
+      },
+      Instruction::Define(slot, name) | Instruction::DefineConstant(slot, name) => {
+        let value = self.pop();
+        let frame = self.current_frame();
+        let mut scope = frame.scope.borrow_mut();
+
+        let defined = match instruction {
+          Instruction::DefineConstant(..) => scope.define_constant(slot, value),
+          _ => scope.define(slot, value),
+        };
+
+        if !defined {
+          let name = &frame.function.names[name];
+          return Err(EvalError::new(format!("Identifier has already been declared: {}", name)))
+        }
+      },
+
+      Instruction::Array(length) => {
+        let elements = self.pop_many(length);
+        self.push(Object::Array(elements));
+      },
+      Instruction::Hash(length) => {
+        let objects = self.pop_many(length * 2);
+        let mut hash = Hash::new();
+
+        for pair in objects.chunks(2) {
+          let hash_key = pair[0].get_hash_key()?;
+          hash.insert(hash_key, pair[0].clone(), pair[1].clone());
+        }
+
+        self.push(Object::Hash(hash));
+      },
+      Instruction::Interpolate(length) => {
+        let parts = self.pop_many(length);
+        self.push(interpolate(&parts));
+      },
+      Instruction::Index => {
+        let index = self.pop();
+        let left = self.pop();
+
+        self.push(eval_index_expression(left, index)?);
+      },
+
+      Instruction::Slice => {
+        let end = self.pop();
+        let start = self.pop();
+        let left = self.pop();
+
+        self.push(eval_slice_expression(left, start, end)?);
+      },
+
+      Instruction::Quote(index, count) => {
+        let mut values = self.pop_many(count).into_iter();
+        let expression = match &self.constants[index] {
+          Object::Quote(expression) => expression,
+          x => return Err(EvalError::new(format!("Expected quoted expression, found: {:?}", x))),
+        };
+        let object = quote(expression, &mut |_| Ok(values.next().unwrap()))?;
+
+        self.push(object);
+      },
+
+      Instruction::Closure(index) => {
+        let function = match &self.constants[index] {
+          Object::CompiledFunction(function) => Rc::clone(function),
+          x => return Err(EvalError::new(format!("Expected compiled function, found: {:?}", x))),
+        };
+        let scope = Rc::clone(&self.current_frame().scope);
+
+        self.push(Object::Closure(function, scope));
+      },
+      Instruction::Call(argument_count) => self.call(argument_count)?,
+      Instruction::ReturnValue => {
+        let return_value = self.pop();
+        let frame = self.frames.pop().unwrap();
+
+        if self.frames.is_empty() {
+          return Ok(Some(return_value))
+        }
+
+        self.stack.truncate(frame.base_pointer);
+        self.push(return_value);
+      },
+    }
+
+    Ok(None)
+  }
+
+  fn execute_infix(&mut self, operator: &str) -> Result<(), EvalError> {
+    let right = self.pop();
+    let left = self.pop();
+
+    self.push(eval_infix_expression(operator, left, right)?);
+    Ok(())
+  }
+
+  fn execute_prefix(&mut self, operator: &str) -> Result<(), EvalError> {
+    let right = self.pop();
+
+    self.push(eval_prefix_expression(operator, right)?);
+    Ok(())
+  }
+
+  fn get_name(&mut self, index: usize) -> Result<Object, EvalError> {
+    let frame = self.current_frame();
+    let lookup = &frame.function.lookups[index];
+    let scope = frame.scope.borrow();
+
+    for (depth, slot) in &lookup.candidates {
+      if let Some(value) = scope.get(*depth, *slot) {
+        return Ok(value)
+      }
+    }
+
+    get_builtin(&lookup.name).ok_or_else(|| EvalError::new(format!("Unknown identifier: {}", lookup.name)))
+  }
+
+  fn set_name(&mut self, index: usize, value: Object) -> Result<(), EvalError> {
+    let frame = self.current_frame();
+    let lookup = &frame.function.lookups[index];
+    let mut scope = frame.scope.borrow_mut();
+
+    for (depth, slot) in &lookup.candidates {
+      match scope.assign(*depth, *slot, value.clone()) {
+        Assignment::Assigned => return Ok(()),
+        Assignment::Constant => return Err(EvalError::new(format!("Cannot assign to constant: {}", lookup.name))),
+        Assignment::Unbound => {},
+      }
+    }
+
+    Err(EvalError::new(format!("Cannot assign to undeclared identifier: {}", lookup.name)))
+  }
+
+  fn call(&mut self, argument_count: usize) -> Result<(), EvalError> {
+    let arguments = self.pop_many(argument_count);
+    let function = self.pop();
+
+    match function {
+      Object::Closure(function, scope) => {
+        let parameters = &function.parameters;
+
+        if parameters.len() != arguments.len() {
+          return Err(EvalError::new(format!(
+            "Expected {} arguments ({}), got {}",
+            parameters.len(),
+            parameters.join(", "),
+            arguments.len()
+          )))
+        }
+
+        // Deep recursion is reported as an error instead of growing the frame stack without bounds.
+        // The first frame runs the program itself rather than a call.
+        if self.frames.len() > MAX_CALL_DEPTH {
+          return Err(EvalError::new("Maximum call depth exceeded".to_string()))
+        }
+
+        let function_scope = Scope::extend(&scope, function.slot_count);
+
+        for (slot, argument) in arguments.into_iter().enumerate() {
+          function_scope.borrow_mut().define(slot, argument);
+        }
+
+        self.frames.push(Frame {
+          function,
+          ip: 0,
+          base_pointer: self.stack.len(),
+          scope: function_scope,
+          outer_scopes: vec![],
+        });
+      },
+      Object::Builtin(_, builtin_function) => {
+        let result = builtin_function(arguments, &mut |function, arguments| self.call_function(function, arguments))?;
+        self.push(result);
+      },
+      x => return Err(EvalError::new(format!("Expected function, found: {:?}", x))),
+    }
+
+    Ok(())
+  }
+
+  // Calls a function from outside of the instruction stream and returns its result
+  fn call_function(&mut self, function: Object, arguments: Vec<Object>) -> Result<Object, EvalError> {
+    let depth = self.frames.len();
+    let argument_count = arguments.len();
+
+    self.push(function);
+    self.stack.extend(arguments);
+    self.call(argument_count)?;
+
+    // Builtins have already pushed their result, closures still have to run to their return
+    if self.frames.len() > depth {
+      self.execute(depth)
+    } else {
+      Ok(self.pop())
+    }
+  }
+
+  fn current_frame(&mut self) -> &mut Frame {
+    self.frames.last_mut().unwrap()
+  }
+
+  fn push(&mut self, object: Object) {
+    self.stack.push(object);
+  }
+
+  fn pop(&mut self) -> Object {
+    self.stack.pop().unwrap()
+  }
+
+  fn pop_many(&mut self, count: usize) -> Vec<Object> {
+    self.stack.split_off(self.stack.len() - count)
+  }
+}
@@ -0,0 +1,68 @@
+use crate::compiler::*;
+use crate::eval::eval_error::EvalError;
+use crate::lexer::Lexer;
+use crate::object::*;
+use crate::parser::Parser;
+use crate::vm::*;
+
+#[test]
+fn test_closures() -> Result<(), EvalError> {
+  let tests = vec![
+    ("let add = fn(a) { fn(b) { a + b } }; add(1)(2)", "3"),
+    ("let counter = fn(x) { if (x > 100) { return x } counter(x + 1) }; counter(0)", "101"),
+    ("let a = 1; let f = fn() { a }; let g = fn(a) { f() }; g(2)", "1"),
+    ("let f = fn() { b }; let b = 5; f()", "5"),
+    ("let f = fn(x) { if (x) { let y = 1 } y }; f(true)", "1"),
+  ];
+
+  for test in &tests {
+    let (input, expected) = test;
+
+    assert_eq!(test_run(&mut VM::new(), input)?.inspect(), *expected, "input: {}", input);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn test_errors() {
+  let tests = vec![
+    ("let f = fn(x) { if (x) { let y = 1 } y }; f(false)", "1:38: Unknown identifier: y"),
+    ("let a = 1; let a = 2;", "1:12: Identifier has already been declared: a"),
+    ("let f = fn() { f() }; f()", "1:16: Maximum call depth exceeded"),
+    ("1 + (2 + true)", "1:6: Expected integer, found: Boolean(true)"),
+  ];
+
+  for test in &tests {
+    let (input, expected) = test;
+    let error = match_or_fail!(test_run(&mut VM::new(), input), Err(m) => m);
+
+    assert_eq!(error.to_string(), *expected, "input: {}", input);
+  }
+}
+
+#[test]
+fn test_globals_survive_between_runs() -> Result<(), EvalError> {
+  let mut compiler = Compiler::new();
+  let mut vm = VM::new();
+
+  let lines = vec![("let f = fn() { x };", "Null"), ("let x = 2;", "Null"), ("f() * 2", "4")];
+
+  for (line, expected) in lines {
+    let program = match_or_fail!(Parser::new(Lexer::new(line)).parse_program(), Ok(m) => m);
+    let result = vm.run(compiler.compile(&program)?)?;
+
+    assert_eq!(result.inspect(), expected);
+  }
+
+  Ok(())
+}
+
+fn test_run(vm: &mut VM, input: &str) -> Result<Object, EvalError> {
+  let lexer = Lexer::new(input);
+  let mut parser = Parser::new(lexer);
+
+  let program = match_or_fail!(parser.parse_program(), Ok(m) => m);
+  let bytecode = Compiler::new().compile(&program)?;
+  vm.run(bytecode)
+}